@@ -0,0 +1,589 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![deny(missing_docs)]
+
+//! A local server implementing the Bonsai REST API on top of the local executor and prover.
+//!
+//! Images, inputs and receipts are stored on disk, and each proof request is executed and proven
+//! on this machine, so [bonsai_sdk] clients, including `BonsaiProver` and
+//! `cargo risczero deploy`, can be used without access to the hosted service. Point them at the
+//! server with `BONSAI_API_URL`; `BONSAI_API_KEY` must be set, but its value is ignored.
+
+mod prover;
+mod storage;
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::Result;
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use bonsai_sdk::responses::{
+    CreateSessRes, ImgUploadRes, ProofReq, Quotas, ReceiptDownload, SessionStats, SessionStatusRes,
+    SnarkReq, SnarkStatusRes, UploadRes, VersionInfo,
+};
+use risc0_zkvm::CancellationToken;
+use tokio::net::TcpListener;
+
+use self::storage::Storage;
+
+/// Options for a server.
+#[derive(Clone, Debug)]
+pub struct ServerOpts {
+    storage_dir: PathBuf,
+    dev_mode: bool,
+}
+
+impl ServerOpts {
+    /// Construct [ServerOpts] that store all images, inputs and receipts in the given directory.
+    pub fn new(storage_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            storage_dir: storage_dir.into(),
+            dev_mode: false,
+        }
+    }
+
+    /// Return [ServerOpts] with dev mode set.
+    ///
+    /// In dev mode, sessions are executed but not proven, and produce fake receipts, as with
+    /// `RISC0_DEV_MODE`. Unlike the environment variable, this only applies to the server, and
+    /// not to other provers and verifiers in the same process.
+    pub fn with_dev_mode(self, dev_mode: bool) -> Self {
+        Self { dev_mode, ..self }
+    }
+}
+
+/// Serve the Bonsai REST API on the given listener.
+///
+/// Upload and download URLs returned by the server are built from the `Host` header of each
+/// request, so clients should use the same address to reach the server as they use for
+/// `BONSAI_API_URL`.
+pub async fn serve(listener: TcpListener, opts: ServerOpts) -> Result<()> {
+    let app = router(opts)?;
+    tracing::info!("bonsai-local listening on {}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Construct a [Router] serving the Bonsai REST API.
+pub fn router(opts: ServerOpts) -> Result<Router> {
+    let state = AppState {
+        storage: Arc::new(Storage::new(opts.storage_dir)?),
+        dev_mode: opts.dev_mode,
+        sessions: Default::default(),
+        snarks: Default::default(),
+        prover_lock: Default::default(),
+    };
+
+    Ok(Router::new()
+        .route("/images/upload/:image_id", get(image_upload))
+        .route("/images/:image_id", delete(image_delete))
+        .route("/inputs/upload", get(input_upload))
+        .route("/inputs/:uuid", delete(input_delete))
+        .route("/receipts/upload", get(receipt_upload))
+        .route("/receipts/:session_id", get(receipt_download))
+        .route("/sessions/create", post(session_create))
+        .route("/sessions/status/:uuid", get(session_status))
+        .route("/sessions/logs/:uuid", get(session_logs))
+        .route("/sessions/stop/:uuid", get(session_stop))
+        .route("/sessions/exec_only_journal/:uuid", get(session_journal))
+        .route("/snark/create", post(snark_create))
+        .route("/snark/status/:uuid", get(snark_status))
+        .route("/version", get(version))
+        .route("/user/quotas", get(quotas))
+        .route("/upload/images/:image_id", put(image_put))
+        .route("/upload/inputs/:uuid", put(input_put))
+        .route("/upload/receipts/:uuid", put(receipt_put))
+        .route("/download/sessions/:uuid", get(session_receipt_get))
+        .route("/download/snarks/:uuid", get(snark_receipt_get))
+        .layer(DefaultBodyLimit::disable())
+        .with_state(state))
+}
+
+#[derive(Clone)]
+struct AppState {
+    storage: Arc<Storage>,
+    dev_mode: bool,
+    sessions: Jobs,
+    snarks: Jobs,
+    // Proving uses all of the available cores or the GPU, so proofs are run one at a time.
+    prover_lock: Arc<Mutex<()>>,
+}
+
+type Jobs = Arc<Mutex<HashMap<String, Job>>>;
+
+/// The status of a proving session or a SNARK session.
+struct Job {
+    status: &'static str,
+    state: Option<String>,
+    error_msg: Option<String>,
+    started: Instant,
+    elapsed: Option<f64>,
+    stats: Option<JobStats>,
+    cancel: CancellationToken,
+}
+
+#[derive(Clone, Copy)]
+struct JobStats {
+    segments: usize,
+    total_cycles: u64,
+    user_cycles: u64,
+}
+
+impl Job {
+    fn new() -> Self {
+        Self {
+            status: RUNNING,
+            state: Some("Setup".into()),
+            error_msg: None,
+            started: Instant::now(),
+            elapsed: None,
+            stats: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Record the outcome of the job, unless it has already been stopped.
+    fn finish(&mut self, result: Result<Option<JobStats>>) {
+        if self.status != RUNNING {
+            return;
+        }
+        match result {
+            Ok(stats) => {
+                self.status = SUCCEEDED;
+                self.stats = stats;
+            }
+            Err(err) => {
+                self.status = FAILED;
+                self.error_msg = Some(format!("{err:#}"));
+            }
+        }
+        self.state = None;
+        self.elapsed = Some(self.started.elapsed().as_secs_f64());
+    }
+
+    fn elapsed_time(&self) -> f64 {
+        self.elapsed
+            .unwrap_or_else(|| self.started.elapsed().as_secs_f64())
+    }
+}
+
+const RUNNING: &str = "RUNNING";
+const SUCCEEDED: &str = "SUCCEEDED";
+const FAILED: &str = "FAILED";
+const ABORTED: &str = "ABORTED";
+
+/// An error returned by a handler, as a status code and a plain text message.
+struct AppError(StatusCode, String);
+
+impl AppError {
+    fn not_found(what: &str, id: &str) -> Self {
+        Self(StatusCode::NOT_FOUND, format!("{what} {id} not found"))
+    }
+
+    fn bad_request(msg: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, msg.into())
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for AppError {
+    fn from(err: E) -> Self {
+        Self(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("{:#}", err.into()),
+        )
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+type HandlerResult<T> = std::result::Result<T, AppError>;
+
+/// Check that an ID given in a path is safe to use as a file name.
+fn check_id(id: &str) -> HandlerResult<&str> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::bad_request(format!("invalid id: {id}")));
+    }
+    Ok(id)
+}
+
+/// The base URL of the server, as seen by the client.
+fn base_url(headers: &HeaderMap) -> HandlerResult<String> {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .ok_or_else(|| AppError::bad_request("missing host header"))?;
+    Ok(format!("http://{host}"))
+}
+
+fn new_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+async fn image_upload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(image_id): Path<String>,
+) -> HandlerResult<Response> {
+    let image_id = check_id(&image_id)?;
+    if state.storage.image(image_id).exists() {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+    let url = format!("{}/upload/images/{image_id}", base_url(&headers)?);
+    Ok(Json(ImgUploadRes { url }).into_response())
+}
+
+async fn image_put(
+    State(state): State<AppState>,
+    Path(image_id): Path<String>,
+    body: Bytes,
+) -> HandlerResult<StatusCode> {
+    let image_id = check_id(&image_id)?;
+    let computed_id = risc0_zkvm::compute_image_id(&body)
+        .map_err(|err| AppError::bad_request(format!("invalid image: {err:#}")))?;
+    if computed_id.to_string() != image_id {
+        return Err(AppError::bad_request(format!(
+            "image id mismatch: uploaded image has id {computed_id}"
+        )));
+    }
+    tokio::fs::write(state.storage.image(image_id), &body).await?;
+    Ok(StatusCode::OK)
+}
+
+async fn image_delete(
+    State(state): State<AppState>,
+    Path(image_id): Path<String>,
+) -> HandlerResult<StatusCode> {
+    let image_id = check_id(&image_id)?;
+    remove(state.storage.image(image_id), "image", image_id).await
+}
+
+async fn input_upload(headers: HeaderMap) -> HandlerResult<Json<UploadRes>> {
+    let uuid = new_uuid();
+    let url = format!("{}/upload/inputs/{uuid}", base_url(&headers)?);
+    Ok(Json(UploadRes { url, uuid }))
+}
+
+async fn input_put(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+    body: Bytes,
+) -> HandlerResult<StatusCode> {
+    let uuid = check_id(&uuid)?;
+    tokio::fs::write(state.storage.input(uuid), &body).await?;
+    Ok(StatusCode::OK)
+}
+
+async fn input_delete(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<StatusCode> {
+    let uuid = check_id(&uuid)?;
+    remove(state.storage.input(uuid), "input", uuid).await
+}
+
+async fn receipt_upload(headers: HeaderMap) -> HandlerResult<Json<UploadRes>> {
+    let uuid = new_uuid();
+    let url = format!("{}/upload/receipts/{uuid}", base_url(&headers)?);
+    Ok(Json(UploadRes { url, uuid }))
+}
+
+async fn receipt_put(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+    body: Bytes,
+) -> HandlerResult<StatusCode> {
+    let uuid = check_id(&uuid)?;
+    tokio::fs::write(state.storage.receipt(uuid), &body).await?;
+    Ok(StatusCode::OK)
+}
+
+async fn receipt_download(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> HandlerResult<Json<ReceiptDownload>> {
+    let session_id = check_id(&session_id)?;
+    if !state.storage.session_receipt(session_id).exists() {
+        return Err(AppError::not_found("receipt for session", session_id));
+    }
+    let url = format!("{}/download/sessions/{session_id}", base_url(&headers)?);
+    Ok(Json(ReceiptDownload { url }))
+}
+
+async fn session_create(
+    State(state): State<AppState>,
+    Json(req): Json<ProofReq>,
+) -> HandlerResult<Json<CreateSessRes>> {
+    check_id(&req.img)?;
+    check_id(&req.input)?;
+    for receipt_id in req.assumptions.iter() {
+        check_id(receipt_id)?;
+    }
+    if !state.storage.image(&req.img).exists() {
+        return Err(AppError::not_found("image", &req.img));
+    }
+    if !state.storage.input(&req.input).exists() {
+        return Err(AppError::not_found("input", &req.input));
+    }
+
+    let uuid = new_uuid();
+    let job = Job::new();
+    let cancel = job.cancel.clone();
+    state.sessions.lock().unwrap().insert(uuid.clone(), job);
+
+    let session_id = uuid.clone();
+    std::thread::spawn(move || {
+        let set_state = {
+            let (sessions, session_id) = (state.sessions.clone(), session_id.clone());
+            move |job_state: String| {
+                if let Some(job) = sessions.lock().unwrap().get_mut(&session_id) {
+                    job.state = Some(job_state);
+                }
+            }
+        };
+        let result = prover::run_session(
+            &state.storage,
+            &session_id,
+            &req,
+            state.dev_mode,
+            cancel,
+            &state.prover_lock,
+            set_state,
+        );
+        if let Err(err) = &result {
+            tracing::warn!("session {session_id} failed: {err:#}");
+        }
+        if let Some(job) = state.sessions.lock().unwrap().get_mut(&session_id) {
+            job.finish(result.map(Some));
+        }
+    });
+
+    Ok(Json(CreateSessRes { uuid }))
+}
+
+async fn session_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Json<SessionStatusRes>> {
+    let uuid = check_id(&uuid)?;
+    let sessions = state.sessions.lock().unwrap();
+    let job = sessions
+        .get(uuid)
+        .ok_or_else(|| AppError::not_found("session", uuid))?;
+    let receipt_url = if job.status == SUCCEEDED && state.storage.session_receipt(uuid).exists() {
+        Some(format!("{}/download/sessions/{uuid}", base_url(&headers)?))
+    } else {
+        None
+    };
+    Ok(Json(SessionStatusRes {
+        status: job.status.into(),
+        receipt_url,
+        error_msg: job.error_msg.clone(),
+        state: job.state.clone(),
+        elapsed_time: Some(job.elapsed_time()),
+        stats: job.stats.map(|stats| SessionStats {
+            segments: stats.segments,
+            total_cycles: stats.total_cycles,
+            cycles: stats.user_cycles,
+        }),
+    }))
+}
+
+async fn session_logs(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Response> {
+    let uuid = check_id(&uuid)?;
+    read(state.storage.session_logs(uuid), "logs for session", uuid).await
+}
+
+async fn session_stop(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<StatusCode> {
+    let uuid = check_id(&uuid)?;
+    let mut sessions = state.sessions.lock().unwrap();
+    let job = sessions
+        .get_mut(uuid)
+        .ok_or_else(|| AppError::not_found("session", uuid))?;
+    if job.status == RUNNING {
+        job.cancel.cancel();
+        job.status = ABORTED;
+        job.state = None;
+        job.error_msg = Some("session was stopped".into());
+        job.elapsed = Some(job.started.elapsed().as_secs_f64());
+    }
+    Ok(StatusCode::OK)
+}
+
+async fn session_journal(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Response> {
+    let uuid = check_id(&uuid)?;
+    read(
+        state.storage.session_journal(uuid),
+        "journal for session",
+        uuid,
+    )
+    .await
+}
+
+async fn session_receipt_get(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Response> {
+    let uuid = check_id(&uuid)?;
+    read(
+        state.storage.session_receipt(uuid),
+        "receipt for session",
+        uuid,
+    )
+    .await
+}
+
+async fn snark_create(
+    State(state): State<AppState>,
+    Json(req): Json<SnarkReq>,
+) -> HandlerResult<Json<CreateSessRes>> {
+    let session_id = check_id(&req.session_id)?.to_string();
+    if !state.storage.session_receipt(&session_id).exists() {
+        return Err(AppError::not_found("receipt for session", &session_id));
+    }
+
+    let uuid = new_uuid();
+    let job = Job::new();
+    let cancel = job.cancel.clone();
+    state.snarks.lock().unwrap().insert(uuid.clone(), job);
+
+    let snark_id = uuid.clone();
+    std::thread::spawn(move || {
+        if let Some(job) = state.snarks.lock().unwrap().get_mut(&snark_id) {
+            job.state = Some("InProgress".into());
+        }
+        let result = prover::run_snark(
+            &state.storage,
+            &snark_id,
+            &session_id,
+            state.dev_mode,
+            cancel,
+            &state.prover_lock,
+        );
+        if let Err(err) = &result {
+            tracing::warn!("snark session {snark_id} failed: {err:#}");
+        }
+        if let Some(job) = state.snarks.lock().unwrap().get_mut(&snark_id) {
+            job.finish(result.map(|()| None));
+        }
+    });
+
+    Ok(Json(CreateSessRes { uuid }))
+}
+
+async fn snark_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Json<SnarkStatusRes>> {
+    let uuid = check_id(&uuid)?;
+    let snarks = state.snarks.lock().unwrap();
+    let job = snarks
+        .get(uuid)
+        .ok_or_else(|| AppError::not_found("snark session", uuid))?;
+    let output = if job.status == SUCCEEDED {
+        Some(format!("{}/download/snarks/{uuid}", base_url(&headers)?))
+    } else {
+        None
+    };
+    Ok(Json(SnarkStatusRes {
+        status: job.status.into(),
+        output,
+        error_msg: job.error_msg.clone(),
+    }))
+}
+
+async fn snark_receipt_get(
+    State(state): State<AppState>,
+    Path(uuid): Path<String>,
+) -> HandlerResult<Response> {
+    let uuid = check_id(&uuid)?;
+    read(
+        state.storage.snark_receipt(uuid),
+        "receipt for snark session",
+        uuid,
+    )
+    .await
+}
+
+async fn version() -> Json<VersionInfo> {
+    Json(VersionInfo {
+        risc0_zkvm: vec![risc0_zkvm::VERSION.to_string()],
+    })
+}
+
+// The local server has no users, so it reports quotas without limits.
+async fn quotas() -> Json<Quotas> {
+    Json(Quotas {
+        exec_cycle_limit: i64::MAX,
+        concurrent_proofs: 1,
+        cycle_budget: i64::MAX,
+        cycle_usage: 0,
+        dedicated_executor: 0,
+        dedicated_gpu: 0,
+    })
+}
+
+async fn read(path: PathBuf, what: &str, id: &str) -> HandlerResult<Response> {
+    match tokio::fs::read(path).await {
+        Ok(data) => Ok(data.into_response()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Err(AppError::not_found(what, id))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn remove(path: PathBuf, what: &str, id: &str) -> HandlerResult<StatusCode> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Err(AppError::not_found(what, id))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests;
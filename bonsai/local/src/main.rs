@@ -0,0 +1,73 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{net::SocketAddr, path::PathBuf};
+
+use anyhow::Result;
+use bonsai_local::ServerOpts;
+use clap::Parser;
+use tokio::net::TcpListener;
+use tracing_subscriber::EnvFilter;
+
+/// A Bonsai-compatible REST server backed by the local prover.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The address to listen on.
+    #[arg(long, env = "BONSAI_LOCAL_LISTEN", default_value = "127.0.0.1:8081")]
+    listen: SocketAddr,
+
+    /// The directory to store images, inputs and receipts in.
+    ///
+    /// If not given, a temporary directory is used and removed on exit.
+    #[arg(long, env = "BONSAI_LOCAL_STORAGE_DIR")]
+    storage_dir: Option<PathBuf>,
+
+    /// Execute sessions without proving them, producing fake receipts.
+    ///
+    /// Clients only accept fake receipts with RISC0_DEV_MODE set.
+    #[arg(long, env = "BONSAI_LOCAL_DEV_MODE")]
+    dev_mode: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let args = Cli::parse();
+    let tmp_dir;
+    let storage_dir = match args.storage_dir {
+        Some(storage_dir) => storage_dir,
+        None => {
+            tmp_dir = tempfile::tempdir()?;
+            tmp_dir.path().to_path_buf()
+        }
+    };
+
+    let listener = TcpListener::bind(args.listen).await?;
+    eprintln!(
+        "Set BONSAI_API_URL=http://{} to use this server",
+        listener.local_addr()?
+    );
+
+    // Return on Ctrl-C, so that the temporary storage directory is removed.
+    tokio::select! {
+        result = bonsai_local::serve(listener, ServerOpts::new(storage_dir).with_dev_mode(args.dev_mode)) => result,
+        result = tokio::signal::ctrl_c() => Ok(result?),
+    }
+}
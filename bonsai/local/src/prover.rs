@@ -0,0 +1,146 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Execution and proving of sessions, run on a thread per session.
+
+use std::{
+    fs::{self, File},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use anyhow::{Context, Result};
+use bonsai_sdk::responses::ProofReq;
+use risc0_zkvm::{
+    get_prover_server, CancellationToken, ExecutorEnv, ExecutorImpl, FakeReceipt,
+    InnerAssumptionReceipt, InnerReceipt, ProgressEvent, ProverOpts, Receipt, VerifierContext,
+};
+
+use crate::{
+    storage::{write_atomic, Storage},
+    JobStats,
+};
+
+/// Execute the given proof request and, unless it is execute-only, prove it as a succinct
+/// receipt, or produce a fake receipt in dev mode.
+///
+/// The `set_state` callback receives the proving state reported by the session status endpoint.
+pub(crate) fn run_session(
+    storage: &Storage,
+    uuid: &str,
+    req: &ProofReq,
+    dev_mode: bool,
+    cancel: CancellationToken,
+    prover_lock: &Mutex<()>,
+    set_state: impl Fn(String) + Send + Sync + 'static,
+) -> Result<JobStats> {
+    fs::create_dir_all(storage.session_dir(uuid))?;
+    let elf = fs::read(storage.image(&req.img)).context("failed to read image")?;
+    let input = fs::read(storage.input(&req.input)).context("failed to read input")?;
+    let logs = File::create(storage.session_logs(uuid))?;
+
+    let mut env = ExecutorEnv::builder();
+    env.write_slice(input.as_slice())
+        .stdout(logs.try_clone()?)
+        .stderr(logs)
+        .session_limit(req.exec_cycle_limit);
+    for receipt_id in req.assumptions.iter() {
+        let data = fs::read(storage.receipt(receipt_id))
+            .with_context(|| format!("failed to read receipt {receipt_id}"))?;
+        let receipt: InnerAssumptionReceipt = bincode::deserialize(&data)
+            .with_context(|| format!("failed to decode receipt {receipt_id}"))?;
+        env.add_assumption(receipt);
+    }
+    let env = env.build()?;
+
+    set_state("Executor".into());
+    let session = ExecutorImpl::from_elf(env, &elf)?.run()?;
+    let stats = JobStats {
+        segments: session.segments.len(),
+        total_cycles: session.total_cycles,
+        user_cycles: session.user_cycles,
+    };
+    if let Some(journal) = &session.journal {
+        write_atomic(&storage.session_journal(uuid), &journal.bytes)?;
+    }
+    if req.execute_only {
+        return Ok(stats);
+    }
+    if dev_mode {
+        let receipt = Receipt::new(
+            InnerReceipt::Fake(FakeReceipt::new(session.claim()?)),
+            session.journal.unwrap_or_default().bytes,
+        );
+        write_atomic(
+            &storage.session_receipt(uuid),
+            &bincode::serialize(&receipt)?,
+        )?;
+        return Ok(stats);
+    }
+
+    let num_segments = stats.segments;
+    set_state(format!("ProveSegments: 0/{num_segments}"));
+    let _guard = prover_lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let segments_proved = AtomicUsize::new(0);
+    let opts = ProverOpts::succinct()
+        .with_cancellation_token(cancel)
+        .with_progress(move |event| match event {
+            ProgressEvent::SegmentProved { .. } => {
+                let proved = segments_proved.fetch_add(1, Ordering::Relaxed) + 1;
+                set_state(format!("ProveSegments: {proved}/{num_segments}"));
+            }
+            ProgressEvent::LiftDone { .. } | ProgressEvent::JoinDone => {
+                set_state("Recursion".into())
+            }
+            ProgressEvent::ResolveDone => set_state("Resolve".into()),
+            ProgressEvent::ReceiptProduced { .. } => set_state("Finalize".into()),
+            _ => {}
+        });
+    let receipt = get_prover_server(&opts)?
+        .prove_session(&VerifierContext::default(), &session)?
+        .receipt;
+    write_atomic(
+        &storage.session_receipt(uuid),
+        &bincode::serialize(&receipt)?,
+    )?;
+
+    Ok(stats)
+}
+
+/// Compress the succinct receipt of the given session to a Groth16 receipt.
+///
+/// In dev mode, the fake receipt of the session is returned as is.
+pub(crate) fn run_snark(
+    storage: &Storage,
+    uuid: &str,
+    session_id: &str,
+    dev_mode: bool,
+    cancel: CancellationToken,
+    prover_lock: &Mutex<()>,
+) -> Result<()> {
+    let data = fs::read(storage.session_receipt(session_id))
+        .with_context(|| format!("failed to read receipt for session {session_id}"))?;
+    let receipt: Receipt = bincode::deserialize(&data)?;
+    if dev_mode && matches!(receipt.inner, InnerReceipt::Fake(_)) {
+        return write_atomic(&storage.snark_receipt(uuid), &data);
+    }
+
+    let _guard = prover_lock.lock().unwrap_or_else(PoisonError::into_inner);
+    let opts = ProverOpts::groth16().with_cancellation_token(cancel);
+    let receipt = get_prover_server(&opts)?.compress(&opts, &receipt)?;
+    write_atomic(&storage.snark_receipt(uuid), &bincode::serialize(&receipt)?)
+}
@@ -0,0 +1,84 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// The on-disk layout of the server's storage directory.
+///
+/// All IDs must be checked to be safe to use as file names before they are passed here.
+pub(crate) struct Storage {
+    root: PathBuf,
+}
+
+const DIRS: &[&str] = &["images", "inputs", "receipts", "sessions", "snarks"];
+
+impl Storage {
+    pub(crate) fn new(root: PathBuf) -> Result<Self> {
+        for dir in DIRS {
+            let path = root.join(dir);
+            std::fs::create_dir_all(&path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+        }
+        Ok(Self { root })
+    }
+
+    /// The ELF binary with the given image ID.
+    pub(crate) fn image(&self, image_id: &str) -> PathBuf {
+        self.root.join("images").join(image_id)
+    }
+
+    /// The uploaded input with the given UUID.
+    pub(crate) fn input(&self, uuid: &str) -> PathBuf {
+        self.root.join("inputs").join(uuid)
+    }
+
+    /// The uploaded assumption receipt with the given UUID.
+    pub(crate) fn receipt(&self, uuid: &str) -> PathBuf {
+        self.root.join("receipts").join(uuid)
+    }
+
+    /// The directory holding the outputs of the session with the given UUID.
+    pub(crate) fn session_dir(&self, uuid: &str) -> PathBuf {
+        self.root.join("sessions").join(uuid)
+    }
+
+    pub(crate) fn session_receipt(&self, uuid: &str) -> PathBuf {
+        self.session_dir(uuid).join("receipt.bin")
+    }
+
+    pub(crate) fn session_journal(&self, uuid: &str) -> PathBuf {
+        self.session_dir(uuid).join("journal.bin")
+    }
+
+    pub(crate) fn session_logs(&self, uuid: &str) -> PathBuf {
+        self.session_dir(uuid).join("logs.txt")
+    }
+
+    /// The Groth16 receipt of the SNARK session with the given UUID.
+    pub(crate) fn snark_receipt(&self, uuid: &str) -> PathBuf {
+        self.root.join("snarks").join(uuid)
+    }
+}
+
+/// Write the given data to a file atomically, so that a partially written file is never served.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, data)
+        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use bonsai_sdk::{
+    blocking::{Client, SessionId},
+    responses::SessionStatusRes,
+};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    BonsaiProver, ExecutorEnv, InnerReceipt, Prover, ProverOpts, Receipt, ReceiptClaim,
+    VerifierContext, VERSION,
+};
+use risc0_zkvm_methods::{
+    multi_test::MultiTestSpec, HELLO_COMMIT_ELF, HELLO_COMMIT_ID, MULTI_TEST_ELF,
+};
+use tempfile::{tempdir, TempDir};
+use tokio::net::TcpListener;
+
+use crate::ServerOpts;
+
+/// Start a server on a free port, returning its URL and storage directory.
+fn spawn_server() -> (String, TempDir) {
+    spawn_server_with_dev_mode(false)
+}
+
+fn spawn_server_with_dev_mode(dev_mode: bool) -> (String, TempDir) {
+    let storage_dir = tempdir().unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let path = storage_dir.path().to_path_buf();
+    std::thread::spawn(move || {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(async move {
+                let listener = TcpListener::from_std(listener).unwrap();
+                let opts = ServerOpts::new(path).with_dev_mode(dev_mode);
+                crate::serve(listener, opts).await.unwrap();
+            })
+    });
+    (url, storage_dir)
+}
+
+/// Wait for the given session to finish, returning its status.
+fn wait_for_session(client: &Client, session: &SessionId) -> SessionStatusRes {
+    loop {
+        let status = session.status(client).unwrap();
+        if status.status != "RUNNING" {
+            return status;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn execute_only() {
+    let (url, _storage_dir) = spawn_server();
+    let client = Client::from_parts(url, "key".into(), VERSION).unwrap();
+
+    let image_id = Digest::from(HELLO_COMMIT_ID).to_string();
+    assert!(!client
+        .upload_img(&image_id, HELLO_COMMIT_ELF.to_vec())
+        .unwrap());
+    assert!(client
+        .upload_img(&image_id, HELLO_COMMIT_ELF.to_vec())
+        .unwrap());
+    let input_id = client.upload_input(vec![]).unwrap();
+
+    let session = client
+        .create_session(image_id, input_id, vec![], true)
+        .unwrap();
+    let status = wait_for_session(&client, &session);
+    assert_eq!(status.status, "SUCCEEDED", "{:?}", status.error_msg);
+    assert!(status.stats.unwrap().total_cycles > 0);
+    assert!(status.receipt_url.is_none());
+    assert_eq!(
+        session.exec_only_journal(&client).unwrap(),
+        b"hello world".to_vec()
+    );
+}
+
+#[test]
+fn image_id_mismatch() {
+    let (url, _storage_dir) = spawn_server();
+    let client = Client::from_parts(url, "key".into(), VERSION).unwrap();
+
+    let image_id = Digest::ZERO.to_string();
+    assert!(client
+        .upload_img(&image_id, HELLO_COMMIT_ELF.to_vec())
+        .is_err());
+}
+
+#[test]
+fn bonsai_prover() {
+    let (url, _storage_dir) = spawn_server();
+    temp_env::with_vars(
+        [
+            ("BONSAI_API_URL", Some(url.as_str())),
+            ("BONSAI_API_KEY", Some("key")),
+            ("BONSAI_POLL_INTERVAL_MS", Some("10")),
+        ],
+        || {
+            let env = ExecutorEnv::default();
+            let receipt = BonsaiProver::new("bonsai-local")
+                .prove(env, HELLO_COMMIT_ELF)
+                .unwrap()
+                .receipt;
+            receipt.verify(HELLO_COMMIT_ID).unwrap();
+            assert_eq!(receipt.journal.bytes, b"hello world".to_vec());
+        },
+    );
+}
+
+#[test]
+fn bonsai_prover_failed_session() {
+    let (url, _storage_dir) = spawn_server();
+    temp_env::with_vars(
+        [
+            ("BONSAI_API_URL", Some(url.as_str())),
+            ("BONSAI_API_KEY", Some("key")),
+            ("BONSAI_POLL_INTERVAL_MS", Some("10")),
+        ],
+        || {
+            let env = ExecutorEnv::builder()
+                .write(&MultiTestSpec::Panic)
+                .unwrap()
+                .build()
+                .unwrap();
+            let err = BonsaiProver::new("bonsai-local")
+                .prove(env, MULTI_TEST_ELF)
+                .unwrap_err();
+            let err = format!("{err:#}");
+            assert!(err.contains("exited: FAILED"), "{err}");
+            // Resuming a failed session can't succeed, so it isn't suggested.
+            assert!(!err.contains("resume_session"), "{err}");
+        },
+    );
+}
+
+#[test]
+fn dev_mode() {
+    let (url, _storage_dir) = spawn_server_with_dev_mode(true);
+    let client = Client::from_parts(url, "key".into(), VERSION).unwrap();
+
+    let image_id = Digest::from(HELLO_COMMIT_ID).to_string();
+    client
+        .upload_img(&image_id, HELLO_COMMIT_ELF.to_vec())
+        .unwrap();
+    let input_id = client.upload_input(vec![]).unwrap();
+    let session = client
+        .create_session(image_id, input_id, vec![], false)
+        .unwrap();
+    let status = wait_for_session(&client, &session);
+    assert_eq!(status.status, "SUCCEEDED", "{:?}", status.error_msg);
+
+    let receipt: Receipt =
+        bincode::deserialize(&client.download(&status.receipt_url.unwrap()).unwrap()).unwrap();
+    assert!(matches!(receipt.inner, InnerReceipt::Fake(_)));
+    assert_eq!(receipt.journal.bytes, b"hello world".to_vec());
+    assert_eq!(
+        receipt.claim().unwrap().digest(),
+        ReceiptClaim::ok(HELLO_COMMIT_ID, b"hello world".to_vec()).digest()
+    );
+    // Dev mode only applies to the server, so the fake receipt is rejected here.
+    assert!(receipt.verify(HELLO_COMMIT_ID).is_err());
+}
+
+#[test]
+fn resume_session() {
+    let (url, _storage_dir) = spawn_server();
+    temp_env::with_vars(
+        [
+            ("BONSAI_API_URL", Some(url.as_str())),
+            ("BONSAI_API_KEY", Some("key")),
+            ("BONSAI_POLL_INTERVAL_MS", Some("10")),
+        ],
+        || {
+            let client = Client::from_env(VERSION).unwrap();
+            let image_id = Digest::from(HELLO_COMMIT_ID).to_string();
+            client
+                .upload_img(&image_id, HELLO_COMMIT_ELF.to_vec())
+                .unwrap();
+            let input_id = client.upload_input(vec![]).unwrap();
+            let session = client
+                .create_session(image_id, input_id, vec![], false)
+                .unwrap();
+
+            let receipt = BonsaiProver::new("bonsai-local")
+                .resume_session(
+                    &session.uuid,
+                    &VerifierContext::default(),
+                    HELLO_COMMIT_ID,
+                    &ProverOpts::default(),
+                )
+                .unwrap()
+                .receipt;
+            assert_eq!(receipt.journal.bytes, b"hello world".to_vec());
+        },
+    );
+}
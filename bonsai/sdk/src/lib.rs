@@ -145,7 +145,7 @@ use self::responses::{
 use duplicate::duplicate_item;
 use reqwest::header;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{path::Path, time::Duration};
 use thiserror::Error;
 
 /// HTTP header key for the API key
@@ -183,11 +183,103 @@ pub enum SdkErr {
     ReceiptNotFound,
 }
 
+/// Policy for retrying idempotent requests that fail with a transient error.
+///
+/// Requests that only read state, such as polling the status of a session, and uploads to a
+/// presigned URL are retried with exponential backoff when the connection fails or the server
+/// responds with a 5xx or 429 status. Requests that create a session are never retried, since a
+/// retry could start the session twice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following retry
+    pub initial_backoff: Duration,
+    /// Maximum delay between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A [RetryPolicy] that never retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// The delay before the given retry, counting from zero.
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << retry.min(31))
+            .min(self.max_backoff)
+    }
+
+    /// The delay before retrying a request, given the number of retries so far and the status or
+    /// error of the last attempt, or [None] if the request should not be retried.
+    fn delay(
+        &self,
+        retries: u32,
+        outcome: Result<reqwest::StatusCode, &reqwest::Error>,
+    ) -> Option<Duration> {
+        if retries >= self.max_retries {
+            return None;
+        }
+        let transient = match outcome {
+            Ok(status) => {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(err) => err.is_connect() || err.is_timeout() || err.is_request() || err.is_body(),
+        };
+        transient.then(|| self.backoff(retries))
+    }
+}
+
 enum ImageExistsOpt {
     Exists,
     New(ImgUploadRes),
 }
 
+/// Parse the `Range: bytes=0-<last>` header of a `308 Resume Incomplete` response into the
+/// number of bytes the server has stored.
+fn parse_upload_range(range: &header::HeaderValue) -> Option<u64> {
+    let last = range.to_str().ok()?.strip_prefix("bytes=0-")?;
+    last.parse::<u64>().ok()?.checked_add(1)
+}
+
+/// Open a file as a request body, starting at the given offset.
+#[cfg(feature = "non_blocking")]
+async fn non_blocking_file_body(path: &Path, offset: u64) -> Result<reqwest::Body, SdkErr> {
+    use tokio::io::AsyncSeekExt;
+
+    let mut fd = tokio::fs::File::open(path).await?;
+    fd.seek(std::io::SeekFrom::Start(offset)).await?;
+    Ok(fd.into())
+}
+
+/// Open a file as a request body, starting at the given offset.
+fn blocking_file_body(path: &Path, offset: u64) -> Result<reqwest::blocking::Body, SdkErr> {
+    use std::io::Seek;
+
+    let mut fd = std::fs::File::open(path)?;
+    let len = fd.metadata()?.len();
+    fd.seek(std::io::SeekFrom::Start(offset))?;
+    Ok(reqwest::blocking::Body::sized(
+        fd,
+        len.saturating_sub(offset),
+    ))
+}
+
 /// Collection of serialization object for the REST api
 pub mod responses {
     use serde::{Deserialize, Serialize};
@@ -347,15 +439,23 @@ duplicate_item(
         module_type      [non_blocking]
         maybe_async_attr [maybe_async::must_be_async]
         File             [tokio::fs::File]
+        file_body        [non_blocking_file_body]
         HttpBody         [reqwest::Body]
         HttpClient       [reqwest::Client]
+        HttpRequest      [reqwest::RequestBuilder]
+        HttpResponse     [reqwest::Response]
+        sleep            [tokio::time::sleep]
     ]
     [
         module_type      [blocking]
         maybe_async_attr [maybe_async::must_be_sync]
         File             [std::fs::File]
+        file_body        [blocking_file_body]
         HttpBody         [reqwest::blocking::Body]
         HttpClient       [reqwest::blocking::Client]
+        HttpRequest      [reqwest::blocking::RequestBuilder]
+        HttpResponse     [reqwest::blocking::Response]
+        sleep            [std::thread::sleep]
     ]
 ))]
 #[cfg_attr(not(feature = "non_blocking"),
@@ -364,8 +464,12 @@ duplicate_item(
         module_type      [blocking]
         maybe_async_attr [maybe_async::must_be_sync]
         File             [std::fs::File]
+        file_body        [blocking_file_body]
         HttpBody         [reqwest::blocking::Body]
         HttpClient       [reqwest::blocking::Client]
+        HttpRequest      [reqwest::blocking::RequestBuilder]
+        HttpResponse     [reqwest::blocking::Response]
+        sleep            [std::thread::sleep]
     ]
 ))]
 /// Client module async/async
@@ -377,6 +481,7 @@ pub mod module_type {
     pub struct Client {
         pub(crate) url: String,
         pub(crate) client: HttpClient,
+        pub(crate) retry: RetryPolicy,
     }
 
     /// Proof Session representation
@@ -396,7 +501,7 @@ pub mod module_type {
         #[maybe_async_attr]
         pub async fn status(&self, client: &Client) -> Result<SessionStatusRes, SdkErr> {
             let url = format!("{}/sessions/status/{}", client.url, self.uuid);
-            let res = client.client.get(url).send().await?;
+            let res = client.send_idempotent(client.client.get(url)).await?;
 
             if !res.status().is_success() {
                 let body = res.text().await?;
@@ -416,7 +521,7 @@ pub mod module_type {
         #[maybe_async_attr]
         pub async fn logs(&self, client: &Client) -> Result<String, SdkErr> {
             let url = format!("{}/sessions/logs/{}", client.url, self.uuid);
-            let res = client.client.get(url).send().await?;
+            let res = client.send_idempotent(client.client.get(url)).await?;
 
             if !res.status().is_success() {
                 let body = res.text().await?;
@@ -429,7 +534,7 @@ pub mod module_type {
         #[maybe_async_attr]
         pub async fn stop(&self, client: &Client) -> Result<(), SdkErr> {
             let url = format!("{}/sessions/stop/{}", client.url, self.uuid);
-            let res = client.client.get(url).send().await?;
+            let res = client.send_idempotent(client.client.get(url)).await?;
             if !res.status().is_success() {
                 let body = res.text().await?;
                 return Err(SdkErr::InternalServerErr(body));
@@ -444,7 +549,7 @@ pub mod module_type {
         #[maybe_async_attr]
         pub async fn exec_only_journal(&self, client: &Client) -> Result<Vec<u8>, SdkErr> {
             let url = format!("{}/sessions/exec_only_journal/{}", client.url, self.uuid);
-            let res = client.client.get(url).send().await?;
+            let res = client.send_idempotent(client.client.get(url)).await?;
 
             if !res.status().is_success() {
                 let body = res.text().await?;
@@ -471,7 +576,7 @@ pub mod module_type {
         #[maybe_async_attr]
         pub async fn status(&self, client: &Client) -> Result<SnarkStatusRes, SdkErr> {
             let url = format!("{}/snark/status/{}", client.url, self.uuid);
-            let res = client.client.get(url).send().await?;
+            let res = client.send_idempotent(client.client.get(url)).await?;
 
             if !res.status().is_success() {
                 let body = res.text().await?;
@@ -522,7 +627,11 @@ bonsai_sdk::non_blocking::Client::from_parts(url, api_key, risc0_zkvm::VERSION)
         pub fn from_parts(url: String, key: String, risc0_version: &str) -> Result<Self, SdkErr> {
             let client = construct_req_client(&key, risc0_version)?;
             let url = url.strip_suffix('/').unwrap_or(&url).to_string();
-            Ok(Self { url, client })
+            Ok(Self {
+                url,
+                client,
+                retry: RetryPolicy::default(),
+            })
         }
 
         /// Construct a [Client] from env vars
@@ -562,15 +671,45 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
             Ok(Self {
                 url: api_url.to_string(),
                 client,
+                retry: RetryPolicy::default(),
             })
         }
 
+        /// Return this client with the given [RetryPolicy] for idempotent requests
+        pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+            Self { retry, ..self }
+        }
+
+        /// Send an idempotent request, retrying it according to the [RetryPolicy] of the client
+        #[maybe_async_attr]
+        async fn send_idempotent(&self, req: HttpRequest) -> Result<HttpResponse, SdkErr> {
+            let mut retries = 0;
+            loop {
+                // Requests with a streaming body cannot be cloned, and so cannot be retried.
+                let Some(attempt) = req.try_clone() else {
+                    return Ok(req.send().await?);
+                };
+                let res = attempt.send().await;
+                match self
+                    .retry
+                    .delay(retries, res.as_ref().map(|res| res.status()))
+                {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        retries += 1;
+                    }
+                    None => return Ok(res?),
+                }
+            }
+        }
+
         #[maybe_async_attr]
         async fn get_image_upload_url(&self, image_id: &str) -> Result<ImageExistsOpt, SdkErr> {
             let res = self
-                .client
-                .get(format!("{}/images/upload/{}", self.url, image_id))
-                .send()
+                .send_idempotent(
+                    self.client
+                        .get(format!("{}/images/upload/{}", self.url, image_id)),
+                )
                 .await?;
 
             if res.status() == 204 {
@@ -588,7 +727,9 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         /// Upload body to a given URL
         #[maybe_async_attr]
         async fn put_data<T: Into<HttpBody>>(&self, url: &str, body: T) -> Result<(), SdkErr> {
-            let res = self.client.put(url).body(body).send().await?;
+            let res = self
+                .send_idempotent(self.client.put(url).body(body))
+                .await?;
             if !res.status().is_success() {
                 let body = res.text().await?;
                 return Err(SdkErr::InternalServerErr(body));
@@ -597,6 +738,71 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
             Ok(())
         }
 
+        /// Upload a file to a presigned URL
+        ///
+        /// When an attempt fails, the upload resumes from the last offset acknowledged by the
+        /// server, or restarts from the beginning if the server doesn't report one. All attempts
+        /// share the retry budget of the [RetryPolicy], and only the final response decides
+        /// whether the upload succeeded.
+        #[maybe_async_attr]
+        async fn put_file(&self, url: &str, path: &Path) -> Result<(), SdkErr> {
+            let len = File::open(path).await?.metadata().await?.len();
+            let mut offset = 0;
+            let mut retries = 0;
+            let res = loop {
+                let mut req = self.client.put(url);
+                if offset > 0 {
+                    req = req.header(
+                        header::CONTENT_RANGE,
+                        format!("bytes {offset}-{}/{len}", len - 1),
+                    );
+                }
+                let res = req.body(file_body(path, offset).await?).send().await;
+                match self
+                    .retry
+                    .delay(retries, res.as_ref().map(|res| res.status()))
+                {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        retries += 1;
+                    }
+                    None => break res?,
+                }
+                offset = match self.upload_status(url, len).await {
+                    Some(stored) if stored < len => stored,
+                    _ => 0,
+                };
+            };
+            if !res.status().is_success() {
+                let body = res.text().await?;
+                return Err(SdkErr::InternalServerErr(body));
+            }
+
+            Ok(())
+        }
+
+        /// Query how many bytes of an interrupted upload the server has stored, with an empty
+        /// `Content-Range: bytes */<len>` request
+        ///
+        /// Returns [None] unless the server answers `308 Resume Incomplete` with a `Range`
+        /// header. Any other response, including a success, may come from a server that doesn't
+        /// support resuming uploads, e.g. one that stored the empty body as the whole file.
+        #[maybe_async_attr]
+        async fn upload_status(&self, url: &str, len: u64) -> Option<u64> {
+            let res = self
+                .client
+                .put(url)
+                .header(header::CONTENT_RANGE, format!("bytes */{len}"))
+                .body(Vec::new())
+                .send()
+                .await
+                .ok()?;
+            if res.status() != reqwest::StatusCode::PERMANENT_REDIRECT {
+                return None;
+            }
+            parse_upload_range(res.headers().get(header::RANGE)?)
+        }
+
         /// Upload a image buffer to the /images/ route
         ///
         /// The boolean return indicates if the image already exists in bonsai
@@ -629,8 +835,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
             match res_or_exists {
                 ImageExistsOpt::Exists => Ok(true),
                 ImageExistsOpt::New(upload_res) => {
-                    let fd = File::open(path).await?;
-                    self.put_data(&upload_res.url, fd).await?;
+                    self.put_file(&upload_res.url, path).await?;
                     Ok(false)
                 }
             }
@@ -640,9 +845,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         #[maybe_async_attr]
         async fn get_upload_url(&self, route: &str) -> Result<UploadRes, SdkErr> {
             let res = self
-                .client
-                .get(format!("{}/{}/upload", self.url, route))
-                .send()
+                .send_idempotent(self.client.get(format!("{}/{}/upload", self.url, route)))
                 .await?;
 
             if !res.status().is_success() {
@@ -666,8 +869,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         pub async fn upload_input_file(&self, path: &Path) -> Result<String, SdkErr> {
             let upload_data = self.get_upload_url("inputs").await?;
 
-            let fd = File::open(path).await?;
-            self.put_data(&upload_data.url, fd).await?;
+            self.put_file(&upload_data.url, path).await?;
 
             Ok(upload_data.uuid)
         }
@@ -685,8 +887,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         pub async fn upload_receipt_file(&self, path: &Path) -> Result<String, SdkErr> {
             let upload_data = self.get_upload_url("receipts").await?;
 
-            let fd = File::open(path).await?;
-            self.put_data(&upload_data.url, fd).await?;
+            self.put_file(&upload_data.url, path).await?;
 
             Ok(upload_data.uuid)
         }
@@ -697,9 +898,10 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         #[maybe_async_attr]
         pub async fn receipt_download(&self, session_id: &SessionId) -> Result<Vec<u8>, SdkErr> {
             let res = self
-                .client
-                .get(format!("{}/receipts/{}", self.url, session_id.uuid))
-                .send()
+                .send_idempotent(
+                    self.client
+                        .get(format!("{}/receipts/{}", self.url, session_id.uuid)),
+                )
                 .await?;
 
             if !res.status().is_success() {
@@ -812,9 +1014,32 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         /// Useful to download a [SessionId] receipt_url
         #[maybe_async_attr]
         pub async fn download(&self, url: &str) -> Result<Vec<u8>, SdkErr> {
-            let data = self.client.get(url).send().await?.bytes().await?;
-
-            Ok(data.into())
+            // A connection dropped while reading the body is retried from the same budget as the
+            // request itself.
+            let mut retries = 0;
+            loop {
+                let err = match self.client.get(url).send().await {
+                    Ok(res) => {
+                        if let Some(delay) = self.retry.delay(retries, Ok(res.status())) {
+                            sleep(delay).await;
+                            retries += 1;
+                            continue;
+                        }
+                        match res.bytes().await {
+                            Ok(data) => return Ok(data.into()),
+                            Err(err) => err,
+                        }
+                    }
+                    Err(err) => err,
+                };
+                match self.retry.delay(retries, Err(&err)) {
+                    Some(delay) => {
+                        sleep(delay).await;
+                        retries += 1;
+                    }
+                    None => return Err(err.into()),
+                }
+            }
         }
 
         // - /snark
@@ -851,9 +1076,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         #[maybe_async_attr]
         pub async fn version(&self) -> Result<VersionInfo, SdkErr> {
             Ok(self
-                .client
-                .get(format!("{}/version", self.url))
-                .send()
+                .send_idempotent(self.client.get(format!("{}/version", self.url)))
                 .await?
                 .json::<VersionInfo>()
                 .await?)
@@ -867,9 +1090,7 @@ bonsai_sdk::non_blocking::Client::from_env(risc0_zkvm::VERSION)
         #[maybe_async_attr]
         pub async fn quotas(&self) -> Result<Quotas, SdkErr> {
             Ok(self
-                .client
-                .get(format!("{}/user/quotas", self.url))
-                .send()
+                .send_idempotent(self.client.get(format!("{}/user/quotas", self.url)))
                 .await?
                 .json::<Quotas>()
                 .await?)
@@ -1475,4 +1696,201 @@ mod tests {
 
         get_mock.assert();
     }
+
+    #[test]
+    fn retry_backoff() {
+        let retry = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        assert_eq!(retry.backoff(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff(2), Duration::from_millis(400));
+        assert_eq!(retry.backoff(4), Duration::from_secs(1));
+        assert_eq!(retry.backoff(40), Duration::from_secs(1));
+
+        let unavailable = Ok(reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            retry.delay(0, unavailable),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(retry.delay(10, unavailable), None);
+        assert_eq!(retry.delay(0, Ok(reqwest::StatusCode::NOT_FOUND)), None);
+        assert_eq!(RetryPolicy::none().delay(0, unavailable), None);
+    }
+
+    #[test]
+    fn retry_idempotent_requests() {
+        let server = MockServer::start();
+        let uuid = Uuid::new_v4().to_string();
+
+        let status_mock = server.mock(|when, then| {
+            when.method(GET).path(format!("/sessions/status/{uuid}"));
+            then.status(503);
+        });
+        let create_mock = server.mock(|when, then| {
+            when.method(POST).path("/sessions/create");
+            then.status(503);
+        });
+
+        let server_url = format!("http://{}", server.address());
+        let client = Client::from_parts(server_url, TEST_KEY.to_string(), TEST_VERSION)
+            .expect("Failed to construct client")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            });
+
+        // Polling the status is retried, but creating a session is not.
+        assert!(SessionId::new(uuid).status(&client).is_err());
+        status_mock.assert_hits(3);
+        assert!(client
+            .create_session(TEST_ID.into(), TEST_ID.into(), vec![], false)
+            .is_err());
+        create_mock.assert_hits(1);
+    }
+
+    #[test]
+    fn retry_download_once() {
+        let server = MockServer::start();
+        let download_mock = server.mock(|when, then| {
+            when.method(GET).path("/receipt");
+            then.status(503);
+        });
+
+        let server_url = format!("http://{}", server.address());
+        let client = Client::from_parts(server_url.clone(), TEST_KEY.to_string(), TEST_VERSION)
+            .expect("Failed to construct client")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            });
+
+        // A server error is returned as the body once the retries are spent.
+        client
+            .download(&format!("{server_url}/receipt"))
+            .expect("Failed to download");
+        download_mock.assert_hits(3);
+    }
+
+    #[test]
+    fn parse_upload_ranges() {
+        let range = |value: &str| header::HeaderValue::from_str(value).unwrap();
+        assert_eq!(parse_upload_range(&range("bytes=0-3")), Some(4));
+        assert_eq!(parse_upload_range(&range("bytes=2-3")), None);
+        assert_eq!(parse_upload_range(&range("bytes=0-")), None);
+    }
+
+    #[test]
+    fn resume_file_upload() {
+        let server = MockServer::start();
+        let input_uuid = Uuid::new_v4();
+        let upload_path = format!("/upload/{input_uuid}");
+        let response = UploadRes {
+            url: format!("http://{}{upload_path}", server.address()),
+            uuid: input_uuid.to_string(),
+        };
+
+        let get_mock = server.mock(|when, then| {
+            when.method(GET).path("/inputs/upload");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body_obj(&response);
+        });
+        // The first attempt fails after the server has stored the first 4 bytes.
+        let put_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path(&upload_path)
+                .header_missing("content-range");
+            then.status(503);
+        });
+        let status_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path(&upload_path)
+                .header("content-range", "bytes */10");
+            then.status(308).header("range", "bytes=0-3");
+        });
+        let resume_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path(&upload_path)
+                .header("content-range", "bytes 4-9/10")
+                .body("456789");
+            then.status(200);
+        });
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "0123456789").unwrap();
+
+        let server_url = format!("http://{}", server.address());
+        let client = Client::from_parts(server_url, TEST_KEY.to_string(), TEST_VERSION)
+            .expect("Failed to construct client")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            });
+        let res = client
+            .upload_input_file(file.path())
+            .expect("Failed to upload input");
+
+        assert_eq!(res, response.uuid);
+        get_mock.assert();
+        put_mock.assert();
+        status_mock.assert();
+        resume_mock.assert();
+    }
+
+    #[test]
+    fn restart_file_upload_after_successful_probe() {
+        let server = MockServer::start();
+        let input_uuid = Uuid::new_v4();
+        let upload_path = format!("/upload/{input_uuid}");
+        let response = UploadRes {
+            url: format!("http://{}{upload_path}", server.address()),
+            uuid: input_uuid.to_string(),
+        };
+
+        let get_mock = server.mock(|when, then| {
+            when.method(GET).path("/inputs/upload");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body_obj(&response);
+        });
+        let put_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path(&upload_path)
+                .header_missing("content-range")
+                .body("0123456789");
+            then.status(503);
+        });
+        // A server that doesn't support resuming uploads may accept the empty probe.
+        let status_mock = server.mock(|when, then| {
+            when.method(PUT)
+                .path(&upload_path)
+                .header("content-range", "bytes */10");
+            then.status(200);
+        });
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "0123456789").unwrap();
+
+        let server_url = format!("http://{}", server.address());
+        let client = Client::from_parts(server_url, TEST_KEY.to_string(), TEST_VERSION)
+            .expect("Failed to construct client")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 1,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            });
+        client
+            .upload_input_file(file.path())
+            .expect_err("upload should fail");
+
+        // The whole file is sent again rather than treating the probe as a finished upload.
+        get_mock.assert();
+        put_mock.assert_hits(2);
+        status_mock.assert();
+    }
 }
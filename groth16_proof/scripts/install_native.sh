@@ -0,0 +1,23 @@
+#!/bin/bash
+
+set -eoux
+
+# Installs the artifacts used by the native prover of the `risc0-groth16` crate, i.e. with the
+# `prove-native` feature, into $RISC0_GROTH16_DIR, or ~/.risc0/groth16 by default.
+#
+# We presume circom is installed system wide, at the revision used by docker/prover.Dockerfile.
+# This script is meant to be run via
+# scripts/install_native.sh from the `groth16_proof` folder
+
+ZKEY_URL=https://risc0-artifacts.s3.us-west-2.amazonaws.com/zkey/2024-05-17.1/stark_verify_final.zkey.gz
+OUT_DIR=${RISC0_GROTH16_DIR:-$HOME/.risc0/groth16}
+
+mkdir -p $OUT_DIR
+
+# Download the proving key, which is the same one used by the prover image
+wget $ZKEY_URL -O $OUT_DIR/stark_verify_final.zkey.gz
+gzip -df $OUT_DIR/stark_verify_final.zkey.gz
+
+# Build the witness calculator
+(cd groth16; circom --wasm stark_verify.circom)
+cp groth16/stark_verify_js/stark_verify.wasm $OUT_DIR/stark_verify.wasm
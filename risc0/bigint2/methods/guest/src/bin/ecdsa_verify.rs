@@ -0,0 +1,109 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use risc0_bigint2::ec::{ecdsa, AffinePoint, Secp256k1Curve, Secp256r1Curve, Secp384r1Curve};
+#[allow(unused)]
+use risc0_zkvm::guest::env;
+
+// SHA-256 of "risc0 bigint2 ecdsa"
+const PREHASH: [u8; 32] = [
+    0x2f, 0x99, 0x27, 0xd3, 0x2c, 0xdc, 0xd3, 0x55, 0x80, 0xb0, 0x5e, 0x1e, 0x76, 0xf9, 0x38, 0xbf,
+    0x9e, 0xfa, 0x85, 0xff, 0xa3, 0xec, 0x12, 0x16, 0x38, 0xb4, 0xf8, 0x89, 0xc9, 0x19, 0xd7, 0x7e,
+];
+
+// SHA-384 of "risc0 bigint2 ecdsa"
+const PREHASH_384: [u8; 48] = [
+    0xed, 0x19, 0xc9, 0xc5, 0x53, 0x5c, 0xab, 0xf3, 0x9f, 0xc0, 0x55, 0xc8, 0x89, 0x85, 0x99, 0x3f,
+    0xd5, 0x3e, 0x27, 0xa8, 0x50, 0xb9, 0x3e, 0xde, 0x6e, 0xe7, 0x06, 0x60, 0x5b, 0x61, 0xc5, 0x56,
+    0x12, 0x76, 0xc3, 0x6f, 0xe9, 0x62, 0x7e, 0x1f, 0x5b, 0xd4, 0x7b, 0xa8, 0xda, 0x33, 0x5f, 0xb6,
+];
+
+fn main() {
+    let public_key = AffinePoint::<8, Secp256r1Curve>::from_be_bytes_unchecked(
+        &[
+            0x47, 0x1c, 0x3e, 0x75, 0x8c, 0x49, 0x04, 0x28, 0x5b, 0xba, 0x7e, 0x53, 0x11, 0x8e,
+            0xd0, 0xf5, 0x24, 0xad, 0xeb, 0x07, 0x57, 0xd2, 0x5b, 0xd2, 0xf8, 0xe7, 0xb0, 0xd7,
+            0x6d, 0xfa, 0x71, 0x4c,
+        ],
+        &[
+            0xdd, 0x52, 0x0f, 0x7a, 0xca, 0x8a, 0x8b, 0x91, 0x7a, 0xcc, 0x37, 0xf5, 0x1d, 0xe8,
+            0xf0, 0xc9, 0xbb, 0xe3, 0xad, 0x85, 0x83, 0x82, 0xe7, 0x02, 0xdc, 0x25, 0xa1, 0x2d,
+            0x09, 0xf7, 0xa8, 0x58,
+        ],
+    );
+    let mut signature = [
+        0x14, 0xa4, 0x7c, 0x4d, 0xf0, 0xbc, 0x04, 0x64, 0x9b, 0x21, 0x04, 0x91, 0x58, 0xe3, 0xf3,
+        0x11, 0x88, 0x9b, 0x7d, 0xaf, 0x03, 0xc4, 0x4e, 0xb5, 0xa1, 0xc6, 0x86, 0x14, 0xee, 0x08,
+        0x00, 0x3b, 0xe1, 0x0f, 0x60, 0xf7, 0x71, 0xfd, 0x26, 0xaa, 0x75, 0xfd, 0x8d, 0xd7, 0xff,
+        0xa0, 0x1f, 0xdb, 0x85, 0xf2, 0x47, 0x65, 0xa4, 0xda, 0x80, 0x98, 0xab, 0x11, 0x50, 0x07,
+        0x3e, 0x43, 0xcf, 0x42,
+    ];
+    assert!(public_key.is_on_curve());
+    assert!(ecdsa::verify_prehash(&public_key, &PREHASH, &signature));
+    signature[40] ^= 1;
+    assert!(!ecdsa::verify_prehash(&public_key, &PREHASH, &signature));
+
+    let public_key = AffinePoint::<8, Secp256k1Curve>::from_be_bytes_unchecked(
+        &[
+            0xbb, 0x50, 0xe2, 0xd8, 0x9a, 0x4e, 0xd7, 0x06, 0x63, 0xd0, 0x80, 0x65, 0x9f, 0xe0,
+            0xad, 0x4b, 0x9b, 0xc3, 0xe0, 0x6c, 0x17, 0xa2, 0x27, 0x43, 0x39, 0x66, 0xcb, 0x59,
+            0xce, 0xee, 0x02, 0x0d,
+        ],
+        &[
+            0xec, 0xdd, 0xbf, 0x6e, 0x00, 0x19, 0x20, 0x11, 0x64, 0x8d, 0x13, 0xb1, 0xc0, 0x0a,
+            0xf7, 0x70, 0xc0, 0xc1, 0xbb, 0x60, 0x9d, 0x4d, 0x3a, 0x5c, 0x98, 0xa4, 0x37, 0x72,
+            0xe0, 0xe1, 0x8e, 0xf4,
+        ],
+    );
+    let signature = [
+        0x93, 0x14, 0xfc, 0x8d, 0x94, 0xab, 0xc5, 0x0f, 0x42, 0x91, 0x3c, 0x12, 0x8c, 0x72, 0x50,
+        0x1d, 0x8a, 0x06, 0x35, 0x2c, 0xc7, 0x66, 0x6d, 0xc3, 0x49, 0x27, 0x7d, 0x3f, 0x8d, 0x9f,
+        0xc5, 0x85, 0xc9, 0x08, 0xd9, 0x5f, 0x1f, 0x6f, 0xbb, 0xf4, 0xa7, 0xc4, 0xfa, 0x75, 0x51,
+        0x44, 0x76, 0xa9, 0x42, 0xce, 0x35, 0xdc, 0x55, 0xdf, 0xb4, 0xd6, 0x0b, 0xa4, 0x65, 0x2d,
+        0x84, 0xc0, 0xa3, 0xd3,
+    ];
+    assert!(ecdsa::verify_prehash(&public_key, &PREHASH, &signature));
+
+    let public_key = AffinePoint::<12, Secp384r1Curve>::from_be_bytes_unchecked(
+        &[
+            0x3e, 0x08, 0xb4, 0x34, 0xc0, 0xc6, 0xee, 0x98, 0x3d, 0x96, 0x2e, 0x37, 0x3f, 0x3d,
+            0xa9, 0x22, 0x85, 0x86, 0x7d, 0xd7, 0x80, 0xa0, 0x7c, 0xb3, 0x2f, 0x5c, 0xc3, 0x38,
+            0x72, 0xb1, 0xb5, 0xbb, 0x4f, 0x18, 0xbf, 0xc0, 0xb6, 0xe3, 0xd2, 0x74, 0x56, 0x92,
+            0xe2, 0x6f, 0xc0, 0x81, 0x99, 0xc8,
+        ],
+        &[
+            0x96, 0xf7, 0x9e, 0xc9, 0x98, 0x9e, 0x28, 0xa5, 0x99, 0x15, 0x15, 0x95, 0x1c, 0xb8,
+            0xc0, 0x48, 0xba, 0xeb, 0x0a, 0x2d, 0x5b, 0x0c, 0xdb, 0x0d, 0x73, 0x54, 0x8d, 0x6b,
+            0x36, 0xdb, 0xc4, 0x06, 0x6d, 0x20, 0x56, 0x3e, 0x4e, 0x4d, 0xd9, 0x84, 0x6e, 0x59,
+            0x52, 0xe3, 0xd7, 0x04, 0x8e, 0x6d,
+        ],
+    );
+    let mut signature = [
+        0x4e, 0x47, 0xa2, 0x37, 0xcc, 0x60, 0x8f, 0x03, 0xd3, 0x8b, 0xf9, 0x53, 0xc2, 0xc2, 0x5c,
+        0x8c, 0xb9, 0x9d, 0xe4, 0xbb, 0xad, 0xa9, 0x28, 0x8a, 0x6b, 0x43, 0x65, 0x65, 0x20, 0xb2,
+        0x55, 0x91, 0x43, 0xf1, 0xfd, 0x34, 0x4d, 0xde, 0x21, 0x08, 0x02, 0xbb, 0xc6, 0x30, 0x31,
+        0x59, 0x96, 0xbe, 0xa0, 0x93, 0xbe, 0x82, 0x32, 0xd8, 0xbb, 0x5c, 0x2d, 0xfa, 0x74, 0xd0,
+        0xb5, 0xd4, 0x2d, 0xf1, 0xb4, 0xec, 0xe2, 0xf4, 0x1e, 0x59, 0xc0, 0x92, 0x57, 0xe6, 0x67,
+        0x5b, 0x6e, 0x26, 0xcd, 0xf3, 0x59, 0x67, 0x40, 0x1d, 0x3b, 0xb8, 0xdf, 0xa4, 0xa8, 0xa9,
+        0x1b, 0xa2, 0xd5, 0xd3, 0xe2, 0xc8,
+    ];
+    assert!(public_key.is_on_curve());
+    assert!(ecdsa::verify_prehash(&public_key, &PREHASH_384, &signature));
+    signature[60] ^= 1;
+    assert!(!ecdsa::verify_prehash(
+        &public_key,
+        &PREHASH_384,
+        &signature
+    ));
+}
@@ -0,0 +1,40 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "num-bigint-dig")]
+extern crate num_bigint_dig as num_bigint;
+
+#[allow(unused)]
+use risc0_zkvm::guest::env;
+
+#[cfg(any(feature = "num-bigint-dig", feature = "num-bigint"))]
+fn main() {
+    use num_bigint::BigUint;
+    use risc0_bigint2::field;
+
+    let (lhs, rhs, exponent, modulus): (BigUint, BigUint, BigUint, BigUint) = env::read();
+
+    let sum = field::modadd(&lhs, &rhs, &modulus);
+    let difference = field::modsub(&lhs, &rhs, &modulus);
+    let product = field::modmul(&lhs, &rhs, &modulus);
+    let inverse = field::modinv(&lhs, &modulus);
+    let power = field::modexp(&lhs, &exponent, &modulus);
+
+    env::commit(&(sum, difference, product, inverse, power));
+}
+
+#[cfg(not(any(feature = "num-bigint-dig", feature = "num-bigint")))]
+fn main() {
+    panic!("No bigint library enabled");
+}
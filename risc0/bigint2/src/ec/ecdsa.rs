@@ -0,0 +1,148 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ECDSA signature verification using the accelerated curve operations.
+
+use super::{
+    be_bytes_to_u32s, is_zero, AffinePoint, Curve, Secp256k1Curve, Secp256r1Curve, Secp384r1Curve,
+    EC_256_WIDTH_WORDS, EC_384_WIDTH_WORDS,
+};
+use crate::field::Modulus;
+
+/// A curve with a generator of prime order, as used for ECDSA.
+pub trait EcdsaCurve<const WIDTH: usize>: Curve<WIDTH> {
+    /// The order of the generator as u32 digits, least significant digit first
+    const ORDER: [u32; WIDTH];
+
+    /// The generator as u32 digits for x and y, least significant digit first
+    const GENERATOR: [[u32; WIDTH]; 2];
+}
+
+impl EcdsaCurve<EC_256_WIDTH_WORDS> for Secp256k1Curve {
+    const ORDER: [u32; EC_256_WIDTH_WORDS] = [
+        0xD0364141, 0xBFD25E8C, 0xAF48A03B, 0xBAAEDCE6, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF,
+        0xFFFFFFFF,
+    ];
+    const GENERATOR: [[u32; EC_256_WIDTH_WORDS]; 2] = [
+        [
+            0x16F81798, 0x59F2815B, 0x2DCE28D9, 0x029BFCDB, 0xCE870B07, 0x55A06295, 0xF9DCBBAC,
+            0x79BE667E,
+        ],
+        [
+            0xFB10D4B8, 0x9C47D08F, 0xA6855419, 0xFD17B448, 0x0E1108A8, 0x5DA4FBFC, 0x26A3C465,
+            0x483ADA77,
+        ],
+    ];
+}
+
+impl EcdsaCurve<EC_256_WIDTH_WORDS> for Secp256r1Curve {
+    const ORDER: [u32; EC_256_WIDTH_WORDS] = [
+        0xFC632551, 0xF3B9CAC2, 0xA7179E84, 0xBCE6FAAD, 0xFFFFFFFF, 0xFFFFFFFF, 0x00000000,
+        0xFFFFFFFF,
+    ];
+    const GENERATOR: [[u32; EC_256_WIDTH_WORDS]; 2] = [
+        [
+            0xD898C296, 0xF4A13945, 0x2DEB33A0, 0x77037D81, 0x63A440F2, 0xF8BCE6E5, 0xE12C4247,
+            0x6B17D1F2,
+        ],
+        [
+            0x37BF51F5, 0xCBB64068, 0x6B315ECE, 0x2BCE3357, 0x7C0F9E16, 0x8EE7EB4A, 0xFE1A7F9B,
+            0x4FE342E2,
+        ],
+    ];
+}
+
+impl EcdsaCurve<EC_384_WIDTH_WORDS> for Secp384r1Curve {
+    const ORDER: [u32; EC_384_WIDTH_WORDS] = [
+        0xCCC52973, 0xECEC196A, 0x48B0A77A, 0x581A0DB2, 0xF4372DDF, 0xC7634D81, 0xFFFFFFFF,
+        0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+    ];
+    const GENERATOR: [[u32; EC_384_WIDTH_WORDS]; 2] = [
+        [
+            0x72760AB7, 0x3A545E38, 0xBF55296C, 0x5502F25D, 0x82542A38, 0x59F741E0, 0x8BA79B98,
+            0x6E1D3B62, 0xF320AD74, 0x8EB1C71E, 0xBE8B0537, 0xAA87CA22,
+        ],
+        [
+            0x90EA0E5F, 0x7A431D7C, 0x1D7E819D, 0x0A60B1CE, 0xB5F0B8C0, 0xE9DA3113, 0x289A147C,
+            0xF8F41DBD, 0x9292DC29, 0x5D9E98BF, 0x96262C6F, 0x3617DE4A,
+        ],
+    ];
+}
+
+/// Verifies an ECDSA signature over a message digest.
+///
+/// `prehash` is the digest of the signed message, e.g. its SHA-256 hash, and `signature` is the
+/// big-endian encoding of `r` followed by `s`, each `4 * WIDTH` bytes long. Digests longer than
+/// the order are truncated to their leftmost bytes, which assumes the order of the curve has
+/// exactly `32 * WIDTH` bits, as is the case for secp256k1, P-256 and P-384.
+#[stability::unstable]
+pub fn verify_prehash<const WIDTH: usize, C: EcdsaCurve<WIDTH>>(
+    public_key: &AffinePoint<WIDTH, C>,
+    prehash: &[u8],
+    signature: &[u8],
+) -> bool {
+    if signature.len() != 8 * WIDTH {
+        return false;
+    }
+    let (r, s) = signature.split_at(4 * WIDTH);
+    let z = be_bytes_to_u32s(&prehash[..prehash.len().min(4 * WIDTH)]);
+    verify(public_key, &z, &be_bytes_to_u32s(r), &be_bytes_to_u32s(s))
+}
+
+/// Verifies an ECDSA signature `(r, s)` over the message digest `z`, all given as u32 digits,
+/// least significant digit first.
+#[stability::unstable]
+pub fn verify<const WIDTH: usize, C: EcdsaCurve<WIDTH>>(
+    public_key: &AffinePoint<WIDTH, C>,
+    z: &[u32; WIDTH],
+    r: &[u32; WIDTH],
+    s: &[u32; WIDTH],
+) -> bool {
+    let modulus = C::ORDER;
+    let order = Modulus::new(&modulus);
+    if is_zero(r) || is_zero(s) || !order.is_reduced(r) || !order.is_reduced(s) {
+        return false;
+    }
+    if !public_key.is_on_curve() {
+        return false;
+    }
+
+    let w = order.inv(s);
+    let u1 = order.mul(z, &w);
+    let u2 = order.mul(r, &w);
+
+    // u2 is non-zero, since both r and w are.
+    let mut rhs = AffinePoint::new_unchecked([0u32; WIDTH], [0u32; WIDTH]);
+    public_key.mul(&u2, &mut rhs);
+    let point = if is_zero(&u1) {
+        rhs
+    } else {
+        let [x, y] = C::GENERATOR;
+        let mut lhs = AffinePoint::new_unchecked([0u32; WIDTH], [0u32; WIDTH]);
+        AffinePoint::<WIDTH, C>::new_unchecked(x, y).mul(&u1, &mut lhs);
+
+        let mut sum = AffinePoint::new_unchecked([0u32; WIDTH], [0u32; WIDTH]);
+        if lhs.buffer[0] != rhs.buffer[0] {
+            lhs.add(&rhs, &mut sum);
+        } else if lhs.buffer[1] == rhs.buffer[1] {
+            lhs.double(&mut sum);
+        } else {
+            // The sum is the point at infinity, which has no x coordinate.
+            return false;
+        }
+        sum
+    };
+
+    order.reduce(&point.buffer[0]) == *r
+}
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod ecdsa;
 #[cfg(test)]
 mod tests;
 
 use include_bytes_aligned::include_bytes_aligned;
 
 use crate::ffi::{sys_bigint2_3, sys_bigint2_4};
+use crate::field::Modulus;
 
 const ADD_BLOB: &[u8] = include_bytes_aligned!(4, "ec_add_256.blob");
 const DOUBLE_BLOB: &[u8] = include_bytes_aligned!(4, "ec_double_256.blob");
@@ -33,8 +35,61 @@ const SECP256K1_CURVE: &WeierstrassCurve<EC_256_WIDTH_WORDS> =
         [7, 0, 0, 0, 0, 0, 0, 0],
     );
 
+/// The secp256r1 (P-256) curve's prime as u32 digits, least significant digit first
+const SECP256R1_PRIME: [u32; EC_256_WIDTH_WORDS] = [
+    0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0x00000000, 0x00000000, 0x00000000, 0x00000001, 0xFFFFFFFF,
+];
+const SECP256R1_CURVE: &WeierstrassCurve<EC_256_WIDTH_WORDS> =
+    &WeierstrassCurve::<EC_256_WIDTH_WORDS>::new(
+        SECP256R1_PRIME,
+        // a = p - 3
+        [
+            0xFFFFFFFC, 0xFFFFFFFF, 0xFFFFFFFF, 0x00000000, 0x00000000, 0x00000000, 0x00000001,
+            0xFFFFFFFF,
+        ],
+        [
+            0x27D2604B, 0x3BCE3C3E, 0xCC53B0F6, 0x651D06B0, 0x769886BC, 0xB3EBBD55, 0xAA3A93E7,
+            0x5AC635D8,
+        ],
+    );
+
+/// The BN254 (alt_bn128) curve's prime as u32 digits, least significant digit first
+const BN254_PRIME: [u32; EC_256_WIDTH_WORDS] = [
+    0xD87CFD47, 0x3C208C16, 0x6871CA8D, 0x97816A91, 0x8181585D, 0xB85045B6, 0xE131A029, 0x30644E72,
+];
+const BN254_CURVE: &WeierstrassCurve<EC_256_WIDTH_WORDS> =
+    &WeierstrassCurve::<EC_256_WIDTH_WORDS>::new(
+        BN254_PRIME,
+        [0u32; EC_256_WIDTH_WORDS],
+        [3, 0, 0, 0, 0, 0, 0, 0],
+    );
+
+/// The secp384r1 (P-384) curve's prime as u32 digits, least significant digit first
+const SECP384R1_PRIME: [u32; EC_384_WIDTH_WORDS] = [
+    0xFFFFFFFF, 0x00000000, 0x00000000, 0xFFFFFFFF, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+    0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+];
+const SECP384R1_CURVE: &WeierstrassCurve<EC_384_WIDTH_WORDS> =
+    &WeierstrassCurve::<EC_384_WIDTH_WORDS>::new(
+        SECP384R1_PRIME,
+        // a = p - 3
+        [
+            0xFFFFFFFC, 0x00000000, 0x00000000, 0xFFFFFFFF, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF,
+            0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+        ],
+        [
+            0xD3EC2AEF, 0x2A85C8ED, 0x8A2ED19D, 0xC656398D, 0x5013875A, 0x0314088F, 0xFE814112,
+            0x181D9C6E, 0xE3F82D19, 0x988E056B, 0xE23EE7E4, 0xB3312FA7,
+        ],
+    );
+
+/// The accelerated point operations support primes of up to 256 bits.
 pub const EC_256_WIDTH_WORDS: usize = 256 / 32;
 
+/// The point operations on curves with primes of up to 384 bits are built on the 384 bit
+/// [field](crate::field) operations.
+pub const EC_384_WIDTH_WORDS: usize = 384 / 32;
+
 pub trait Curve<const WIDTH: usize> {
     const CURVE: &'static WeierstrassCurve<WIDTH>;
 }
@@ -46,6 +101,30 @@ impl Curve<EC_256_WIDTH_WORDS> for Secp256k1Curve {
     const CURVE: &'static WeierstrassCurve<EC_256_WIDTH_WORDS> = SECP256K1_CURVE;
 }
 
+/// The NIST P-256 curve, also known as secp256r1 or prime256v1.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Secp256r1Curve {}
+
+impl Curve<EC_256_WIDTH_WORDS> for Secp256r1Curve {
+    const CURVE: &'static WeierstrassCurve<EC_256_WIDTH_WORDS> = SECP256R1_CURVE;
+}
+
+/// The BN254 curve, also known as alt_bn128, whose G1 group is used by Groth16 proofs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Bn254Curve {}
+
+impl Curve<EC_256_WIDTH_WORDS> for Bn254Curve {
+    const CURVE: &'static WeierstrassCurve<EC_256_WIDTH_WORDS> = BN254_CURVE;
+}
+
+/// The NIST P-384 curve, also known as secp384r1.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Secp384r1Curve {}
+
+impl Curve<EC_384_WIDTH_WORDS> for Secp384r1Curve {
+    const CURVE: &'static WeierstrassCurve<EC_384_WIDTH_WORDS> = SECP384R1_CURVE;
+}
+
 /// An elliptic curve over a prime field
 ///
 /// The curve is given in short Weierstrass form y^2 = x^3 + ax + b. It supports a maximum `WIDTH` of its prime (and hence all coefficients and coordinates) given as number of 32-bit words (so the maximum bitwidth will be `32 * WIDTH`)
@@ -79,6 +158,22 @@ impl WeierstrassCurve<EC_256_WIDTH_WORDS> {
     pub const fn secp256k1() -> &'static WeierstrassCurve<EC_256_WIDTH_WORDS> {
         SECP256K1_CURVE
     }
+
+    /// The secp256r1 (P-256) curve configuration.
+    pub const fn secp256r1() -> &'static WeierstrassCurve<EC_256_WIDTH_WORDS> {
+        SECP256R1_CURVE
+    }
+
+    /// The BN254 (alt_bn128) curve configuration.
+    pub const fn bn254() -> &'static WeierstrassCurve<EC_256_WIDTH_WORDS> {
+        BN254_CURVE
+    }
+}
+impl WeierstrassCurve<EC_384_WIDTH_WORDS> {
+    /// The secp384r1 (P-384) curve configuration.
+    pub const fn secp384r1() -> &'static WeierstrassCurve<EC_384_WIDTH_WORDS> {
+        SECP384R1_CURVE
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
@@ -104,6 +199,14 @@ impl<const WIDTH: usize, C> AffinePoint<WIDTH, C> {
     pub fn as_u32s(&self) -> &[[u32; WIDTH]; 2] {
         &self.buffer
     }
+
+    /// Constructs an affine point from big-endian encoded x and y coordinates, without checking
+    /// that it is on a specific curve.
+    ///
+    /// Each coordinate may be at most `4 * WIDTH` bytes long.
+    pub fn from_be_bytes_unchecked(x: &[u8], y: &[u8]) -> AffinePoint<WIDTH, C> {
+        Self::new_unchecked(be_bytes_to_u32s(x), be_bytes_to_u32s(y))
+    }
 }
 
 impl<const WIDTH: usize, C: Curve<WIDTH>> AffinePoint<WIDTH, C> {
@@ -164,6 +267,23 @@ impl<const WIDTH: usize, C: Curve<WIDTH>> AffinePoint<WIDTH, C> {
         result.buffer = result_point;
     }
 
+    /// Checks that the point satisfies the curve equation, with coordinates less than the prime.
+    #[stability::unstable]
+    pub fn is_on_curve(&self) -> bool {
+        let [prime, a, b] = C::CURVE.as_u32s();
+        let [x, y] = &self.buffer;
+        let field = Modulus::new(prime);
+        if !field.is_reduced(x) || !field.is_reduced(y) {
+            return false;
+        }
+
+        // y^2 = x^3 + ax + b, where a and b of the supported curves are reduced
+        let lhs = field.mul(y, y);
+        let rhs = field.mul(&field.add(&field.mul(x, x), a), x);
+        let rhs = field.add(&rhs, b);
+        lhs == rhs
+    }
+
     #[stability::unstable]
     pub fn double(&self, result: &mut Self) {
         let curve = C::CURVE;
@@ -189,6 +309,9 @@ fn double_raw<const WIDTH: usize>(
     curve: &[[u32; WIDTH]; 3],
     result: &mut [[u32; WIDTH]; 2],
 ) {
+    if WIDTH != EC_256_WIDTH_WORDS {
+        return double_field(point, curve, result);
+    }
     unsafe {
         // Because [[u32; WIDTH]; 2] and [u32; WIDTH * 2] are laid out the same way, this `as` is safe
         // (and similarly with [[u32; WIDTH]; 3] and [u32; WIDTH * 3])
@@ -208,6 +331,9 @@ fn add_raw<const WIDTH: usize>(
     curve: &[[u32; WIDTH]; 3],
     result: &mut [[u32; WIDTH]; 2],
 ) {
+    if WIDTH != EC_256_WIDTH_WORDS {
+        return add_field(lhs, rhs, curve, result);
+    }
     unsafe {
         // Because [[u32; WIDTH]; 2] and [u32; WIDTH * 2] are laid out the same way, this `as` is safe
         // (and similarly with [[u32; WIDTH]; 3] and [u32; WIDTH * 3])
@@ -222,6 +348,72 @@ fn add_raw<const WIDTH: usize>(
     }
 }
 
+/// Doubles a point with the field operations, for curves without an accelerated program.
+///
+/// Like the program, this fails if the y coordinate is zero.
+fn double_field<const WIDTH: usize>(
+    point: &[[u32; WIDTH]; 2],
+    curve: &[[u32; WIDTH]; 3],
+    result: &mut [[u32; WIDTH]; 2],
+) {
+    let [prime, a, _] = curve;
+    let [x, y] = point;
+    let field = Modulus::new(prime);
+
+    // lambda = (3x^2 + a) / 2y
+    let x2 = field.mul(x, x);
+    let numerator = field.add(&field.add(&field.add(&x2, &x2), &x2), a);
+    let lambda = field.mul(&numerator, &field.inv(&field.add(y, y)));
+
+    // x3 = lambda^2 - 2x, y3 = lambda (x - x3) - y
+    let x3 = field.sub(&field.sub(&field.mul(&lambda, &lambda), x), x);
+    let y3 = field.sub(&field.mul(&lambda, &field.sub(x, &x3)), y);
+    *result = [x3, y3];
+}
+
+/// Adds two points with the field operations, for curves without an accelerated program.
+///
+/// Like the program, this fails if the points have the same x coordinate.
+fn add_field<const WIDTH: usize>(
+    lhs: &[[u32; WIDTH]; 2],
+    rhs: &[[u32; WIDTH]; 2],
+    curve: &[[u32; WIDTH]; 3],
+    result: &mut [[u32; WIDTH]; 2],
+) {
+    let [prime, _, _] = curve;
+    let [x1, y1] = lhs;
+    let [x2, y2] = rhs;
+    let field = Modulus::new(prime);
+
+    // lambda = (y2 - y1) / (x2 - x1)
+    let lambda = field.mul(&field.sub(y2, y1), &field.inv(&field.sub(x2, x1)));
+
+    // x3 = lambda^2 - x1 - x2, y3 = lambda (x1 - x3) - y1
+    let x3 = field.sub(&field.sub(&field.mul(&lambda, &lambda), x1), x2);
+    let y3 = field.sub(&field.mul(&lambda, &field.sub(x1, &x3)), y1);
+    *result = [x3, y3];
+}
+
+/// Returns true if all digits of the value are zero.
+pub(crate) fn is_zero<const WIDTH: usize>(value: &[u32; WIDTH]) -> bool {
+    value.iter().all(|&digit| digit == 0)
+}
+
+/// Converts a big-endian byte string into u32 digits, least significant digit first.
+pub(crate) fn be_bytes_to_u32s<const WIDTH: usize>(bytes: &[u8]) -> [u32; WIDTH] {
+    assert!(
+        bytes.len() <= WIDTH * 4,
+        "Input too large: {} bytes exceeds width of {} bytes",
+        bytes.len(),
+        WIDTH * 4,
+    );
+    let mut result = [0u32; WIDTH];
+    for (i, &byte) in bytes.iter().rev().enumerate() {
+        result[i / 4] |= (byte as u32) << (8 * (i % 4));
+    }
+    result
+}
+
 /// Checks if the bit at the position is set.
 fn bit<const WIDTH: usize>(scalar: &[u32; WIDTH], bit: u32) -> bool {
     let bits_per_digit = 32u32;
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use risc0_bigint2_methods::{EC_ADD_ELF, EC_DOUBLE_ELF, EC_MUL_ELF};
+use risc0_bigint2_methods::{ECDSA_VERIFY_ELF, EC_ADD_ELF, EC_DOUBLE_ELF, EC_MUL_ELF};
 use risc0_zkvm::{
     get_prover_server, ExecutorEnv, ExecutorImpl, ExitCode, ProverOpts, VerifierContext,
 };
@@ -75,3 +75,23 @@ fn ec_mul() {
     tracing::info!("Runtime: {}", elapsed.as_millis());
     tracing::info!("User cycles: {}", prove_info.stats.user_cycles);
 }
+
+#[test]
+fn ecdsa_verify() {
+    let env = ExecutorEnv::builder().build().unwrap();
+    let now = Instant::now();
+    let session = ExecutorImpl::from_elf(env, ECDSA_VERIFY_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+
+    // Proving checks the accelerated point operations on a curve with a non-zero `a`.
+    let prover = get_prover_server(&ProverOpts::fast()).unwrap();
+    let prove_info = prover
+        .prove_session(&VerifierContext::default(), &session)
+        .unwrap();
+    let elapsed = now.elapsed();
+    tracing::info!("Runtime: {}", elapsed.as_millis());
+    tracing::info!("User cycles: {}", prove_info.stats.user_cycles);
+}
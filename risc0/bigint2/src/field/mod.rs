@@ -0,0 +1,436 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Modular arithmetic with 256, 384 and 4096 bit moduli.
+//!
+//! Values are u32 digits, least significant digit first. The accelerator only proves that the
+//! result is congruent to the expected value and fits in the width, so every function checks
+//! that the result is less than the modulus and panics otherwise.
+//!
+//! The generic functions, such as [modmul], work on any [ToBigInt2Buffer], e.g. the `BigUint`
+//! of `num-bigint` or `num-bigint-dig`, and use the narrowest width that fits the modulus.
+//!
+//! The programs are generated by `cargo xtask bootstrap-bigint2`.
+
+#[cfg(test)]
+mod tests;
+
+use include_bytes_aligned::include_bytes_aligned;
+
+use crate::ffi::{sys_bigint2_3, sys_bigint2_4};
+use crate::{ToBigInt2Buffer, WORD_SIZE};
+
+pub const FIELD_256_WIDTH_WORDS: usize = 256 / (WORD_SIZE * 8);
+pub const FIELD_384_WIDTH_WORDS: usize = 384 / (WORD_SIZE * 8);
+pub const FIELD_4096_WIDTH_WORDS: usize = 4096 / (WORD_SIZE * 8);
+
+struct Blobs {
+    add: &'static [u8],
+    sub: &'static [u8],
+    mul: &'static [u8],
+    inv: &'static [u8],
+}
+
+const BLOBS_256: Blobs = Blobs {
+    add: include_bytes_aligned!(4, "modadd_256.blob"),
+    sub: include_bytes_aligned!(4, "modsub_256.blob"),
+    mul: include_bytes_aligned!(4, "modmul_256.blob"),
+    inv: include_bytes_aligned!(4, "modinv_256.blob"),
+};
+
+const BLOBS_384: Blobs = Blobs {
+    add: include_bytes_aligned!(4, "modadd_384.blob"),
+    sub: include_bytes_aligned!(4, "modsub_384.blob"),
+    mul: include_bytes_aligned!(4, "modmul_384.blob"),
+    inv: include_bytes_aligned!(4, "modinv_384.blob"),
+};
+
+const BLOBS_4096: Blobs = Blobs {
+    add: include_bytes_aligned!(4, "modadd_4096.blob"),
+    sub: include_bytes_aligned!(4, "modsub_4096.blob"),
+    mul: include_bytes_aligned!(4, "modmul_4096.blob"),
+    inv: include_bytes_aligned!(4, "modinv_4096.blob"),
+};
+
+macro_rules! impl_field_width {
+    (
+        $bits:literal,
+        $width:ident,
+        $blobs:ident,
+        $add:ident,
+        $sub:ident,
+        $mul:ident,
+        $inv:ident,
+        $exp:ident
+    ) => {
+        #[doc = concat!("Computes `(lhs + rhs) mod modulus` for ", $bits, " bit values.")]
+        ///
+        /// Both inputs must be less than the modulus.
+        #[stability::unstable]
+        pub fn $add(
+            lhs: &[u32; $width],
+            rhs: &[u32; $width],
+            modulus: &[u32; $width],
+            result: &mut [u32; $width],
+        ) {
+            add_or_sub($blobs.add, lhs, rhs, modulus, result);
+        }
+
+        #[doc = concat!("Computes `(lhs - rhs) mod modulus` for ", $bits, " bit values.")]
+        ///
+        /// Both inputs must be less than the modulus.
+        #[stability::unstable]
+        pub fn $sub(
+            lhs: &[u32; $width],
+            rhs: &[u32; $width],
+            modulus: &[u32; $width],
+            result: &mut [u32; $width],
+        ) {
+            add_or_sub($blobs.sub, lhs, rhs, modulus, result);
+        }
+
+        #[doc = concat!("Computes `(lhs * rhs) mod modulus` for ", $bits, " bit values.")]
+        ///
+        /// Both inputs must be less than the modulus.
+        #[stability::unstable]
+        pub fn $mul(
+            lhs: &[u32; $width],
+            rhs: &[u32; $width],
+            modulus: &[u32; $width],
+            result: &mut [u32; $width],
+        ) {
+            mul($blobs.mul, lhs, rhs, modulus, result);
+        }
+
+        #[doc = concat!("Computes `value^-1 mod modulus` for ", $bits, " bit values.")]
+        ///
+        /// The value must be invertible, i.e. coprime to the modulus, or the execution fails.
+        #[stability::unstable]
+        pub fn $inv(value: &[u32; $width], modulus: &[u32; $width], result: &mut [u32; $width]) {
+            inv($blobs.inv, value, modulus, result);
+        }
+
+        #[doc = concat!("Computes `base^exponent mod modulus` for ", $bits, " bit values.")]
+        ///
+        /// The exponent has any number of u32 digits, least significant digit first.
+        #[stability::unstable]
+        pub fn $exp(
+            base: &[u32; $width],
+            exponent: &[u32],
+            modulus: &[u32; $width],
+            result: &mut [u32; $width],
+        ) {
+            exp($blobs.mul, base, exponent, modulus, result);
+        }
+    };
+}
+
+impl_field_width!(
+    256,
+    FIELD_256_WIDTH_WORDS,
+    BLOBS_256,
+    modadd_256,
+    modsub_256,
+    modmul_256,
+    modinv_256,
+    modexp_256
+);
+impl_field_width!(
+    384,
+    FIELD_384_WIDTH_WORDS,
+    BLOBS_384,
+    modadd_384,
+    modsub_384,
+    modmul_384,
+    modinv_384,
+    modexp_384
+);
+impl_field_width!(
+    4096,
+    FIELD_4096_WIDTH_WORDS,
+    BLOBS_4096,
+    modadd_4096,
+    modsub_4096,
+    modmul_4096,
+    modinv_4096,
+    modexp_4096
+);
+
+/// Computes `(lhs + rhs) mod modulus`, using the narrowest width that fits the modulus.
+///
+/// Both inputs must be less than the modulus, which must have at most 4096 bits.
+#[stability::unstable]
+pub fn modadd<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(lhs: &T, rhs: &T, modulus: &T) -> T {
+    apply(Op::Add, &[lhs, rhs], modulus)
+}
+
+/// Computes `(lhs - rhs) mod modulus`, using the narrowest width that fits the modulus.
+///
+/// Both inputs must be less than the modulus, which must have at most 4096 bits.
+#[stability::unstable]
+pub fn modsub<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(lhs: &T, rhs: &T, modulus: &T) -> T {
+    apply(Op::Sub, &[lhs, rhs], modulus)
+}
+
+/// Computes `(lhs * rhs) mod modulus`, using the narrowest width that fits the modulus.
+///
+/// Both inputs must be less than the modulus, which must have at most 4096 bits.
+#[stability::unstable]
+pub fn modmul<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(lhs: &T, rhs: &T, modulus: &T) -> T {
+    apply(Op::Mul, &[lhs, rhs], modulus)
+}
+
+/// Computes `value^-1 mod modulus`, using the narrowest width that fits the modulus.
+///
+/// The value must be less than the modulus and coprime to it, and the modulus must have at most
+/// 4096 bits.
+#[stability::unstable]
+pub fn modinv<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(value: &T, modulus: &T) -> T {
+    apply(Op::Inv, &[value], modulus)
+}
+
+/// Computes `base^exponent mod modulus`, using the narrowest width that fits the modulus.
+///
+/// The base must be less than the modulus, and both the exponent and the modulus must have at
+/// most 4096 bits.
+#[stability::unstable]
+pub fn modexp<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(
+    base: &T,
+    exponent: &T,
+    modulus: &T,
+) -> T {
+    apply(Op::Exp, &[base, exponent], modulus)
+}
+
+/// Arithmetic modulo a fixed modulus, for the other modules of this crate.
+///
+/// `WIDTH` must be one of the widths of this module, e.g. [FIELD_256_WIDTH_WORDS].
+pub(crate) struct Modulus<'a, const WIDTH: usize> {
+    modulus: &'a [u32; WIDTH],
+    blobs: &'static Blobs,
+}
+
+impl<'a, const WIDTH: usize> Modulus<'a, WIDTH> {
+    pub fn new(modulus: &'a [u32; WIDTH]) -> Self {
+        let blobs = match WIDTH {
+            FIELD_256_WIDTH_WORDS => &BLOBS_256,
+            FIELD_384_WIDTH_WORDS => &BLOBS_384,
+            FIELD_4096_WIDTH_WORDS => &BLOBS_4096,
+            _ => panic!("Unsupported width: {WIDTH} words"),
+        };
+        Self { modulus, blobs }
+    }
+
+    /// Returns true if the value is fully reduced, i.e. less than the modulus.
+    pub fn is_reduced(&self, value: &[u32; WIDTH]) -> bool {
+        is_less(value, self.modulus)
+    }
+
+    /// Reduces any value of the width.
+    pub fn reduce(&self, value: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let mut one = [0u32; WIDTH];
+        one[0] = 1;
+        self.mul(value, &one)
+    }
+
+    /// Computes `lhs + rhs` for reduced inputs.
+    pub fn add(&self, lhs: &[u32; WIDTH], rhs: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let mut result = [0u32; WIDTH];
+        add_or_sub(self.blobs.add, lhs, rhs, self.modulus, &mut result);
+        result
+    }
+
+    /// Computes `lhs - rhs` for reduced inputs.
+    pub fn sub(&self, lhs: &[u32; WIDTH], rhs: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let mut result = [0u32; WIDTH];
+        add_or_sub(self.blobs.sub, lhs, rhs, self.modulus, &mut result);
+        result
+    }
+
+    /// Computes `lhs * rhs`.
+    pub fn mul(&self, lhs: &[u32; WIDTH], rhs: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let mut result = [0u32; WIDTH];
+        mul(self.blobs.mul, lhs, rhs, self.modulus, &mut result);
+        result
+    }
+
+    /// Computes `value^-1` for a value coprime to the modulus.
+    pub fn inv(&self, value: &[u32; WIDTH]) -> [u32; WIDTH] {
+        let mut result = [0u32; WIDTH];
+        inv(self.blobs.inv, value, self.modulus, &mut result);
+        result
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Inv,
+    Exp,
+}
+
+type Wide = [u32; FIELD_4096_WIDTH_WORDS];
+
+fn apply<T: ToBigInt2Buffer<FIELD_4096_WIDTH_WORDS>>(op: Op, operands: &[&T], modulus: &T) -> T {
+    let modulus = modulus.to_u32_array();
+    let operands: [Wide; 2] = core::array::from_fn(|i| {
+        operands
+            .get(i)
+            .map_or([0u32; FIELD_4096_WIDTH_WORDS], |operand| {
+                operand.to_u32_array()
+            })
+    });
+    // The exponent is the only operand that may exceed the modulus.
+    let reduced = if op == Op::Exp { 1 } else { 2 };
+    for operand in &operands[..reduced] {
+        assert!(is_less(operand, &modulus), "Input not reduced");
+    }
+
+    let width = modulus
+        .iter()
+        .rposition(|&digit| digit != 0)
+        .map_or(0, |i| i + 1);
+    let result = if width <= FIELD_256_WIDTH_WORDS {
+        apply_width::<FIELD_256_WIDTH_WORDS>(&BLOBS_256, op, &operands, &modulus)
+    } else if width <= FIELD_384_WIDTH_WORDS {
+        apply_width::<FIELD_384_WIDTH_WORDS>(&BLOBS_384, op, &operands, &modulus)
+    } else {
+        apply_width::<FIELD_4096_WIDTH_WORDS>(&BLOBS_4096, op, &operands, &modulus)
+    };
+    T::from_u32_array(result)
+}
+
+fn apply_width<const WIDTH: usize>(
+    blobs: &Blobs,
+    op: Op,
+    [lhs, rhs]: &[Wide; 2],
+    modulus: &Wide,
+) -> Wide {
+    let narrow = |value: &Wide| -> [u32; WIDTH] { value[..WIDTH].try_into().unwrap() };
+    let modulus = narrow(modulus);
+    let mut result = [0u32; WIDTH];
+    match op {
+        Op::Add => add_or_sub(blobs.add, &narrow(lhs), &narrow(rhs), &modulus, &mut result),
+        Op::Sub => add_or_sub(blobs.sub, &narrow(lhs), &narrow(rhs), &modulus, &mut result),
+        Op::Mul => mul(blobs.mul, &narrow(lhs), &narrow(rhs), &modulus, &mut result),
+        Op::Inv => inv(blobs.inv, &narrow(lhs), &modulus, &mut result),
+        Op::Exp => exp(blobs.mul, &narrow(lhs), rhs, &modulus, &mut result),
+    }
+    let mut wide = [0u32; FIELD_4096_WIDTH_WORDS];
+    wide[..WIDTH].copy_from_slice(&result);
+    wide
+}
+
+/// Runs an addition or subtraction program, which require reduced inputs.
+fn add_or_sub<const WIDTH: usize>(
+    blob: &[u8],
+    lhs: &[u32; WIDTH],
+    rhs: &[u32; WIDTH],
+    modulus: &[u32; WIDTH],
+    result: &mut [u32; WIDTH],
+) {
+    assert!(
+        is_less(lhs, modulus) && is_less(rhs, modulus),
+        "Input not reduced"
+    );
+    unsafe {
+        sys_bigint2_4(
+            blob.as_ptr(),
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+            modulus.as_ptr(),
+            result.as_mut_ptr(),
+        );
+    }
+    check_reduced(result, modulus);
+}
+
+fn mul<const WIDTH: usize>(
+    blob: &[u8],
+    lhs: &[u32; WIDTH],
+    rhs: &[u32; WIDTH],
+    modulus: &[u32; WIDTH],
+    result: &mut [u32; WIDTH],
+) {
+    unsafe {
+        sys_bigint2_4(
+            blob.as_ptr(),
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+            modulus.as_ptr(),
+            result.as_mut_ptr(),
+        );
+    }
+    check_reduced(result, modulus);
+}
+
+fn inv<const WIDTH: usize>(
+    blob: &[u8],
+    value: &[u32; WIDTH],
+    modulus: &[u32; WIDTH],
+    result: &mut [u32; WIDTH],
+) {
+    unsafe {
+        sys_bigint2_3(
+            blob.as_ptr(),
+            value.as_ptr(),
+            modulus.as_ptr(),
+            result.as_mut_ptr(),
+        );
+    }
+    check_reduced(result, modulus);
+}
+
+/// Square-and-multiply exponentiation, from the most significant bit of the exponent down.
+fn exp<const WIDTH: usize>(
+    mul_blob: &[u8],
+    base: &[u32; WIDTH],
+    exponent: &[u32],
+    modulus: &[u32; WIDTH],
+    result: &mut [u32; WIDTH],
+) {
+    let mut one = [0u32; WIDTH];
+    one[0] = 1;
+    // Multiplying by one reduces the base, and gives 0 rather than 1 for a modulus of 1.
+    let mut reduced_base = [0u32; WIDTH];
+    mul(mul_blob, base, &one, modulus, &mut reduced_base);
+    mul(mul_blob, &one, &one, modulus, result);
+
+    let bits = exponent
+        .iter()
+        .rposition(|&digit| digit != 0)
+        .map_or(0, |i| 32 * i + 32 - exponent[i].leading_zeros() as usize);
+    for i in (0..bits).rev() {
+        let acc = *result;
+        mul(mul_blob, &acc, &acc, modulus, result);
+        if (exponent[i / 32] >> (i % 32)) & 1 == 1 {
+            let acc = *result;
+            mul(mul_blob, &acc, &reduced_base, modulus, result);
+        }
+    }
+}
+
+/// Checks the result of a program against the modulus.
+///
+/// The programs prove that the result is congruent to the expected value, which leaves the choice
+/// between it and the values that differ by a multiple of the modulus up to the prover.
+fn check_reduced<const WIDTH: usize>(result: &[u32; WIDTH], modulus: &[u32; WIDTH]) {
+    assert!(is_less(result, modulus), "bigint2 result is not reduced");
+}
+
+fn is_less<const WIDTH: usize>(lhs: &[u32; WIDTH], rhs: &[u32; WIDTH]) -> bool {
+    lhs.iter().rev().lt(rhs.iter().rev())
+}
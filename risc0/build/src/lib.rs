@@ -19,6 +19,8 @@
 
 mod config;
 mod docker;
+mod reproducible;
+mod toolchain;
 
 use std::{
     borrow::Cow,
@@ -41,11 +43,17 @@ use serde::Deserialize;
 use self::{
     config::{GuestBuildOptions, GuestMetadata},
     docker::build_guest_package_docker,
+    toolchain::installed_tag,
 };
 
 pub use self::{
     config::{DockerOptions, GuestOptions},
     docker::{docker_build, BuildStatus, TARGET_DIR},
+    reproducible::{
+        reproducible_build, BuildProvenance, ImageProvenance, PROVENANCE_FILE,
+        REPRODUCIBLE_TARGET_DIR,
+    },
+    toolchain::{version_matches, PinnedToolchain, ToolchainFile, TOOLCHAIN_FILE},
 };
 
 /// This const represents a filename that is used in the use to indicate to in
@@ -652,7 +660,7 @@ fn build_guest_package<P>(
     }
 }
 
-fn detect_toolchain(name: &str) {
+fn detect_toolchain(name: &str, project_dir: &Path) {
     let result = Command::new("rustup")
         .args(["toolchain", "list", "--verbose"])
         .stderr(Stdio::inherit())
@@ -672,6 +680,97 @@ fn detect_toolchain(name: &str) {
         eprintln!("  rzup install");
         std::process::exit(-1);
     }
+
+    if let Err(err) = check_pinned_toolchain(name, project_dir) {
+        eprintln!("{err:#}");
+        eprintln!(
+            "To install and activate the pinned toolchains, run `rzup install` in the project."
+        );
+        std::process::exit(-1);
+    }
+}
+
+/// Checks that the active toolchains and `cargo-risczero` match the versions pinned by the
+/// [TOOLCHAIN_FILE] of the project, if it has one.
+fn check_pinned_toolchain(name: &str, project_dir: &Path) -> Result<()> {
+    let Some((path, file)) = ToolchainFile::discover(project_dir)? else {
+        return Ok(());
+    };
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    check_pinned(
+        &path,
+        "the risc0 rust toolchain",
+        &file.toolchain.rust,
+        || active_rust_tag(name),
+    )?;
+
+    // An overridden C++ toolchain is not managed by rzup, so it can't be checked.
+    if !cpp_toolchain_override() {
+        let cpp_tag = || installed_tag(&risc0_data().ok()?.join("cpp"), "cpp");
+        check_pinned(
+            &path,
+            "the risc0 cpp toolchain",
+            &file.toolchain.cpp,
+            cpp_tag,
+        )?;
+    }
+
+    let cargo_risczero_version = || {
+        let output = sanitized_cmd("cargo")
+            .args(["risczero", "--version"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        // The version is printed as `cargo-risczero <version>`.
+        let stdout = String::from_utf8(output.stdout).ok()?;
+        Some(stdout.split_whitespace().last()?.to_string())
+    };
+    check_pinned(
+        &path,
+        "cargo-risczero",
+        &file.toolchain.cargo_risczero,
+        cargo_risczero_version,
+    )?;
+
+    Ok(())
+}
+
+/// Returns the release tag of the rust toolchain linked to rustup as `name`, if it was installed
+/// by `rzup`.
+fn active_rust_tag(name: &str) -> Option<String> {
+    let output = sanitized_cmd("rustup")
+        .args([&format!("+{name}"), "which", "rustc"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let rustc = String::from_utf8(output.stdout).ok()?;
+    installed_tag(Path::new(rustc.trim()).parent()?.parent()?, "rust")
+}
+
+/// Checks the installed version of a component against the version pinned in the
+/// [TOOLCHAIN_FILE] at `path`. The installed version is only looked up if the component is
+/// pinned.
+fn check_pinned(
+    path: &Path,
+    component: &str,
+    pinned: &Option<String>,
+    installed: impl FnOnce() -> Option<String>,
+) -> Result<()> {
+    let Some(pinned) = pinned else {
+        return Ok(());
+    };
+    match installed() {
+        Some(installed) if version_matches(pinned, &installed) => Ok(()),
+        Some(installed) => anyhow::bail!(
+            "{} pins {component} to {pinned}, but {installed} is active.",
+            path.display()
+        ),
+        None => anyhow::bail!(
+            "{} pins {component} to {pinned}, but the active version could not be determined.",
+            path.display()
+        ),
+    }
 }
 
 fn get_out_dir() -> PathBuf {
@@ -743,7 +842,10 @@ fn do_embed_methods<G: GuestBuilder>(
         .unwrap();
 
     if !is_skip_build() {
-        detect_toolchain(RUSTUP_TOOLCHAIN_NAME);
+        detect_toolchain(
+            RUSTUP_TOOLCHAIN_NAME,
+            pkg.manifest_path.parent().unwrap().as_std_path(),
+        );
     }
 
     let mut guest_list = vec![];
@@ -0,0 +1,580 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use cargo_metadata::MetadataCommand;
+use risc0_binfmt::compute_image_id;
+use risc0_zkp::core::hash::sha::{cpu, Sha256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    active_rust_tag, cargo_command, check_pinned_toolchain,
+    config::{GuestBuildOptions, GuestMetadata},
+    cpp_toolchain_override, encode_rust_flags, get_env_var, installed_tag, risc0_data,
+    sanitized_cmd, BuildStatus, GuestOptions, ToolchainFile, RUSTUP_TOOLCHAIN_NAME, TOOLCHAIN_FILE,
+};
+
+/// The target directory for the ELF binaries of reproducible builds.
+pub const REPRODUCIBLE_TARGET_DIR: &str =
+    "target/riscv-guest/riscv32im-risc0-zkvm-elf/reproducible";
+
+/// The name of the build provenance file written next to the ELF binaries of a reproducible
+/// build.
+pub const PROVENANCE_FILE: &str = "provenance.json";
+
+/// The cargo target directory used for reproducible builds.
+const BUILD_DIR: &str = "target/riscv-guest/reproducible-build";
+
+/// Flags added to the rustc flags of reproducible builds, in addition to the path remapping.
+const REPRODUCIBLE_RUST_FLAGS: &[&str] = &[
+    // Debug info contains absolute paths of the build host, symbol tables are not needed to run
+    // the guest, and build IDs differ between hosts.
+    "-C",
+    "strip=symbols",
+    "-C",
+    "link-arg=--build-id=none",
+];
+
+/// Sections that describe the tools used for a build rather than the guest, which are cleared
+/// from the ELF binaries of reproducible builds.
+const CLEARED_SECTIONS: &[&str] = &[".comment"];
+
+/// The inputs and outputs of a reproducible build, written to [PROVENANCE_FILE].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildProvenance {
+    /// The name of the guest package.
+    pub package: String,
+
+    /// The release tag of the rust toolchain pinned in the [TOOLCHAIN_FILE].
+    pub rust_toolchain: String,
+
+    /// The version of rustc the guest was built with, as reported by `rustc --version`.
+    pub rustc_version: String,
+
+    /// The release tag of the C++ toolchain, if the guest was built with the toolchain installed
+    /// by rzup.
+    pub cpp_toolchain: Option<String>,
+
+    /// The rustc flags the guest was built with, excluding the path remapping.
+    pub rustflags: Vec<String>,
+
+    /// The features the guest was built with.
+    pub features: Vec<String>,
+
+    /// The hex-encoded SHA-256 digest of the `Cargo.lock` the guest was built with.
+    pub cargo_lock_sha256: String,
+
+    /// The ELF binaries produced by the build.
+    pub images: Vec<ImageProvenance>,
+}
+
+/// An ELF binary produced by a reproducible build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageProvenance {
+    /// The name of the binary target.
+    pub name: String,
+
+    /// The path to the ELF binary, relative to the source directory.
+    pub path: String,
+
+    /// The image ID of the ELF binary.
+    pub image_id: String,
+}
+
+/// Build the package in the manifest path reproducibly, without a docker environment.
+///
+/// The build uses the rust toolchain pinned in the project's [TOOLCHAIN_FILE] and the existing
+/// `Cargo.lock`, and remaps the paths of `src_dir`, the cargo home directory and the rust source
+/// directory set with `RISC0_RUST_SRC` so that the resulting image IDs don't depend on the build
+/// host. The ELF binaries are written to [REPRODUCIBLE_TARGET_DIR], along with a
+/// [PROVENANCE_FILE] describing the build.
+pub fn reproducible_build(
+    manifest_path: &Path,
+    src_dir: &Path,
+    guest_opts: &GuestOptions,
+) -> Result<BuildStatus> {
+    build_guest_package_reproducible(manifest_path, src_dir, &guest_opts.clone().into())
+}
+
+pub(crate) fn build_guest_package_reproducible(
+    manifest_path: &Path,
+    src_dir: &Path,
+    guest_opts: &GuestBuildOptions,
+) -> Result<BuildStatus> {
+    if !get_env_var("RISC0_SKIP_BUILD").is_empty() {
+        eprintln!("Skipping build because RISC0_SKIP_BUILD is set");
+        return Ok(BuildStatus::Skipped);
+    }
+
+    let manifest_path = manifest_path
+        .canonicalize()
+        .context(format!("manifest_path: {manifest_path:?}"))?;
+    let src_dir = src_dir.canonicalize().context("src_dir")?;
+    // Paths outside of the source directory are not remapped, so they would end up in the ELFs.
+    manifest_path.strip_prefix(&src_dir).with_context(|| {
+        format!(
+            "{} is not in the source directory {}",
+            manifest_path.display(),
+            src_dir.display()
+        )
+    })?;
+    let meta = MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .exec()
+        .context("Manifest not found")?;
+    let root_pkg = meta.root_package().context("Failed to parse Cargo.toml")?;
+    let guest_opts = guest_opts
+        .clone()
+        .with_metadata(GuestMetadata::from(root_pkg));
+    let pkg_name = root_pkg.name.replace('-', "_");
+
+    let project_dir = manifest_path.parent().context("invalid manifest path")?;
+    let rust_toolchain = ToolchainFile::discover(project_dir)?
+        .and_then(|(_, file)| file.toolchain.rust)
+        .with_context(|| {
+            format!(
+                "Reproducible builds require the rust toolchain to be pinned in {TOOLCHAIN_FILE}"
+            )
+        })?;
+    check_pinned_toolchain(RUSTUP_TOOLCHAIN_NAME, project_dir)?;
+
+    let lock_path = meta.workspace_root.as_std_path().join("Cargo.lock");
+    let lock = fs::read(&lock_path).with_context(|| {
+        format!(
+            "Reproducible builds require a Cargo.lock, but none was found at {}",
+            lock_path.display()
+        )
+    })?;
+
+    eprintln!(
+        "Building ELF binaries in {} reproducibly for riscv32im-risc0-zkvm-elf target...",
+        root_pkg.name
+    );
+
+    let portable_flags: Vec<&str> = guest_opts
+        .rustc_flags
+        .iter()
+        .map(|s| s.as_str())
+        .chain(REPRODUCIBLE_RUST_FLAGS.iter().copied())
+        .collect();
+    let rust_src = get_env_var("RISC0_RUST_SRC");
+    let rust_src = (!rust_src.is_empty()).then(|| Path::new(&rust_src));
+    let remap_flags = remap_path_prefixes(&src_dir, &cargo_homes(), rust_src);
+    let rust_flags: Vec<&str> = portable_flags
+        .iter()
+        .copied()
+        .chain(remap_flags.iter().map(|s| s.as_str()))
+        .collect();
+
+    let build_dir = src_dir.join(BUILD_DIR);
+    let mut cmd = cargo_command("build", &rust_flags);
+    // `cargo_command` already passes `--locked` if RISC0_BUILD_LOCKED is set.
+    if env::var("RISC0_BUILD_LOCKED").is_err() {
+        cmd.arg("--locked");
+    }
+    let features_str = guest_opts.features.join(",");
+    if !features_str.is_empty() {
+        cmd.args(["--features", &features_str]);
+    }
+    cmd.args(["--release", "--manifest-path"])
+        .arg(&manifest_path)
+        .arg("--target-dir")
+        .arg(&build_dir)
+        .env("CARGO_INCREMENTAL", "0");
+    if !cpp_toolchain_override() {
+        cmd.env(
+            "CFLAGS_riscv32im_risc0_zkvm_elf",
+            format!(
+                "-march=rv32im -nostdlib -ffile-prefix-map={}=/src",
+                src_dir.display()
+            ),
+        );
+    }
+    if !cmd.status().context("cargo failed to execute")?.success() {
+        bail!("cargo build failed");
+    }
+
+    let target_dir = Path::new(REPRODUCIBLE_TARGET_DIR).join(&pkg_name);
+    fs::create_dir_all(src_dir.join(&target_dir))?;
+    let mut images = vec![];
+    for target in root_pkg.targets.iter().filter(|t| t.is_bin()) {
+        let built_path = build_dir
+            .join("riscv32im-risc0-zkvm-elf")
+            .join("release")
+            .join(&target.name);
+        let mut elf = fs::read(&built_path)
+            .with_context(|| format!("failed to read {}", built_path.display()))?;
+        clear_sections(&mut elf, CLEARED_SECTIONS)
+            .with_context(|| format!("failed to clear sections of {}", built_path.display()))?;
+        let rel_elf_path = target_dir.join(&target.name);
+        fs::write(src_dir.join(&rel_elf_path), &elf)?;
+        images.push(ImageProvenance {
+            name: target.name.clone(),
+            path: rel_elf_path.display().to_string(),
+            image_id: compute_image_id(&elf)?.to_string(),
+        });
+    }
+
+    let provenance = BuildProvenance {
+        package: root_pkg.name.clone(),
+        rust_toolchain,
+        rustc_version: rustc_version(RUSTUP_TOOLCHAIN_NAME)?,
+        cpp_toolchain: (!cpp_toolchain_override())
+            .then(|| installed_tag(&risc0_data().ok()?.join("cpp"), "cpp"))
+            .flatten(),
+        rustflags: encode_rust_flags(&portable_flags)
+            .split('\x1f')
+            .map(String::from)
+            .collect(),
+        features: guest_opts.features.clone(),
+        cargo_lock_sha256: cpu::Impl::hash_bytes(&lock).to_string(),
+        images,
+    };
+    let provenance_path = src_dir.join(&target_dir).join(PROVENANCE_FILE);
+    fs::write(
+        &provenance_path,
+        serde_json::to_string_pretty(&provenance)? + "\n",
+    )?;
+
+    println!("ELFs ready at:");
+    for image in provenance.images.iter() {
+        println!("ImageID: {} - {:?}", image.image_id, image.path);
+    }
+    println!(
+        "Build provenance written to {:?}",
+        target_dir.join(PROVENANCE_FILE)
+    );
+
+    Ok(BuildStatus::Success)
+}
+
+/// Returns the cargo home directories of the build host: the default one, and the one set with
+/// `CARGO_HOME`.
+fn cargo_homes() -> Vec<PathBuf> {
+    [
+        dirs::home_dir().map(|home| home.join(".cargo")),
+        env::var_os("CARGO_HOME").map(PathBuf::from),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Returns the rustc flags that replace the host specific paths of a build with fixed ones.
+fn remap_path_prefixes(
+    src_dir: &Path,
+    cargo_homes: &[PathBuf],
+    rust_src: Option<&Path>,
+) -> Vec<String> {
+    let mut prefixes: Vec<(&Path, &str)> = cargo_homes
+        .iter()
+        .map(|cargo_home| (cargo_home.as_path(), "/cargo"))
+        .chain(rust_src.map(|rust_src| (rust_src, "/rust-src")))
+        .chain([(src_dir, "/src")])
+        .collect();
+
+    // When several prefixes match a path, rustc uses the last one, so the most specific prefixes
+    // go last.
+    prefixes.sort_by_key(|(from, _)| (from.as_os_str().len(), *from));
+    prefixes.dedup();
+    prefixes
+        .iter()
+        .map(|(from, to)| format!("--remap-path-prefix={}={to}", from.display()))
+        .collect()
+}
+
+/// Clears the contents of the named sections of a 32-bit little-endian ELF binary, and sets
+/// their size to zero. The layout of the binary is left unchanged.
+fn clear_sections(elf: &mut [u8], names: &[&str]) -> Result<()> {
+    const SHT_NOBITS: u32 = 8;
+
+    let read_u16 = |elf: &[u8], offset: usize| -> Result<u16> {
+        let bytes = elf.get(offset..offset + 2).context("ELF is truncated")?;
+        Ok(u16::from_le_bytes(bytes.try_into()?))
+    };
+    let read_u32 = |elf: &[u8], offset: usize| -> Result<u32> {
+        let bytes = elf.get(offset..offset + 4).context("ELF is truncated")?;
+        Ok(u32::from_le_bytes(bytes.try_into()?))
+    };
+
+    if elf.get(..6) != Some(&b"\x7fELF\x01\x01"[..]) {
+        bail!("not a 32-bit little-endian ELF");
+    }
+    let shoff = read_u32(elf, 0x20)? as usize;
+    let shentsize = read_u16(elf, 0x2e)? as usize;
+    let shnum = read_u16(elf, 0x30)? as usize;
+    let shstrndx = read_u16(elf, 0x32)? as usize;
+    if shnum == 0 {
+        return Ok(());
+    }
+    if shentsize < 0x28 || shstrndx >= shnum {
+        bail!("invalid section header table");
+    }
+
+    let header = |index: usize| shoff + index * shentsize;
+    let strtab_offset = read_u32(elf, header(shstrndx) + 0x10)? as usize;
+    let strtab_size = read_u32(elf, header(shstrndx) + 0x14)? as usize;
+    let strtab = elf
+        .get(strtab_offset..strtab_offset + strtab_size)
+        .context("ELF is truncated")?
+        .to_vec();
+
+    for index in 0..shnum {
+        let name_offset = read_u32(elf, header(index))? as usize;
+        let name = strtab
+            .get(name_offset..)
+            .and_then(|name| name.split(|&b| b == 0).next())
+            .context("invalid section name")?;
+        if !names.iter().any(|n| n.as_bytes() == name) {
+            continue;
+        }
+        let sh_type = read_u32(elf, header(index) + 0x04)?;
+        let offset = read_u32(elf, header(index) + 0x10)? as usize;
+        let size = read_u32(elf, header(index) + 0x14)? as usize;
+        if sh_type != SHT_NOBITS {
+            elf.get_mut(offset..offset + size)
+                .context("ELF is truncated")?
+                .fill(0);
+        }
+        elf[header(index) + 0x14..header(index) + 0x18].copy_from_slice(&0u32.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Returns the version of the rustc linked to rustup as `name`, as reported by `rustc --version`.
+fn rustc_version(name: &str) -> Result<String> {
+    let output = sanitized_cmd("rustup")
+        .args([&format!("+{name}"), "which", "rustc"])
+        .output()
+        .with_context(|| format!("rustup failed to find {name} toolchain"))?;
+    if !output.status.success() {
+        bail!("rustup failed to find {name} toolchain");
+    }
+    let rustc = String::from_utf8(output.stdout)?;
+    let output = sanitized_cmd(rustc.trim())
+        .arg("--version")
+        .output()
+        .context("failed to run rustc")?;
+    if !output.status.success() {
+        bail!("`rustc --version` failed");
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn remap_path_prefixes_order() {
+        let src_dir = Path::new("/home/user/.cargo/git/checkouts/project");
+        let cargo_homes = [
+            PathBuf::from("/home/user/.cargo"),
+            PathBuf::from("/opt/cargo"),
+            PathBuf::from("/home/user/.cargo"),
+        ];
+        assert_eq!(
+            remap_path_prefixes(src_dir, &cargo_homes, Some(Path::new("/opt/rust"))),
+            [
+                "--remap-path-prefix=/opt/rust=/rust-src",
+                "--remap-path-prefix=/opt/cargo=/cargo",
+                "--remap-path-prefix=/home/user/.cargo=/cargo",
+                "--remap-path-prefix=/home/user/.cargo/git/checkouts/project=/src",
+            ]
+        );
+        assert_eq!(
+            remap_path_prefixes(src_dir, &[], None),
+            ["--remap-path-prefix=/home/user/.cargo/git/checkouts/project=/src"]
+        );
+    }
+
+    #[test]
+    fn clear_comment_section() {
+        const COMMENT: &[u8] = b"rustc version 1.81.0-dev\0";
+        const SHSTRTAB: &[u8] = b"\0.comment\0.shstrtab\0";
+        let section_header = |name: u32, sh_type: u32, offset: usize, size: usize| {
+            let mut header = [0u8; 40];
+            header[0x00..0x04].copy_from_slice(&name.to_le_bytes());
+            header[0x04..0x08].copy_from_slice(&sh_type.to_le_bytes());
+            header[0x10..0x14].copy_from_slice(&(offset as u32).to_le_bytes());
+            header[0x14..0x18].copy_from_slice(&(size as u32).to_le_bytes());
+            header
+        };
+
+        // An ELF header, followed by the contents of the sections and the section headers.
+        let mut elf = vec![0u8; 52];
+        elf[..7].copy_from_slice(b"\x7fELF\x01\x01\x01");
+        let comment_offset = elf.len();
+        elf.extend_from_slice(COMMENT);
+        let shstrtab_offset = elf.len();
+        elf.extend_from_slice(SHSTRTAB);
+        let shoff = elf.len();
+        elf[0x20..0x24].copy_from_slice(&(shoff as u32).to_le_bytes());
+        elf[0x2e..0x30].copy_from_slice(&40u16.to_le_bytes());
+        elf[0x30..0x32].copy_from_slice(&3u16.to_le_bytes());
+        elf[0x32..0x34].copy_from_slice(&2u16.to_le_bytes());
+        elf.extend_from_slice(&[0u8; 40]);
+        elf.extend_from_slice(&section_header(1, 1, comment_offset, COMMENT.len()));
+        elf.extend_from_slice(&section_header(10, 3, shstrtab_offset, SHSTRTAB.len()));
+
+        let mut cleared = elf.clone();
+        clear_sections(&mut cleared, CLEARED_SECTIONS).unwrap();
+        assert_eq!(cleared.len(), elf.len());
+        assert!(cleared[comment_offset..shstrtab_offset]
+            .iter()
+            .all(|&b| b == 0));
+        assert_eq!(cleared[shstrtab_offset..shoff], *SHSTRTAB);
+        assert_eq!(cleared[shoff + 40 + 0x14..shoff + 40 + 0x18], [0; 4]);
+        assert_eq!(cleared[shoff + 80..], elf[shoff + 80..]);
+
+        // Sections that are not named are left alone.
+        let mut kept = elf.clone();
+        clear_sections(&mut kept, &[".text"]).unwrap();
+        assert_eq!(kept, elf);
+
+        assert!(clear_sections(&mut elf[..shoff + 40], CLEARED_SECTIONS).is_err());
+        assert!(clear_sections(&mut [0u8; 52], CLEARED_SECTIONS).is_err());
+    }
+
+    #[test]
+    fn provenance_round_trip() {
+        let provenance = BuildProvenance {
+            package: "guest".into(),
+            rust_toolchain: "r0.1.81.0".into(),
+            rustc_version: "rustc 1.81.0-dev".into(),
+            cpp_toolchain: None,
+            rustflags: vec!["-C".into(), "strip=symbols".into()],
+            features: vec!["std".into()],
+            cargo_lock_sha256: "00".repeat(32),
+            images: vec![ImageProvenance {
+                name: "guest".into(),
+                path: "target/riscv-guest/guest".into(),
+                image_id: "11".repeat(32),
+            }],
+        };
+        let json = serde_json::to_string_pretty(&provenance).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BuildProvenance>(&json).unwrap(),
+            provenance
+        );
+    }
+
+    const GUEST_MANIFEST: &str = r#"
+[package]
+name = "reproducible-guest"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+
+[dependencies]
+risc0-zkvm = { path = "{zkvm_dir}", default-features = false, features = ["std"] }
+"#;
+
+    const GUEST_MAIN: &str = r#"
+#![no_main]
+
+risc0_zkvm::guest::entry!(main);
+
+fn main() {
+    let input: u32 = risc0_zkvm::guest::env::read();
+    risc0_zkvm::guest::env::commit(&input);
+}
+"#;
+
+    // Builds a sample guest from two source directories at different paths, which must produce
+    // the same image IDs.
+    #[test]
+    #[ignore = "requires the risc0 toolchain installed by rzup and network access"]
+    fn reproducible_across_dirs() {
+        let zkvm_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../zkvm")
+            .canonicalize()
+            .unwrap()
+            .display()
+            .to_string();
+        let rust_tag = active_rust_tag(RUSTUP_TOOLCHAIN_NAME).unwrap();
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let src_dirs = [
+            first.path().to_path_buf(),
+            second.path().join("a").join("longer").join("path"),
+        ];
+        for src_dir in src_dirs.iter() {
+            fs::create_dir_all(src_dir.join("guest/src")).unwrap();
+            fs::write(
+                src_dir.join("guest/Cargo.toml"),
+                GUEST_MANIFEST.replace("{zkvm_dir}", &zkvm_dir),
+            )
+            .unwrap();
+            fs::write(src_dir.join("guest/src/main.rs"), GUEST_MAIN).unwrap();
+            fs::write(
+                src_dir.join(TOOLCHAIN_FILE),
+                format!("[toolchain]\nrust = \"{rust_tag}\"\n"),
+            )
+            .unwrap();
+        }
+
+        // Both builds use the same Cargo.lock.
+        let status = Command::new("cargo")
+            .arg("generate-lockfile")
+            .current_dir(src_dirs[0].join("guest"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::copy(
+            src_dirs[0].join("guest/Cargo.lock"),
+            src_dirs[1].join("guest/Cargo.lock"),
+        )
+        .unwrap();
+
+        let builds: Vec<(Vec<String>, Vec<Vec<u8>>)> = src_dirs
+            .iter()
+            .map(|src_dir| {
+                build_guest_package_reproducible(
+                    &src_dir.join("guest/Cargo.toml"),
+                    src_dir,
+                    &GuestBuildOptions::default(),
+                )
+                .unwrap();
+                let provenance_path = src_dir
+                    .join(REPRODUCIBLE_TARGET_DIR)
+                    .join("reproducible_guest")
+                    .join(PROVENANCE_FILE);
+                let provenance: BuildProvenance =
+                    serde_json::from_slice(&fs::read(provenance_path).unwrap()).unwrap();
+                provenance
+                    .images
+                    .into_iter()
+                    .map(|image| {
+                        let elf = fs::read(src_dir.join(&image.path)).unwrap();
+                        (image.image_id, elf)
+                    })
+                    .unzip()
+            })
+            .collect();
+        assert_eq!(builds[0].0.len(), 1);
+        assert_eq!(builds[0].0, builds[1].0);
+        // The ELF binaries themselves are identical, not only the loaded images.
+        assert!(builds[0].1 == builds[1].1);
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Name of the file a project uses to pin the RISC Zero toolchain versions it needs.
+///
+/// The file is looked up in the project directory and its ancestors, like
+/// `rust-toolchain.toml`. For example:
+///
+/// ```toml
+/// [toolchain]
+/// rust = "r0.1.81.0"
+/// cpp = "2024.01.05"
+/// cargo-risczero = "v1.1.1"
+/// ```
+///
+/// Each entry is the release tag installed by `rzup`, and entries that are left out are not
+/// pinned.
+pub const TOOLCHAIN_FILE: &str = "risc0-toolchain.toml";
+
+/// The contents of a [TOOLCHAIN_FILE].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ToolchainFile {
+    /// The pinned toolchain versions.
+    #[serde(default)]
+    pub toolchain: PinnedToolchain,
+}
+
+/// Versions of the RISC Zero toolchains pinned by a [ToolchainFile].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PinnedToolchain {
+    /// Release tag of the rust toolchain.
+    pub rust: Option<String>,
+
+    /// Release tag of the C++ toolchain.
+    pub cpp: Option<String>,
+
+    /// Release tag of the `cargo-risczero` extension.
+    pub cargo_risczero: Option<String>,
+}
+
+impl ToolchainFile {
+    /// Parses the contents of a [TOOLCHAIN_FILE].
+    pub fn parse(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Reads and parses the [TOOLCHAIN_FILE] at the given path.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Finds the [TOOLCHAIN_FILE] in `dir` or the closest of its ancestors, returning its path
+    /// along with its contents.
+    pub fn discover(dir: impl AsRef<Path>) -> Result<Option<(PathBuf, Self)>> {
+        for dir in dir.as_ref().ancestors() {
+            let path = dir.join(TOOLCHAIN_FILE);
+            if path.is_file() {
+                let file = Self::load(&path)?;
+                return Ok(Some((path, file)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Returns true if the installed release tag matches the pinned one.
+///
+/// Tags are compared ignoring a leading `v`, since `cargo risczero --version` reports the version
+/// without the `v` of the release tag.
+pub fn version_matches(pinned: &str, installed: &str) -> bool {
+    pinned.trim().trim_start_matches('v') == installed.trim().trim_start_matches('v')
+}
+
+/// Returns the release tag of a toolchain installed by `rzup`, given the path it is linked to.
+///
+/// `rzup` installs toolchains into directories named `<tag>-risc0-<language>-<target>`.
+pub(crate) fn installed_tag(path: &Path, language: &str) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    let name = path.file_name()?.to_str()?;
+    let (tag, _target) = name.split_once(&format!("-risc0-{language}-"))?;
+    Some(tag.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_toolchain_file() {
+        let file = ToolchainFile::parse(
+            r#"
+            [toolchain]
+            rust = "r0.1.81.0"
+            cargo-risczero = "v1.1.1"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.toolchain,
+            PinnedToolchain {
+                rust: Some("r0.1.81.0".into()),
+                cpp: None,
+                cargo_risczero: Some("v1.1.1".into()),
+            }
+        );
+
+        assert_eq!(ToolchainFile::parse("").unwrap(), ToolchainFile::default());
+        assert!(ToolchainFile::parse("[toolchain]\nrustc = \"1.81.0\"").is_err());
+    }
+
+    #[test]
+    fn match_versions() {
+        assert!(version_matches("v1.1.1", "1.1.1"));
+        assert!(version_matches("r0.1.81.0", "r0.1.81.0"));
+        assert!(!version_matches("r0.1.81.0", "r0.1.79.0"));
+    }
+
+    #[test]
+    fn discover_and_read_installed_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("methods").join("guest");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(ToolchainFile::discover(&nested).unwrap().is_none());
+
+        fs::write(
+            dir.path().join(TOOLCHAIN_FILE),
+            "[toolchain]\ncpp = \"2024.01.05\"",
+        )
+        .unwrap();
+        let (path, file) = ToolchainFile::discover(&nested).unwrap().unwrap();
+        assert_eq!(path, dir.path().join(TOOLCHAIN_FILE));
+        assert_eq!(file.toolchain.cpp.as_deref(), Some("2024.01.05"));
+
+        let toolchain_dir = dir
+            .path()
+            .join("2024.01.05-risc0-cpp-x86_64-unknown-linux-gnu");
+        fs::create_dir(&toolchain_dir).unwrap();
+        assert_eq!(
+            installed_tag(&toolchain_dir, "cpp").as_deref(),
+            Some("2024.01.05")
+        );
+        assert_eq!(installed_tag(&toolchain_dir, "rust"), None);
+    }
+}
@@ -33,6 +33,9 @@ fn main() -> Result<()> {
         RisczeroCmd::New(cmd) => cmd.run(),
         RisczeroCmd::Deploy(cmd) => cmd.run(),
         RisczeroCmd::Verify(cmd) => cmd.run(),
+        RisczeroCmd::Inspect(cmd) => cmd.run(),
+        RisczeroCmd::Receipt(cmd) => cmd.run(),
+        RisczeroCmd::Profile(cmd) => cmd.run(),
         #[cfg(feature = "experimental")]
         RisczeroCmd::BuildCrate(build) => build.run(BuildSubcommand::Build),
         #[cfg(feature = "experimental")]
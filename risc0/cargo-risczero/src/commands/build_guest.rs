@@ -20,7 +20,7 @@ use risc0_build::{BuildStatus, GuestOptions};
 
 /// `cargo risczero build`
 ///
-/// NOTE: Requires Docker to be installed and running.
+/// NOTE: Requires Docker to be installed and running, unless `--reproducible` is passed.
 #[derive(Parser)]
 pub struct BuildGuest {
     /// Location of the Cargo.toml for the guest code.
@@ -32,22 +32,32 @@ pub struct BuildGuest {
     /// Feature flags passed to cargo.
     #[arg(long, value_delimiter = ',')]
     pub features: Vec<String>,
+
+    /// Build reproducibly with the local toolchain instead of Docker.
+    ///
+    /// Requires the rust toolchain to be pinned in risc0-toolchain.toml and a Cargo.lock. A
+    /// provenance.json describing the build is written next to the ELF binaries.
+    #[arg(long)]
+    pub reproducible: bool,
 }
 
 impl BuildGuest {
     pub fn run(&self) -> Result<()> {
-        build(
-            &self.manifest_path,
-            &GuestOptions {
-                features: self.features.clone(),
-                ..Default::default()
-            },
-        )?;
+        let guest_options = GuestOptions {
+            features: self.features.clone(),
+            ..Default::default()
+        };
+        if self.reproducible {
+            let src_dir = std::env::current_dir()?;
+            risc0_build::reproducible_build(&self.manifest_path, &src_dir, &guest_options)?;
+        } else {
+            build(&self.manifest_path, &guest_options)?;
+        }
         Ok(())
     }
 }
 
 pub(crate) fn build(manifest_path: &Path, guest_options: &GuestOptions) -> Result<BuildStatus> {
-    let src_dir = std::env::current_dir().unwrap();
+    let src_dir = std::env::current_dir()?;
     risc0_build::docker_build(manifest_path, &src_dir, guest_options)
 }
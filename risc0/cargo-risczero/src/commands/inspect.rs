@@ -0,0 +1,465 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    Assumption, CompositeReceiptVerifierParameters, Groth16ReceiptVerifierParameters,
+    InnerAssumptionReceipt, InnerReceipt, MaybePruned, Output, Receipt, ReceiptClaim,
+    SegmentReceiptVerifierParameters, SuccinctReceiptVerifierParameters, SystemState,
+};
+use serde::Serialize;
+
+/// `cargo risczero inspect`
+#[derive(Parser, Debug, Clone, PartialEq, Eq)]
+pub struct InspectCommand {
+    /// The path to the receipt file.
+    receipt: PathBuf,
+
+    /// Also print the journal decoded in this format.
+    #[arg(long, value_enum)]
+    decode_journal: Option<JournalFormat>,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum JournalFormat {
+    /// UTF-8 text, replacing invalid sequences.
+    Utf8,
+    /// Little-endian u32 words, as written by `env::commit`.
+    Words,
+}
+
+impl InspectCommand {
+    pub fn run(&self) -> Result<()> {
+        let data = fs::read(&self.receipt)
+            .with_context(|| format!("failed to read {}", self.receipt.display()))?;
+        let receipt: Receipt =
+            bincode::deserialize(&data).context("failed to decode the receipt")?;
+
+        let report = ReceiptReport::new(&receipt, self.decode_journal)?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print!("{}", report.to_text());
+        }
+
+        Ok(())
+    }
+}
+
+/// A value that may have been pruned from the receipt, leaving only its digest.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Field<T> {
+    Value(T),
+    Pruned(String),
+}
+
+impl<T> Field<T> {
+    fn new<U: Clone + Serialize>(value: &MaybePruned<U>, f: impl FnOnce(&U) -> T) -> Self {
+        match value {
+            MaybePruned::Value(value) => Self::Value(f(value)),
+            MaybePruned::Pruned(digest) => Self::Pruned(digest.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ReceiptReport {
+    kind: &'static str,
+    seal_size: usize,
+    verifier_parameters: String,
+    /// The name of the known [VerifierContext][risc0_zkvm::VerifierContext] whose parameters
+    /// match the verifier_parameters, if any.
+    verifier_context: Option<&'static str>,
+    claim: Field<ClaimReport>,
+    journal: JournalReport,
+    seals: Vec<SealReport>,
+}
+
+#[derive(Serialize)]
+struct ClaimReport {
+    digest: String,
+    pre: Field<StateReport>,
+    post: Field<StateReport>,
+    exit_code: String,
+    input: String,
+    output: Field<Option<OutputReport>>,
+}
+
+#[derive(Serialize)]
+struct StateReport {
+    pc: String,
+    merkle_root: String,
+}
+
+#[derive(Serialize)]
+struct OutputReport {
+    journal_digest: String,
+    assumptions: Field<Vec<Field<AssumptionReport>>>,
+}
+
+#[derive(Serialize)]
+struct AssumptionReport {
+    claim: String,
+    control_root: String,
+}
+
+#[derive(Serialize)]
+struct JournalReport {
+    size: usize,
+    digest: String,
+    hex: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct SealReport {
+    name: String,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashfn: Option<String>,
+    size: usize,
+}
+
+impl ReceiptReport {
+    fn new(receipt: &Receipt, decode_journal: Option<JournalFormat>) -> Result<Self> {
+        let inner = &receipt.inner;
+        let journal = &receipt.journal.bytes;
+        Ok(Self {
+            kind: receipt_kind(inner),
+            seal_size: inner.seal_size(),
+            verifier_parameters: inner.verifier_parameters().to_string(),
+            verifier_context: known_verifier_context(inner),
+            claim: Field::new(&inner.claim()?, ClaimReport::new),
+            journal: JournalReport {
+                size: journal.len(),
+                digest: journal.digest().to_string(),
+                hex: hex::encode(journal),
+                decoded: decode_journal.map(|format| decode(journal, format)),
+            },
+            seals: seals(inner),
+        })
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        let mut line = |indent: usize, text: String| {
+            out.push_str(&"  ".repeat(indent));
+            out.push_str(&text);
+            out.push('\n');
+        };
+
+        line(0, format!("kind: {}", self.kind));
+        line(0, format!("seal size: {} bytes", self.seal_size));
+        line(
+            0,
+            format!(
+                "verifier parameters: {} ({})",
+                self.verifier_parameters,
+                self.verifier_context.unwrap_or("no known verifier context")
+            ),
+        );
+
+        match &self.claim {
+            Field::Pruned(digest) => line(0, format!("claim: pruned {digest}")),
+            Field::Value(claim) => {
+                line(0, format!("claim: {}", claim.digest));
+                for (name, state) in [("pre", &claim.pre), ("post", &claim.post)] {
+                    match state {
+                        Field::Value(state) => line(
+                            1,
+                            format!("{name}: pc {} merkle root {}", state.pc, state.merkle_root),
+                        ),
+                        Field::Pruned(digest) => line(1, format!("{name}: pruned {digest}")),
+                    }
+                }
+                line(1, format!("exit code: {}", claim.exit_code));
+                line(1, format!("input: {}", claim.input));
+                match &claim.output {
+                    Field::Pruned(digest) => line(1, format!("output: pruned {digest}")),
+                    Field::Value(None) => line(1, "output: none".to_string()),
+                    Field::Value(Some(output)) => {
+                        line(1, "output:".to_string());
+                        line(2, format!("journal digest: {}", output.journal_digest));
+                        match &output.assumptions {
+                            Field::Pruned(digest) => {
+                                line(2, format!("assumptions: pruned {digest}"))
+                            }
+                            Field::Value(assumptions) => {
+                                line(2, format!("assumptions: {}", assumptions.len()));
+                                for assumption in assumptions {
+                                    match assumption {
+                                        Field::Value(assumption) => line(
+                                            3,
+                                            format!(
+                                                "claim {} control root {}",
+                                                assumption.claim, assumption.control_root
+                                            ),
+                                        ),
+                                        Field::Pruned(digest) => {
+                                            line(3, format!("pruned {digest}"))
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        line(0, format!("journal: {} bytes", self.journal.size));
+        line(1, format!("digest: {}", self.journal.digest));
+        line(1, format!("hex: {}", self.journal.hex));
+        if let Some(decoded) = &self.journal.decoded {
+            line(1, format!("decoded: {decoded}"));
+        }
+
+        line(0, format!("seals: {}", self.seals.len()));
+        for seal in self.seals.iter() {
+            let hashfn = seal
+                .hashfn
+                .as_ref()
+                .map(|hashfn| format!(" with {hashfn}"))
+                .unwrap_or_default();
+            line(
+                1,
+                format!("{}: {}{hashfn}, {} bytes", seal.name, seal.kind, seal.size),
+            );
+        }
+
+        out
+    }
+}
+
+impl ClaimReport {
+    fn new(claim: &ReceiptClaim) -> Self {
+        Self {
+            digest: claim.digest().to_string(),
+            pre: Field::new(&claim.pre, StateReport::new),
+            post: Field::new(&claim.post, StateReport::new),
+            exit_code: format!("{:?}", claim.exit_code),
+            input: claim.input.digest().to_string(),
+            output: Field::new(&claim.output, |output| {
+                output.as_ref().map(OutputReport::new)
+            }),
+        }
+    }
+}
+
+impl StateReport {
+    fn new(state: &SystemState) -> Self {
+        Self {
+            pc: format!("{:#010x}", state.pc),
+            merkle_root: state.merkle_root.to_string(),
+        }
+    }
+}
+
+impl OutputReport {
+    fn new(output: &Output) -> Self {
+        Self {
+            journal_digest: output.journal.digest().to_string(),
+            assumptions: Field::new(&output.assumptions, |assumptions| {
+                assumptions
+                    .0
+                    .iter()
+                    .map(|assumption| Field::new(assumption, AssumptionReport::new))
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl AssumptionReport {
+    fn new(assumption: &Assumption) -> Self {
+        Self {
+            claim: assumption.claim.to_string(),
+            control_root: assumption.control_root.to_string(),
+        }
+    }
+}
+
+fn receipt_kind(inner: &InnerReceipt) -> &'static str {
+    match inner {
+        InnerReceipt::Composite(_) => "composite",
+        InnerReceipt::Succinct(_) => "succinct",
+        InnerReceipt::Groth16(_) => "groth16",
+        InnerReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    }
+}
+
+/// Returns the name of the known verifier context whose parameters for this kind of receipt
+/// match the ones the receipt was produced for.
+fn known_verifier_context(inner: &InnerReceipt) -> Option<&'static str> {
+    let candidates: [(&'static str, Digest); 2] = match inner {
+        InnerReceipt::Composite(_) => [
+            (
+                "VerifierContext::default()",
+                CompositeReceiptVerifierParameters::default().digest(),
+            ),
+            (
+                "VerifierContext::all_po2s()",
+                CompositeReceiptVerifierParameters::all_po2s().digest(),
+            ),
+        ],
+        InnerReceipt::Succinct(_) => [
+            (
+                "VerifierContext::default()",
+                SuccinctReceiptVerifierParameters::default().digest(),
+            ),
+            (
+                "VerifierContext::all_po2s()",
+                SuccinctReceiptVerifierParameters::all_po2s().digest(),
+            ),
+        ],
+        InnerReceipt::Groth16(_) => [
+            (
+                "VerifierContext::default()",
+                Groth16ReceiptVerifierParameters::default().digest(),
+            ),
+            (
+                "VerifierContext::all_po2s()",
+                Groth16ReceiptVerifierParameters::all_po2s().digest(),
+            ),
+        ],
+        _ => return None,
+    };
+    let digest = inner.verifier_parameters();
+    candidates
+        .into_iter()
+        .find(|(_, candidate)| *candidate == digest)
+        .map(|(name, _)| name)
+}
+
+fn seals(inner: &InnerReceipt) -> Vec<SealReport> {
+    match inner {
+        InnerReceipt::Composite(receipt) => {
+            let segments = receipt.segments.iter().map(|segment| {
+                let kind = if segment.verifier_parameters
+                    == SegmentReceiptVerifierParameters::default().digest()
+                {
+                    "segment"
+                } else {
+                    "segment (non-default parameters)"
+                };
+                SealReport {
+                    name: format!("segment {}", segment.index),
+                    kind,
+                    hashfn: Some(segment.hashfn.clone()),
+                    size: segment.seal_size(),
+                }
+            });
+            let assumptions =
+                receipt
+                    .assumption_receipts
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, assumption)| SealReport {
+                        name: format!("assumption {idx}"),
+                        kind: assumption_kind(assumption),
+                        hashfn: match assumption {
+                            InnerAssumptionReceipt::Succinct(receipt) => {
+                                Some(receipt.hashfn.clone())
+                            }
+                            _ => None,
+                        },
+                        size: assumption.seal_size(),
+                    });
+            segments.chain(assumptions).collect()
+        }
+        InnerReceipt::Succinct(receipt) => vec![SealReport {
+            name: "receipt".to_string(),
+            kind: "succinct",
+            hashfn: Some(receipt.hashfn.clone()),
+            size: receipt.seal_size(),
+        }],
+        InnerReceipt::Groth16(receipt) => vec![SealReport {
+            name: "receipt".to_string(),
+            kind: "groth16",
+            hashfn: None,
+            size: receipt.seal_size(),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+fn assumption_kind(inner: &InnerAssumptionReceipt) -> &'static str {
+    match inner {
+        InnerAssumptionReceipt::Composite(_) => "composite",
+        InnerAssumptionReceipt::Succinct(_) => "succinct",
+        InnerAssumptionReceipt::Groth16(_) => "groth16",
+        InnerAssumptionReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    }
+}
+
+fn decode(journal: &[u8], format: JournalFormat) -> serde_json::Value {
+    match format {
+        JournalFormat::Utf8 => String::from_utf8_lossy(journal).into_owned().into(),
+        JournalFormat::Words => journal
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0u8; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(word)
+            })
+            .collect::<Vec<_>>()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_zkvm::FakeReceipt;
+
+    use super::*;
+
+    #[test]
+    fn inspect_fake_receipt() {
+        let journal = b"hello".to_vec();
+        let claim = ReceiptClaim::ok(Digest::ZERO, journal.clone());
+        let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim.clone())), journal);
+
+        let report = ReceiptReport::new(&receipt, Some(JournalFormat::Utf8)).unwrap();
+        assert_eq!(report.kind, "fake");
+        assert_eq!(report.verifier_context, None);
+        assert!(report.seals.is_empty());
+        let Field::Value(claim_report) = &report.claim else {
+            panic!("claim is pruned");
+        };
+        assert_eq!(claim_report.digest, claim.digest().to_string());
+        assert_eq!(claim_report.exit_code, "Halted(0)");
+        assert_eq!(report.journal.hex, "68656c6c6f");
+        assert_eq!(report.journal.decoded, Some("hello".into()));
+
+        let text = report.to_text();
+        assert!(text.contains("kind: fake"));
+        assert!(text.contains("exit code: Halted(0)"));
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["kind"], "fake");
+        assert_eq!(json["claim"]["value"]["exit_code"], "Halted(0)");
+    }
+}
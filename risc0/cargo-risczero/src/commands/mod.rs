@@ -19,6 +19,9 @@ pub mod build_guest;
 pub mod build_toolchain;
 pub mod datasheet;
 pub mod deploy;
+pub mod inspect;
 pub mod install;
 pub mod new;
+pub mod profile;
+pub mod receipt;
 pub mod verify;
@@ -0,0 +1,75 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use risc0_zkvm::{default_executor, ExecutorEnv};
+
+/// `cargo risczero profile`
+#[derive(Parser, Debug, Clone, PartialEq, Eq)]
+pub struct ProfileCommand {
+    /// The guest ELF to execute.
+    elf: PathBuf,
+
+    /// File to read the guest's input from.
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Add environment variables in the form of NAME=value.
+    #[arg(long, action = clap::ArgAction::Append)]
+    env: Vec<String>,
+
+    /// The number of functions to list.
+    #[arg(long, default_value_t = 20)]
+    top: usize,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl ProfileCommand {
+    pub fn run(&self) -> Result<()> {
+        let elf = fs::read(&self.elf)
+            .with_context(|| format!("failed to read {}", self.elf.display()))?;
+
+        let mut builder = ExecutorEnv::builder();
+        for var in self.env.iter() {
+            let (name, value) = var
+                .split_once('=')
+                .context("Environment variables should be of the form NAME=value")?;
+            builder.env_var(name, value);
+        }
+        if let Some(input) = self.input.as_ref() {
+            builder.stdin(fs::File::open(input)?);
+        }
+        let env = builder.enable_cycle_report().build()?;
+
+        let session = default_executor().execute(env, &elf)?;
+        let report = session
+            .cycle_report
+            .context("the executor did not produce a cycle report")?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print!("{}", report.to_text(self.top));
+            eprintln!("exit code: {:?}", session.exit_code);
+        }
+
+        Ok(())
+    }
+}
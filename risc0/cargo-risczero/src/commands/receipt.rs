@@ -0,0 +1,143 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use risc0_zkvm::codec::{decode_receipt, encode_receipt, ReceiptFormat};
+
+/// `cargo risczero receipt`
+#[derive(Parser, Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptCommand {
+    #[command(subcommand)]
+    command: ReceiptSubcommand,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+enum ReceiptSubcommand {
+    /// Converts a receipt from one format to another.
+    Convert(ConvertCommand),
+}
+
+/// `cargo risczero receipt convert`
+#[derive(Parser, Debug, Clone, PartialEq, Eq)]
+struct ConvertCommand {
+    /// The path to the receipt file to convert.
+    input: PathBuf,
+
+    /// The path to write the converted receipt to.
+    output: PathBuf,
+
+    /// The format of the input receipt.
+    #[arg(long, value_enum, default_value_t = Format::Bincode)]
+    from: Format,
+
+    /// The format to convert the receipt to.
+    ///
+    /// Calldata can only be produced for Groth16 receipts, and contains the
+    /// ABI-encoded seal and journal digest for the Solidity verifier.
+    #[arg(long, value_enum)]
+    to: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// bincode, as written by r0vm and most tools.
+    Bincode,
+    /// JSON.
+    Json,
+    /// protobuf, as used by the r0vm API.
+    Protobuf,
+    /// ABI-encoded calldata for the Solidity verifier.
+    Calldata,
+}
+
+impl From<Format> for ReceiptFormat {
+    fn from(value: Format) -> Self {
+        match value {
+            Format::Bincode => ReceiptFormat::Bincode,
+            Format::Json => ReceiptFormat::Json,
+            Format::Protobuf => ReceiptFormat::Protobuf,
+            Format::Calldata => ReceiptFormat::Calldata,
+        }
+    }
+}
+
+impl ReceiptCommand {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            ReceiptSubcommand::Convert(cmd) => cmd.run(),
+        }
+    }
+}
+
+impl ConvertCommand {
+    fn run(&self) -> Result<()> {
+        let data = fs::read(&self.input)
+            .with_context(|| format!("failed to read {}", self.input.display()))?;
+        let receipt =
+            decode_receipt(&data, self.from.into()).context("failed to decode the receipt")?;
+        let data = encode_receipt(&receipt, self.to.into())?;
+        fs::write(&self.output, &data)
+            .with_context(|| format!("failed to write {}", self.output.display()))?;
+
+        eprintln!(
+            "Wrote {} bytes of receipt to {}",
+            data.len(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_zkvm::{sha::Digest, FakeReceipt, InnerReceipt, Receipt, ReceiptClaim};
+    use tempfile::tempdir;
+
+    use super::{ConvertCommand, Format};
+
+    #[test]
+    fn convert_round_trip() {
+        let journal = b"hello".to_vec();
+        let claim = ReceiptClaim::ok(Digest::ZERO, journal.clone());
+        let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal);
+
+        let dir = tempdir().unwrap();
+        let mut path = dir.path().join("receipt.bin");
+        std::fs::write(&path, bincode::serialize(&receipt).unwrap()).unwrap();
+
+        let mut from = Format::Bincode;
+        for (to, name) in [
+            (Format::Json, "receipt.json"),
+            (Format::Protobuf, "receipt.pb"),
+            (Format::Bincode, "receipt-converted.bin"),
+        ] {
+            let output = dir.path().join(name);
+            ConvertCommand {
+                input: path,
+                output: output.clone(),
+                from,
+                to,
+            }
+            .run()
+            .unwrap();
+            (path, from) = (output, to);
+        }
+
+        let converted: Receipt = bincode::deserialize(&std::fs::read(path).unwrap()).unwrap();
+        assert_eq!(converted, receipt);
+    }
+}
@@ -12,12 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::{Path, PathBuf};
+
 use crate::utils::{self, ClientEnvs};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bonsai_sdk::blocking::SessionId;
 use clap::Parser;
 use hex::FromHex;
-use risc0_zkvm::{sha::Digest, Receipt};
+use risc0_zkvm::{sha::Digest, Receipt, VerifierContext, VerifierContextConfig};
 
 /// `cargo risczero verify`
 #[derive(Parser, Debug, Clone, PartialEq, Eq)]
@@ -29,6 +31,14 @@ pub struct VerifyCommand {
     /// The hex-encoded image ID to verify the receipt against.
     image_id: String,
 
+    /// A JSON file with the verifier context configuration to verify the
+    /// receipt against, e.g. to accept a non-default max po2 or a pinned
+    /// control root.
+    ///
+    /// Uses the default verifier context if not provided.
+    #[arg(long)]
+    verifier_context: Option<PathBuf>,
+
     /// The client environment variables.
     #[command(flatten)]
     client: ClientEnvs,
@@ -56,7 +66,11 @@ impl VerifyCommand {
     pub fn run(&self) -> Result<()> {
         let receipt = self.get_receipt()?;
         let image_id = self.get_image_id()?;
-        let result = receipt.verify(image_id);
+        let ctx = match &self.verifier_context {
+            Some(path) => load_verifier_context(path)?,
+            None => VerifierContext::default(),
+        };
+        let result = receipt.verify_with_context(&ctx, image_id);
         match result {
             Ok(_) => {
                 println!("✅ Receipt is valid!");
@@ -107,3 +121,9 @@ fn parse_receipt(receipt_raw: Vec<u8>) -> Result<Receipt> {
     let receipt: Receipt = bincode::deserialize(&receipt_raw)?;
     Ok(receipt)
 }
+
+fn load_verifier_context(path: &Path) -> Result<VerifierContext> {
+    let config: VerifierContextConfig = serde_json::from_slice(&std::fs::read(path)?)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    VerifierContext::from_config(&config)
+}
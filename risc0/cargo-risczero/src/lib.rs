@@ -30,7 +30,8 @@ use clap::{Parser, Subcommand};
 use self::commands::build::BuildCommand;
 use self::commands::{
     build_guest::BuildGuest, build_toolchain::BuildToolchain, datasheet::Datasheet,
-    deploy::DeployCommand, install::Install, new::NewCommand, verify::VerifyCommand,
+    deploy::DeployCommand, inspect::InspectCommand, install::Install, new::NewCommand,
+    profile::ProfileCommand, receipt::ReceiptCommand, verify::VerifyCommand,
 };
 
 #[derive(Parser)]
@@ -70,6 +71,12 @@ pub enum RisczeroCmd {
     Deploy(DeployCommand),
     /// Verifies if a receipt is valid.
     Verify(VerifyCommand),
+    /// Prints the claim, journal and seals of a receipt.
+    Inspect(InspectCommand),
+    /// Converts receipts between formats.
+    Receipt(ReceiptCommand),
+    /// Executes a guest and reports where it spent its cycles.
+    Profile(ProfileCommand),
     /// Build a crate for RISC Zero.
     #[cfg(feature = "experimental")]
     BuildCrate(BuildCommand),
@@ -101,6 +101,17 @@ pub trait SyscallContext {
     fn get_pc(&self) -> u32;
 }
 
+/// An interactive debugger that is given control before each guest instruction is executed.
+pub trait Debugger {
+    /// Called before the instruction at [SyscallContext::get_pc] is executed.
+    ///
+    /// Execution resumes once this returns.
+    fn on_step(&mut self, ctx: &mut dyn SyscallContext) -> Result<()>;
+
+    /// Called once when execution stops with the given [ExitCode].
+    fn on_exit(&mut self, ctx: &mut dyn SyscallContext, exit_code: ExitCode) -> Result<()>;
+}
+
 pub struct ExecutorResult {
     pub segments: usize,
     pub exit_code: ExitCode,
@@ -149,6 +160,7 @@ pub struct Executor<'a, 'b, S: Syscall> {
     output_digest: Option<Digest>,
     pending: PendingState,
     trace: Vec<Rc<RefCell<dyn TraceCallback + 'b>>>,
+    debugger: Option<Rc<RefCell<dyn Debugger + 'b>>>,
     cycles: SessionCycles,
 }
 
@@ -189,10 +201,17 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
                 events: BTreeSet::new(),
             },
             trace,
+            debugger: None,
             cycles: SessionCycles::default(),
         }
     }
 
+    /// Attach a [Debugger] that is given control before each instruction is executed.
+    pub fn with_debugger(mut self, debugger: Rc<RefCell<dyn Debugger + 'b>>) -> Self {
+        self.debugger = Some(debugger);
+        self
+    }
+
     pub fn run<F: FnMut(Segment) -> Result<()>>(
         &mut self,
         segment_po2: usize,
@@ -213,12 +232,21 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
         let mut emu = Emulator::new();
         let mut segments = 0;
         let initial_state = self.pager.image.get_system_state();
+        let mut debug_cycle = None;
 
         loop {
             if self.exit_code.is_some() {
                 break;
             }
 
+            if let Some(debugger) = self.debugger.clone() {
+                // An instruction replayed after a split is only presented to the debugger once.
+                if debug_cycle != Some(self.cycles.user) {
+                    debug_cycle = Some(self.cycles.user);
+                    debugger.borrow_mut().on_step(self)?;
+                }
+            }
+
             if let Some(max_cycles) = max_cycles {
                 if self.cycles.user >= max_cycles as usize {
                     bail!("Session limit exceeded");
@@ -254,6 +282,7 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
                     post_state,
                     syscalls: mem::take(&mut self.syscalls),
                     insn_cycles: self.insn_cycles,
+                    paging_cycles: self.pager.cycles,
                     po2: segment_po2,
                     exit_code: ExitCode::SystemSplit,
                     index: segments,
@@ -279,12 +308,17 @@ impl<'a, 'b, S: Syscall> Executor<'a, 'b, S> {
         let po2_padding = (1 << po2) - segment_cycles;
         let exit_code = self.exit_code.unwrap();
 
+        if let Some(debugger) = self.debugger.clone() {
+            debugger.borrow_mut().on_exit(self, exit_code)?;
+        }
+
         callback(Segment {
             partial_image,
             pre_state: pre_state.clone(),
             post_state: post_state.clone(),
             syscalls: mem::take(&mut self.syscalls),
             insn_cycles: self.insn_cycles,
+            paging_cycles: self.pager.cycles,
             po2,
             exit_code,
             index: segments,
@@ -32,6 +32,7 @@ pub struct SyscallRecord {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Segment {
     #[debug(skip)]
     pub partial_image: MemoryImage,
@@ -40,6 +41,7 @@ pub struct Segment {
     #[debug(skip)]
     pub syscalls: Vec<SyscallRecord>,
     pub insn_cycles: usize,
+    pub paging_cycles: usize,
     pub po2: usize,
     pub exit_code: ExitCode,
     pub index: usize,
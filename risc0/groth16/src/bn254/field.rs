@@ -0,0 +1,406 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The BN254 base field and its extensions, with the tower used by the pairing:
+//!
+//! * `Fq2 = Fq[u] / (u^2 + 1)`
+//! * `Fq6 = Fq2[v] / (v^3 - ξ)`, where `ξ = 9 + u`
+//! * `Fq12 = Fq6[w] / (w^2 - v)`
+//!
+//! Base field elements are kept in canonical form, as little-endian u32 digits, so that each
+//! operation is a single call to the bigint2 modular arithmetic programs. On the host, which only
+//! builds this module for tests, the programs are replaced by software equivalents.
+
+#[cfg(target_os = "zkvm")]
+use risc0_bigint2::field::{modadd_256, modinv_256, modmul_256, modsub_256};
+
+#[cfg(not(target_os = "zkvm"))]
+use self::host::{modadd_256, modinv_256, modmul_256, modsub_256};
+
+/// The BN254 base field modulus.
+pub(crate) const MODULUS: [u32; 8] = [
+    0xD87CFD47, 0x3C208C16, 0x6871CA8D, 0x97816A91, 0x8181585D, 0xB85045B6, 0xE131A029, 0x30644E72,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Fq(pub [u32; 8]);
+
+impl Fq {
+    pub const ZERO: Self = Self([0; 8]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    /// Returns true if the digits are less than the modulus.
+    pub fn is_reduced(digits: &[u32; 8]) -> bool {
+        digits.iter().rev().lt(MODULUS.iter().rev())
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        let mut result = Self::ZERO;
+        modadd_256(&self.0, &rhs.0, &MODULUS, &mut result.0);
+        result
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        let mut result = Self::ZERO;
+        modsub_256(&self.0, &rhs.0, &MODULUS, &mut result.0);
+        result
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        let mut result = Self::ZERO;
+        modmul_256(&self.0, &rhs.0, &MODULUS, &mut result.0);
+        result
+    }
+
+    pub fn inv(&self) -> Self {
+        let mut result = Self::ZERO;
+        modinv_256(&self.0, &MODULUS, &mut result.0);
+        result
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Fq2 {
+    pub c0: Fq,
+    pub c1: Fq,
+}
+
+impl Fq2 {
+    pub const ZERO: Self = Self::new(Fq::ZERO, Fq::ZERO);
+    pub const ONE: Self = Self::new(Fq::ONE, Fq::ZERO);
+
+    pub const fn new(c0: Fq, c1: Fq) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        Self::new(self.c0.add(&rhs.c0), self.c1.add(&rhs.c1))
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        Self::new(self.c0.sub(&rhs.c0), self.c1.sub(&rhs.c1))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg())
+    }
+
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        // Karatsuba: (a0 + a1 u)(b0 + b1 u) = a0 b0 - a1 b1 + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) u
+        let t0 = self.c0.mul(&rhs.c0);
+        let t1 = self.c1.mul(&rhs.c1);
+        let t2 = self.c0.add(&self.c1).mul(&rhs.c0.add(&rhs.c1));
+        Self::new(t0.sub(&t1), t2.sub(&t0).sub(&t1))
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    pub fn scale(&self, rhs: &Fq) -> Self {
+        Self::new(self.c0.mul(rhs), self.c1.mul(rhs))
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, self.c1.neg())
+    }
+
+    pub fn inv(&self) -> Self {
+        let norm = self.c0.mul(&self.c0).add(&self.c1.mul(&self.c1));
+        self.conjugate().scale(&norm.inv())
+    }
+
+    /// Multiplies by the non-residue `ξ = 9 + u`.
+    pub fn mul_by_nonresidue(&self) -> Self {
+        let nine = Fq([9, 0, 0, 0, 0, 0, 0, 0]);
+        Self::new(
+            self.c0.mul(&nine).sub(&self.c1),
+            self.c0.add(&self.c1.mul(&nine)),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Fq6 {
+    pub c0: Fq2,
+    pub c1: Fq2,
+    pub c2: Fq2,
+}
+
+impl Fq6 {
+    pub const ZERO: Self = Self::new(Fq2::ZERO, Fq2::ZERO, Fq2::ZERO);
+    pub const ONE: Self = Self::new(Fq2::ONE, Fq2::ZERO, Fq2::ZERO);
+
+    pub const fn new(c0: Fq2, c1: Fq2, c2: Fq2) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    pub fn add(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.c0.add(&rhs.c0),
+            self.c1.add(&rhs.c1),
+            self.c2.add(&rhs.c2),
+        )
+    }
+
+    pub fn sub(&self, rhs: &Self) -> Self {
+        Self::new(
+            self.c0.sub(&rhs.c0),
+            self.c1.sub(&rhs.c1),
+            self.c2.sub(&rhs.c2),
+        )
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg(), self.c2.neg())
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let (b0, b1, b2) = (&rhs.c0, &rhs.c1, &rhs.c2);
+        Self::new(
+            a0.mul(b0)
+                .add(&a1.mul(b2).add(&a2.mul(b1)).mul_by_nonresidue()),
+            a0.mul(b1)
+                .add(&a1.mul(b0))
+                .add(&a2.mul(b2).mul_by_nonresidue()),
+            a0.mul(b2).add(&a1.mul(b1)).add(&a2.mul(b0)),
+        )
+    }
+
+    /// Multiplies by `v`, using `v^3 = ξ`.
+    pub fn mul_by_v(&self) -> Self {
+        Self::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+    }
+
+    pub fn inv(&self) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let t0 = a0.square().sub(&a1.mul(a2).mul_by_nonresidue());
+        let t1 = a2.square().mul_by_nonresidue().sub(&a0.mul(a1));
+        let t2 = a1.square().sub(&a0.mul(a2));
+        let norm = a0
+            .mul(&t0)
+            .add(&a2.mul(&t1).add(&a1.mul(&t2)).mul_by_nonresidue());
+        let norm_inv = norm.inv();
+        Self::new(t0.mul(&norm_inv), t1.mul(&norm_inv), t2.mul(&norm_inv))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Fq12 {
+    pub c0: Fq6,
+    pub c1: Fq6,
+}
+
+impl Fq12 {
+    pub const ONE: Self = Self::new(Fq6::ONE, Fq6::ZERO);
+
+    pub const fn new(c0: Fq6, c1: Fq6) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Self {
+        // Karatsuba, using w^2 = v.
+        let t0 = self.c0.mul(&rhs.c0);
+        let t1 = self.c1.mul(&rhs.c1);
+        let t2 = self.c0.add(&self.c1).mul(&rhs.c0.add(&rhs.c1));
+        Self::new(t0.add(&t1.mul_by_v()), t2.sub(&t0).sub(&t1))
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Raises to the power `p^6`, which negates the coefficient of `w`.
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, self.c1.neg())
+    }
+
+    pub fn inv(&self) -> Self {
+        let norm = self.c0.mul(&self.c0).sub(&self.c1.mul(&self.c1).mul_by_v());
+        let norm_inv = norm.inv();
+        Self::new(self.c0.mul(&norm_inv), self.c1.mul(&norm_inv).neg())
+    }
+
+    /// Raises to the power `p^2`.
+    ///
+    /// Elements of `Fq2` are fixed by this map, and `w^(p^2) = γ w`, where `γ = ξ^((p^2 - 1) / 6)`
+    /// is in `Fq`, so the coefficient of `v^i w^j = w^(2i + j)` is multiplied by `γ^(2i + j)`.
+    pub fn frobenius_p2(&self) -> Self {
+        Self::new(
+            Fq6::new(
+                self.c0.c0,
+                self.c0.c1.scale(&FROBENIUS_P2_GAMMA[1]),
+                self.c0.c2.scale(&FROBENIUS_P2_GAMMA[3]),
+            ),
+            Fq6::new(
+                self.c1.c0.scale(&FROBENIUS_P2_GAMMA[0]),
+                self.c1.c1.scale(&FROBENIUS_P2_GAMMA[2]),
+                self.c1.c2.scale(&FROBENIUS_P2_GAMMA[4]),
+            ),
+        )
+    }
+
+    /// Raises to the given power, with u32 digits, least significant digit first.
+    pub fn pow(&self, exponent: &[u32]) -> Self {
+        let mut result = Self::ONE;
+        for i in (0..32 * exponent.len()).rev() {
+            result = result.square();
+            if (exponent[i / 32] >> (i % 32)) & 1 == 1 {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+}
+
+/// `γ^k` for `k` from 1 to 5, where `γ = ξ^((p^2 - 1) / 6)`.
+const FROBENIUS_P2_GAMMA: [Fq; 5] = [
+    Fq([
+        0x607CFD49, 0xE4BD44E5, 0xBB966E3D, 0xC28F069F, 0xE0ACCCB0, 0x5E6DD9E7, 0xE131A029,
+        0x30644E72,
+    ]),
+    Fq([
+        0x607CFD48, 0xE4BD44E5, 0xBB966E3D, 0xC28F069F, 0xE0ACCCB0, 0x5E6DD9E7, 0xE131A029,
+        0x30644E72,
+    ]),
+    Fq([
+        0xD87CFD46, 0x3C208C16, 0x6871CA8D, 0x97816A91, 0x8181585D, 0xB85045B6, 0xE131A029,
+        0x30644E72,
+    ]),
+    Fq([
+        0x77FFFFFE, 0x57634731, 0xACDB5C4F, 0xD4F263F1, 0xA0D48BAC, 0x59E26BCE, 0x00000000,
+        0x00000000,
+    ]),
+    Fq([
+        0x77FFFFFF, 0x57634731, 0xACDB5C4F, 0xD4F263F1, 0xA0D48BAC, 0x59E26BCE, 0x00000000,
+        0x00000000,
+    ]),
+];
+
+/// Software equivalents of the bigint2 modular arithmetic programs, for tests on the host.
+#[cfg(not(target_os = "zkvm"))]
+mod host {
+    use num_bigint::BigUint;
+
+    pub fn modadd_256(lhs: &[u32; 8], rhs: &[u32; 8], modulus: &[u32; 8], result: &mut [u32; 8]) {
+        *result = digits((big(lhs) + big(rhs)) % big(modulus));
+    }
+
+    pub fn modsub_256(lhs: &[u32; 8], rhs: &[u32; 8], modulus: &[u32; 8], result: &mut [u32; 8]) {
+        *result = digits((big(lhs) + big(modulus) - big(rhs)) % big(modulus));
+    }
+
+    pub fn modmul_256(lhs: &[u32; 8], rhs: &[u32; 8], modulus: &[u32; 8], result: &mut [u32; 8]) {
+        *result = digits(big(lhs) * big(rhs) % big(modulus));
+    }
+
+    /// Inverts by Fermat's little theorem, since the modulus is prime.
+    pub fn modinv_256(value: &[u32; 8], modulus: &[u32; 8], result: &mut [u32; 8]) {
+        let modulus = big(modulus);
+        *result = digits(big(value).modpow(&(&modulus - 2u32), &modulus));
+    }
+
+    fn big(digits: &[u32; 8]) -> BigUint {
+        BigUint::from_slice(digits)
+    }
+
+    fn digits(value: BigUint) -> [u32; 8] {
+        let mut result = [0; 8];
+        for (digit, value) in result.iter_mut().zip(value.to_u32_digits()) {
+            *digit = value;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ark_ff::Field;
+
+    use super::{Fq, Fq12, Fq2, Fq6};
+    use crate::bn254::u32s_from_ark;
+
+    pub(crate) fn fq_from_ark(value: &ark_bn254::Fq) -> Fq {
+        Fq(u32s_from_ark(value).unwrap())
+    }
+
+    fn fq2_from_ark(value: &ark_bn254::Fq2) -> Fq2 {
+        Fq2::new(fq_from_ark(&value.c0), fq_from_ark(&value.c1))
+    }
+
+    fn fq6_from_ark(value: &ark_bn254::Fq6) -> Fq6 {
+        Fq6::new(
+            fq2_from_ark(&value.c0),
+            fq2_from_ark(&value.c1),
+            fq2_from_ark(&value.c2),
+        )
+    }
+
+    pub(crate) fn fq12_from_ark(value: &ark_bn254::Fq12) -> Fq12 {
+        Fq12::new(fq6_from_ark(&value.c0), fq6_from_ark(&value.c1))
+    }
+
+    /// An arbitrary element, with all of its coefficients derived from the seed.
+    fn fq12(seed: u64) -> ark_bn254::Fq12 {
+        let mut x = ark_bn254::Fq::from(seed);
+        let mut next = || {
+            x = x * x + ark_bn254::Fq::from(7u64);
+            ark_bn254::Fq2::new(x, x.double())
+        };
+        ark_bn254::Fq12::new(
+            ark_bn254::Fq6::new(next(), next(), next()),
+            ark_bn254::Fq6::new(next(), next(), next()),
+        )
+    }
+
+    #[test]
+    fn fq12_arithmetic() {
+        let (x, y) = (fq12(1), fq12(2));
+        let (ours_x, ours_y) = (fq12_from_ark(&x), fq12_from_ark(&y));
+
+        assert_eq!(ours_x.mul(&ours_y), fq12_from_ark(&(x * y)));
+        assert_eq!(ours_x.square(), fq12_from_ark(&x.square()));
+        assert_eq!(ours_x.inv(), fq12_from_ark(&x.inverse().unwrap()));
+        assert_eq!(ours_x.mul(&ours_x.inv()), Fq12::ONE);
+        assert_eq!(ours_x.conjugate(), fq12_from_ark(&x.frobenius_map(6)));
+        assert_eq!(ours_x.frobenius_p2(), fq12_from_ark(&x.frobenius_map(2)));
+        assert_eq!(
+            ours_x.pow(&[0xDEADBEEF, 0x12345678, 7]),
+            fq12_from_ark(&x.pow([0x12345678DEADBEEF, 7]))
+        );
+    }
+
+    #[test]
+    fn fq2_arithmetic() {
+        let x = ark_bn254::Fq2::new(ark_bn254::Fq::from(3u64), -ark_bn254::Fq::from(5u64));
+        let y = fq12(3).c0.c0;
+        let (ours_x, ours_y) = (fq2_from_ark(&x), fq2_from_ark(&y));
+
+        assert_eq!(ours_x.mul(&ours_y), fq2_from_ark(&(x * y)));
+        assert_eq!(ours_x.sub(&ours_y), fq2_from_ark(&(x - y)));
+        assert_eq!(ours_x.inv(), fq2_from_ark(&x.inverse().unwrap()));
+        // ξ = 9 + u
+        let xi = ark_bn254::Fq2::new(ark_bn254::Fq::from(9u64), ark_bn254::Fq::from(1u64));
+        assert_eq!(ours_y.mul_by_nonresidue(), fq2_from_ark(&(y * xi)));
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Groth16 verification in the zkVM guest, with the BN254 arithmetic accelerated by the bigint2
+//! circuit.
+//!
+//! Verification with arkworks runs its field arithmetic in software, which is too expensive to be
+//! practical in the guest. Instead, the G1 operations use the bigint2 elliptic curve programs, and
+//! the extension fields of the pairing are built on the bigint2 modular arithmetic programs.
+//!
+//! On the host, only the G2 and pairing arithmetic is built, so that it can be tested against
+//! arkworks.
+
+mod field;
+mod pairing;
+
+use alloc::vec::Vec;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use ark_serialize::CanonicalSerialize;
+#[cfg(target_os = "zkvm")]
+use risc0_bigint2::ec::{AffinePoint, Bn254Curve, EC_256_WIDTH_WORDS};
+
+use self::field::{Fq, Fq2};
+#[cfg(target_os = "zkvm")]
+use self::pairing::pairing_product_is_one;
+#[cfg(target_os = "zkvm")]
+use crate::{Fr, Seal, VerifyingKey};
+
+#[cfg(target_os = "zkvm")]
+type G1Affine = AffinePoint<EC_256_WIDTH_WORDS, Bn254Curve>;
+
+/// The order of the G1 and G2 groups.
+const ORDER: [u32; 8] = [
+    0xF0000001, 0x43E1F593, 0x79B97091, 0x2833E848, 0x8181585D, 0xB85045B6, 0xE131A029, 0x30644E72,
+];
+
+/// The coefficient `b' = 3 / ξ` of the twist `y^2 = x^3 + b'` that contains G2.
+const TWIST_B: Fq2 = Fq2::new(
+    Fq([
+        0x24A138E5, 0x3267E6DC, 0x59DBEFA3, 0xB5B4C5E5, 0x1BE06AC3, 0x81BE1899, 0xCEB8AAAE,
+        0x2B149D40,
+    ]),
+    Fq([
+        0x85C315D2, 0xE4A2BD06, 0xE52D1852, 0xA74FA084, 0xEED8FDF4, 0xCD2CAFAD, 0x3AF0FED4,
+        0x009713B0,
+    ]),
+);
+
+/// Verifies a Groth16 proof, checking `e(A, B) = e(α, β) e(vk_x, γ) e(C, δ)`, where `vk_x` is
+/// the linear combination of the verifying key's IC points with the public inputs.
+#[cfg(target_os = "zkvm")]
+pub(crate) fn verify(
+    seal: &Seal,
+    public_inputs: &[Fr],
+    verifying_key: &VerifyingKey,
+) -> Result<()> {
+    let vk = &verifying_key.0;
+    ensure!(
+        public_inputs.len() + 1 == vk.gamma_abc_g1.len(),
+        "expected {} public inputs, got {}",
+        vk.gamma_abc_g1.len().saturating_sub(1),
+        public_inputs.len()
+    );
+
+    let a = g1_from_seal(&seal.a)?;
+    let b = G2Affine::from_seal(&seal.b)?;
+    let c = g1_from_seal(&seal.c)?;
+
+    let mut vk_x = Some(g1_from_ark(&vk.gamma_abc_g1[0])?);
+    for (input, ic) in public_inputs.iter().zip(vk.gamma_abc_g1[1..].iter()) {
+        let scalar = u32s_from_ark(&input.0)?;
+        // Multiplication by zero is not supported, since the result is the point at infinity.
+        if scalar != [0; 8] {
+            let mut term = G1Affine::new_unchecked([0; 8], [0; 8]);
+            g1_from_ark(ic)?.mul(&scalar, &mut term);
+            vk_x = g1_add(vk_x, &term);
+        }
+    }
+
+    let mut pairs = Vec::with_capacity(4);
+    pairs.push((fq_pair(&a), b));
+    pairs.push((
+        neg_fq_pair(&g1_from_ark(&vk.alpha_g1)?),
+        g2_from_ark(&vk.beta_g2)?,
+    ));
+    // The pairing with the point at infinity is one, so it can be left out of the product.
+    if let Some(vk_x) = vk_x {
+        pairs.push((neg_fq_pair(&vk_x), g2_from_ark(&vk.gamma_g2)?));
+    }
+    pairs.push((neg_fq_pair(&c), g2_from_ark(&vk.delta_g2)?));
+
+    ensure!(pairing_product_is_one(&pairs), "Invalid proof");
+    Ok(())
+}
+
+/// An affine point on the twist over `Fq2` that contains G2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct G2Affine {
+    pub x: Fq2,
+    pub y: Fq2,
+}
+
+impl G2Affine {
+    /// Parses a point from its encoding in a [Seal], checking that it is in G2.
+    fn from_seal(elem: &[Vec<Vec<u8>>]) -> Result<Self> {
+        if elem.len() != 2 || elem[0].len() != 2 || elem[1].len() != 2 {
+            bail!("Malformed G2 field element");
+        }
+        let point = Self {
+            x: Fq2::new(
+                fq_from_be_bytes(&elem[0][1])?,
+                fq_from_be_bytes(&elem[0][0])?,
+            ),
+            y: Fq2::new(
+                fq_from_be_bytes(&elem[1][1])?,
+                fq_from_be_bytes(&elem[1][0])?,
+            ),
+        };
+        ensure!(point.is_on_curve(), "G2 point is not on the curve");
+        ensure!(point.is_in_subgroup(), "G2 point is not in the subgroup");
+        Ok(point)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x,
+            y: self.y.neg(),
+        }
+    }
+
+    fn is_on_curve(&self) -> bool {
+        self.y.square() == self.x.square().mul(&self.x).add(&TWIST_B)
+    }
+
+    /// Checks that `[r] Q` is the point at infinity, where `r` is the order of G2.
+    ///
+    /// Unlike G1, the twist has points outside of G2, which must be rejected.
+    fn is_in_subgroup(&self) -> bool {
+        let mut acc: Option<Self> = None;
+        for i in (0..256).rev() {
+            acc = acc.and_then(|acc| acc.double());
+            if (ORDER[i / 32] >> (i % 32)) & 1 == 1 {
+                acc = match acc {
+                    None => Some(*self),
+                    Some(acc) if acc.x == self.x => {
+                        if acc.y == self.y {
+                            acc.double()
+                        } else {
+                            None
+                        }
+                    }
+                    Some(acc) => Some(acc.next(&acc.chord_slope(self), &self.x)),
+                };
+            }
+        }
+        acc.is_none()
+    }
+
+    /// Doubles the point, returning `None` for the point at infinity.
+    fn double(&self) -> Option<Self> {
+        (self.y != Fq2::ZERO).then(|| self.next(&self.tangent_slope(), &self.x))
+    }
+
+    /// The slope of the tangent at the point, which must not have `y = 0`.
+    pub fn tangent_slope(&self) -> Fq2 {
+        let x2 = self.x.square();
+        x2.double().add(&x2).mul(&self.y.double().inv())
+    }
+
+    /// The slope of the line through the point and `rhs`, which must have different x
+    /// coordinates.
+    pub fn chord_slope(&self, rhs: &Self) -> Fq2 {
+        rhs.y.sub(&self.y).mul(&rhs.x.sub(&self.x).inv())
+    }
+
+    /// Returns the negation of the third point of intersection of the curve with the line through
+    /// the point with the given slope, where `x` is the x coordinate of the second point.
+    pub fn next(&self, lambda: &Fq2, x: &Fq2) -> Self {
+        let next_x = lambda.square().sub(&self.x).sub(x);
+        Self {
+            y: lambda.mul(&self.x.sub(&next_x)).sub(&self.y),
+            x: next_x,
+        }
+    }
+}
+
+/// Parses a point from its encoding in a [Seal], checking that it is on the curve. G1 has a
+/// cofactor of one, so every point on the curve is in G1.
+#[cfg(target_os = "zkvm")]
+fn g1_from_seal(elem: &[Vec<u8>]) -> Result<G1Affine> {
+    if elem.len() != 2 {
+        bail!("Malformed G1 field element");
+    }
+    let point =
+        G1Affine::new_unchecked(fq_from_be_bytes(&elem[0])?.0, fq_from_be_bytes(&elem[1])?.0);
+    ensure!(point.is_on_curve(), "G1 point is not on the curve");
+    Ok(point)
+}
+
+/// Adds two points, where `None` is the point at infinity.
+#[cfg(target_os = "zkvm")]
+fn g1_add(lhs: Option<G1Affine>, rhs: &G1Affine) -> Option<G1Affine> {
+    let Some(lhs) = lhs else {
+        return Some(*rhs);
+    };
+    let ([lhs_x, lhs_y], [rhs_x, rhs_y]) = (lhs.as_u32s(), rhs.as_u32s());
+    let mut result = G1Affine::new_unchecked([0; 8], [0; 8]);
+    // The accelerated addition requires distinct x coordinates.
+    if lhs_x == rhs_x {
+        if lhs_y != rhs_y {
+            return None;
+        }
+        lhs.double(&mut result);
+    } else {
+        lhs.add(rhs, &mut result);
+    }
+    Some(result)
+}
+
+#[cfg(target_os = "zkvm")]
+fn fq_pair(point: &G1Affine) -> (Fq, Fq) {
+    let [x, y] = point.as_u32s();
+    (Fq(*x), Fq(*y))
+}
+
+#[cfg(target_os = "zkvm")]
+fn neg_fq_pair(point: &G1Affine) -> (Fq, Fq) {
+    let (x, y) = fq_pair(point);
+    (x, y.neg())
+}
+
+fn fq_from_be_bytes(bytes: &[u8]) -> Result<Fq> {
+    ensure!(bytes.len() <= 32, "Malformed field element");
+    let mut digits = [0u32; 8];
+    for (i, &byte) in bytes.iter().rev().enumerate() {
+        digits[i / 4] |= (byte as u32) << (8 * (i % 4));
+    }
+    ensure!(Fq::is_reduced(&digits), "Field element is not reduced");
+    Ok(Fq(digits))
+}
+
+/// Converts an arkworks field element into u32 digits, least significant digit first.
+fn u32s_from_ark(value: &impl CanonicalSerialize) -> Result<[u32; 8]> {
+    let mut bytes = Vec::with_capacity(32);
+    value
+        .serialize_uncompressed(&mut bytes)
+        .map_err(|err| anyhow!(err))?;
+    ensure!(bytes.len() == 32, "Malformed field element");
+    let mut digits = [0u32; 8];
+    for (digit, chunk) in digits.iter_mut().zip(bytes.chunks_exact(4)) {
+        *digit = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    Ok(digits)
+}
+
+#[cfg(target_os = "zkvm")]
+fn g1_from_ark(point: &ark_bn254::G1Affine) -> Result<G1Affine> {
+    ensure!(
+        !point.infinity,
+        "Verifying key contains the point at infinity"
+    );
+    Ok(G1Affine::new_unchecked(
+        u32s_from_ark(&point.x)?,
+        u32s_from_ark(&point.y)?,
+    ))
+}
+
+fn g2_from_ark(point: &ark_bn254::G2Affine) -> Result<G2Affine> {
+    ensure!(
+        !point.infinity,
+        "Verifying key contains the point at infinity"
+    );
+    Ok(G2Affine {
+        x: Fq2::new(
+            Fq(u32s_from_ark(&point.x.c0)?),
+            Fq(u32s_from_ark(&point.x.c1)?),
+        ),
+        y: Fq2::new(
+            Fq(u32s_from_ark(&point.y.c0)?),
+            Fq(u32s_from_ark(&point.y.c1)?),
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    use super::{g2_from_ark, G2Affine};
+    use crate::from_u256;
+
+    fn seal_g2(x: [&str; 2], y: [&str; 2]) -> Vec<Vec<Vec<u8>>> {
+        [x, y]
+            .iter()
+            .map(|[c0, c1]| vec![from_u256(c1).unwrap(), from_u256(c0).unwrap()])
+            .collect()
+    }
+
+    #[test]
+    fn g2_from_seal() {
+        let generator = ark_bn254::G2Affine::generator();
+        let point = (generator * Fr::from(5u64)).into_affine();
+        let [x0, x1, y0, y1] =
+            [point.x.c0, point.x.c1, point.y.c0, point.y.c1].map(|c| c.to_string());
+        let elem = seal_g2([&x0, &x1], [&y0, &y1]);
+        assert_eq!(
+            G2Affine::from_seal(&elem).unwrap(),
+            g2_from_ark(&point).unwrap()
+        );
+
+        // A point on the twist with x = 1, which is not in G2.
+        let elem = seal_g2(
+            ["1", "0"],
+            [
+                "18278151005453108793778860132295291098363647455926340152056652516292830556603",
+                "5912654199736721486680175016176231956195085055698687135131307249486702594212",
+            ],
+        );
+        let err = G2Affine::from_seal(&elem).unwrap_err();
+        assert_eq!(err.to_string(), "G2 point is not in the subgroup");
+
+        // The same point with a different y is not on the twist.
+        let elem = seal_g2(["1", "0"], ["1", "0"]);
+        let err = G2Affine::from_seal(&elem).unwrap_err();
+        assert_eq!(err.to_string(), "G2 point is not on the curve");
+    }
+}
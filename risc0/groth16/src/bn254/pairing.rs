@@ -0,0 +1,225 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The optimal ate pairing on BN254.
+//!
+//! Points on G2 are kept in affine coordinates on the sextic twist `y^2 = x^3 + 3 / ξ` over `Fq2`,
+//! since an inversion costs about as much as a multiplication with the bigint2 programs. A point
+//! `(x, y)` on the twist maps to `(x w^2, y w^3)` on the curve over `Fq12`.
+
+use alloc::vec::Vec;
+
+use super::{
+    field::{Fq, Fq12, Fq2, Fq6},
+    G2Affine,
+};
+
+/// The digits of `6x + 2` in non-adjacent form, least significant digit first, where `x` is the
+/// BN254 curve parameter.
+const ATE_LOOP_COUNT: [i8; 66] = [
+    0, 0, 0, 1, 0, 1, 0, -1, 0, 0, -1, 0, 0, 0, 1, 0, 0, -1, 0, -1, 0, 0, 0, 1, 0, -1, 0, 0, 0, 0,
+    -1, 0, 0, 1, 0, -1, 0, 0, 1, 0, 0, 0, 0, 0, -1, 0, 0, -1, 0, 1, 0, -1, 0, 0, 0, -1, 0, -1, 0,
+    0, 0, 1, 0, -1, 0, 1,
+];
+
+/// `ξ^((p - 1) / 3)`, which multiplies the conjugated x coordinate of the Frobenius of a point.
+const FROBENIUS_X: Fq2 = Fq2::new(
+    Fq([
+        0x176F553D, 0x99E39557, 0xC2C3330C, 0xB78CC310, 0xF559B143, 0x4C0BEC3C, 0x4F7911F7,
+        0x2FB34798,
+    ]),
+    Fq([
+        0x640FCBA2, 0x1665D51C, 0x0B7C9DCE, 0x32AE2A1D, 0xD75A0794, 0x4BA4CC8B, 0x61EBAE20,
+        0x16C9E550,
+    ]),
+);
+
+/// `ξ^((p - 1) / 2)`, which multiplies the conjugated y coordinate of the Frobenius of a point.
+const FROBENIUS_Y: Fq2 = Fq2::new(
+    Fq([
+        0x71A0135A, 0xDC540146, 0xA9C95998, 0xDBAAE0ED, 0xB6E2F9B9, 0xDC5EC698, 0x489AF5DC,
+        0x063CF305,
+    ]),
+    Fq([
+        0x2623B0E3, 0x82D37F63, 0x8FA25BD2, 0x21807DC9, 0xEC796F2B, 0x0704B5A7, 0xAC41049A,
+        0x07C03CBC,
+    ]),
+);
+
+/// `ξ^((p^2 - 1) / 3)`, which multiplies the x coordinate of the square of the Frobenius of a
+/// point. The y coordinate is negated, since `ξ^((p^2 - 1) / 2) = -1`.
+const FROBENIUS_P2_X: Fq = Fq([
+    0x607CFD48, 0xE4BD44E5, 0xBB966E3D, 0xC28F069F, 0xE0ACCCB0, 0x5E6DD9E7, 0xE131A029, 0x30644E72,
+]);
+
+/// The exponent `(p^4 - p^2 + 1) / r` of the hard part of the final exponentiation.
+const FINAL_EXP_HARD: [u32; 24] = [
+    0xCCDF42B1, 0xE81BB482, 0xF49C36D4, 0x5ABF5CC4, 0x1DA014FD, 0xF1154E7E, 0x87CDBACF, 0xDCC7B44C,
+    0x954BCF8A, 0xAAA441E3, 0xD5095F23, 0x6B887D56, 0xF3FD90C6, 0x79581E16, 0xD189227D, 0x3B1B1355,
+    0x61876F6B, 0x4E529A58, 0xD5B12278, 0x6C0EB522, 0x83177FAF, 0x331EC151, 0x0B0759AD, 0x01BAAA71,
+];
+
+/// Returns true if the product of the pairings of the given points is one.
+///
+/// The G1 points are given as affine `(x, y)` coordinates, and none of the points may be the
+/// point at infinity.
+pub(crate) fn pairing_product_is_one(pairs: &[((Fq, Fq), G2Affine)]) -> bool {
+    final_exponentiation(&multi_miller_loop(pairs)) == Fq12::ONE
+}
+
+fn multi_miller_loop(pairs: &[((Fq, Fq), G2Affine)]) -> Fq12 {
+    let mut f = Fq12::ONE;
+    let mut points: Vec<G2Affine> = pairs.iter().map(|(_, q)| *q).collect();
+
+    for &digit in ATE_LOOP_COUNT.iter().rev().skip(1) {
+        f = f.square();
+        for ((p, q), t) in pairs.iter().zip(points.iter_mut()) {
+            f = f.mul(&t.double_with_line(p));
+            if digit != 0 {
+                let q = if digit > 0 { *q } else { q.neg() };
+                f = f.mul(&t.add_with_line(&q, p));
+            }
+        }
+    }
+
+    for ((p, q), t) in pairs.iter().zip(points.iter_mut()) {
+        // Q1 = π(Q) and Q2 = -π^2(Q), where π is the Frobenius endomorphism.
+        let q1 = G2Affine {
+            x: q.x.conjugate().mul(&FROBENIUS_X),
+            y: q.y.conjugate().mul(&FROBENIUS_Y),
+        };
+        let q2 = G2Affine {
+            x: q.x.scale(&FROBENIUS_P2_X),
+            y: q.y,
+        };
+        f = f.mul(&t.add_with_line(&q1, p));
+        f = f.mul(&t.add_with_line(&q2, p));
+    }
+    f
+}
+
+fn final_exponentiation(f: &Fq12) -> Fq12 {
+    // The easy part raises to the power (p^6 - 1)(p^2 + 1).
+    let f = f.conjugate().mul(&f.inv());
+    let f = f.frobenius_p2().mul(&f);
+    f.pow(&FINAL_EXP_HARD)
+}
+
+impl G2Affine {
+    /// Doubles the point, returning the tangent line at the point evaluated at `p`.
+    fn double_with_line(&mut self, p: &(Fq, Fq)) -> Fq12 {
+        let lambda = self.tangent_slope();
+        let line = self.line(&lambda, p);
+        *self = self.next(&lambda, &self.x);
+        line
+    }
+
+    /// Adds `rhs` to the point, returning the line through both points evaluated at `p`.
+    ///
+    /// The points must not be equal or opposite.
+    fn add_with_line(&mut self, rhs: &Self, p: &(Fq, Fq)) -> Fq12 {
+        let lambda = self.chord_slope(rhs);
+        let line = self.line(&lambda, p);
+        *self = self.next(&lambda, &rhs.x);
+        line
+    }
+
+    /// Evaluates the line with the given slope through the point at `p`.
+    fn line(&self, lambda: &Fq2, p: &(Fq, Fq)) -> Fq12 {
+        // Untwisted, the line is y - y_T w^3 - λ w (x - x_T w^2), which evaluated at P is
+        // y_P - λ x_P w + (λ x_T - y_T) w^3.
+        Fq12::new(
+            Fq6::new(Fq2::new(p.1, Fq::ZERO), Fq2::ZERO, Fq2::ZERO),
+            Fq6::new(
+                lambda.scale(&p.0).neg(),
+                lambda.mul(&self.x).sub(&self.y),
+                Fq2::ZERO,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+
+    use super::{final_exponentiation, multi_miller_loop, pairing_product_is_one};
+    use crate::bn254::{
+        field::{
+            tests::{fq12_from_ark, fq_from_ark},
+            Fq, Fq12,
+        },
+        g2_from_ark, G2Affine,
+    };
+
+    /// `2x (6x^2 + 3x + 1)`, where `x` is the BN254 curve parameter.
+    ///
+    /// The final exponentiation of arkworks follows Fuentes-Castañeda et al., "Faster hashing to
+    /// G2", which raises to this multiple of `(p^12 - 1) / r`.
+    const ARK_FINAL_EXP_MULTIPLE: [u32; 6] = [
+        0x3DDEDAF4, 0x2E5D4E22, 0xD9D9E38D, 0x1EA96B02, 0x15E307C8, 0x3BEC47DF,
+    ];
+
+    fn g1(scalar: u64) -> (Fq, Fq) {
+        let point = (ark_bn254::G1Affine::generator() * Fr::from(scalar)).into_affine();
+        (fq_from_ark(&point.x), fq_from_ark(&point.y))
+    }
+
+    fn g2(scalar: u64) -> G2Affine {
+        let point = (ark_bn254::G2Affine::generator() * Fr::from(scalar)).into_affine();
+        g2_from_ark(&point).unwrap()
+    }
+
+    fn pairing(p: (Fq, Fq), q: G2Affine) -> Fq12 {
+        final_exponentiation(&multi_miller_loop(&[(p, q)]))
+    }
+
+    #[test]
+    fn known_value() {
+        let expected = Bn254::pairing(
+            ark_bn254::G1Affine::generator(),
+            ark_bn254::G2Affine::generator(),
+        );
+        let e = pairing(g1(1), g2(1));
+        assert_ne!(e, Fq12::ONE);
+        assert_eq!(e.pow(&ARK_FINAL_EXP_MULTIPLE), fq12_from_ark(&expected.0));
+    }
+
+    #[test]
+    fn bilinearity() {
+        let (a, b) = (0x1234_5678_9ABC_DEF0u64, 0x0FED_CBA9_8765_4321u64);
+        let ab = a as u128 * b as u128;
+        let ab_digits = [0, 1, 2, 3].map(|i| (ab >> (32 * i)) as u32);
+
+        let e = pairing(g1(1), g2(1));
+        assert_eq!(pairing(g1(a), g2(b)), e.pow(&ab_digits));
+        assert_eq!(pairing(g1(a), g2(b)), pairing(g1(b), g2(a)));
+    }
+
+    #[test]
+    fn product() {
+        let (a, b) = (3, 11);
+        let (x, y) = g1(a * b);
+        assert!(pairing_product_is_one(&[
+            (g1(a), g2(b)),
+            ((x, y.neg()), g2(1))
+        ]));
+        assert!(!pairing_product_is_one(&[(g1(a), g2(b)), ((x, y), g2(1))]));
+        assert!(!pairing_product_is_one(&[
+            (g1(a), g2(b)),
+            ((x, y.neg()), g2(2))
+        ]));
+    }
+}
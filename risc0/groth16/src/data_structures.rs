@@ -137,7 +137,7 @@ pub struct ProofJson {
 }
 
 /// Groth16 Verifying Key encoded as JSON.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct VerifyingKeyJson {
     protocol: String,
     curve: String,
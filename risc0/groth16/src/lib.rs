@@ -42,9 +42,12 @@
 //! }
 //! ```
 //!
+//! Inside the zkVM guest, [verify] checks a proof with the BN254 arithmetic accelerated by the
+//! bigint2 circuit, which is far cheaper than verifying with [Verifier].
+//!
 //! ## STARK to SNARK
 //!
-//! It also provides the [stark_to_snark][docker::stark_to_snark] function to run a prover Groth16
+//! It also provides the [stark_to_snark] function to run a prover Groth16
 //! recursion prover via Docker. After generating a RISC Zero STARK proof, this function can be
 //! used to transform it into a Groth16 proof. This function becomes available when the `prove`
 //! feature flag is enabled.
@@ -52,6 +55,10 @@
 //! > IMPORTANT: This feature requires an x86 architecture and Docker installed.
 //! > Additionally, specific [installation steps](https://github.com/risc0/risc0/tree/main/groth16_proof) must be followed to use this functionality.
 //!
+//! With the `prove-native` feature flag, [stark_to_snark] instead uses the prover in the
+//! [native] module, which is implemented in Rust and does not require Docker. It runs on any
+//! architecture, using the artifacts described in the [native] module.
+//!
 //! The recommended way to get a Groth16 proof is to use the `Prover` trait in the [risc0-zkvm]
 //! crate. With `ProverOpts::groth16()` it will produce a Groth16 proof.
 //!
@@ -73,9 +80,13 @@ use ark_serialize::CanonicalDeserialize;
 use num_bigint::BigInt;
 use risc0_zkp::core::digest::Digest;
 
+#[cfg(any(target_os = "zkvm", test))]
+mod bn254;
 mod data_structures;
 #[cfg(feature = "prove")]
 pub mod docker;
+#[cfg(feature = "prove-native")]
+pub mod native;
 #[cfg(feature = "prove")]
 mod seal_format;
 #[cfg(feature = "prove")]
@@ -85,7 +96,19 @@ mod verifier;
 pub use data_structures::{ProofJson, PublicInputsJson, Seal, VerifyingKeyJson};
 #[cfg(feature = "prove")]
 pub use seal_to_json::to_json;
-pub use verifier::{verifying_key, Fr, Verifier, VerifyingKey};
+pub use verifier::{verify, verifying_key, Fr, Verifier, VerifyingKey};
+
+/// Groth16 a given seal of an `identity_p254` receipt into a Groth16 [Seal].
+///
+/// Uses the prover in the [native] module if the `prove-native` feature is enabled, and the
+/// Docker prover in the [docker] module otherwise.
+#[cfg(feature = "prove")]
+pub fn stark_to_snark(identity_p254_seal_bytes: &[u8]) -> Result<Seal> {
+    #[cfg(feature = "prove-native")]
+    return native::stark_to_snark(identity_p254_seal_bytes);
+    #[cfg(not(feature = "prove-native"))]
+    return docker::stark_to_snark(identity_p254_seal_bytes);
+}
 
 /// Splits the digest in half returning a scalar for each halve.
 pub fn split_digest(d: Digest) -> Result<(Fr, Fr), Error> {
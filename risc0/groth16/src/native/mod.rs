@@ -0,0 +1,253 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module contains a Groth16 prover implemented in Rust, which does not require Docker.
+//!
+//! The prover needs two artifacts of the `stark_verify` circuit, which are expected in the
+//! directory given by `RISC0_GROTH16_DIR`, and `~/.risc0/groth16` by default:
+//!
+//! * `stark_verify_final.zkey`: the proving key, in the snarkjs format, which is the same key
+//!   used by the Docker image.
+//! * `stark_verify.wasm`: the witness calculator, compiled from the circuit with
+//!   `circom --wasm`. It is run in process, so the prover works on any architecture.
+//!
+//! The `groth16_proof/scripts/install_native.sh` script downloads the proving key and builds the
+//! witness calculator into that directory.
+//!
+//! Proving the `stark_verify` circuit requires a large amount of memory, since the proving key
+//! is loaded in full.
+
+mod reduction;
+#[cfg(test)]
+mod tests;
+mod witness;
+mod wtns;
+mod zkey;
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_relations::r1cs::ConstraintMatrices;
+use ark_std::UniformRand;
+
+use self::{reduction::CircomReduction, witness::WitnessCalculator};
+use crate::{to_json, Seal};
+
+/// Groth16 a given seal of an `identity_p254` receipt into a Groth16 [Seal], using the artifacts
+/// in the directory given by `RISC0_GROTH16_DIR`.
+///
+/// The artifacts are loaded once, by the first call, and reused by later ones. If they fail to
+/// load, every call returns that error.
+pub fn stark_to_snark(identity_p254_seal_bytes: &[u8]) -> Result<Seal> {
+    static PROVER: OnceLock<Result<Prover>> = OnceLock::new();
+    match PROVER.get_or_init(|| Prover::from_dir(artifacts_dir()?)) {
+        Ok(prover) => prover.prove(identity_p254_seal_bytes),
+        Err(err) => Err(anyhow!("failed to load the Groth16 artifacts: {err:#}")),
+    }
+}
+
+/// A Groth16 prover for the `stark_verify` circuit.
+///
+/// Loading the proving key is expensive, so a [Prover] should be reused to produce many seals.
+pub struct Prover {
+    witness_calculator: WitnessCalculator,
+    proving_key: ProvingKey<Bn254>,
+    matrices: ConstraintMatrices<Fr>,
+}
+
+impl Prover {
+    /// Construct a [Prover] from the artifacts in the given directory.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let wasm_path = dir.join("stark_verify.wasm");
+        let wasm = std::fs::read(&wasm_path)
+            .with_context(|| format!("failed to read {}", wasm_path.display()))?;
+        let witness_calculator = WitnessCalculator::new(&wasm)
+            .with_context(|| format!("failed to load {}", wasm_path.display()))?;
+
+        let zkey_path = dir.join("stark_verify_final.zkey");
+        tracing::debug!("loading proving key from {}", zkey_path.display());
+        let mut reader = BufReader::new(
+            File::open(&zkey_path)
+                .with_context(|| format!("failed to open {}", zkey_path.display()))?,
+        );
+        let (proving_key, matrices) = zkey::read_zkey(&mut reader)
+            .with_context(|| format!("failed to read {}", zkey_path.display()))?;
+
+        Ok(Self {
+            witness_calculator,
+            proving_key,
+            matrices,
+        })
+    }
+
+    /// Groth16 a given seal of an `identity_p254` receipt into a Groth16 [Seal].
+    pub fn prove(&self, identity_p254_seal_bytes: &[u8]) -> Result<Seal> {
+        tracing::debug!("seal-to-json");
+        let mut seal_json = Vec::new();
+        to_json(identity_p254_seal_bytes, &mut seal_json)?;
+        self.prove_inputs(&serde_json::from_slice(&seal_json)?)
+    }
+
+    /// Produce a Groth16 [Seal] from a witness in the `.wtns` format, e.g. one calculated by
+    /// the witness generator that circom compiles to C++.
+    pub fn prove_witness<R: Read + Seek>(&self, witness: &mut R) -> Result<Seal> {
+        self.prove_assignment(&wtns::read_wtns(witness)?)
+    }
+
+    /// Produce a Groth16 [Seal] from the values of the input signals, in the JSON format
+    /// accepted by circom witness generators.
+    fn prove_inputs(&self, inputs: &serde_json::Value) -> Result<Seal> {
+        tracing::debug!("stark_verify");
+        let inputs = witness::parse_inputs(inputs)?;
+        let witness = self.witness_calculator.calculate(&inputs)?;
+        self.prove_assignment(&witness)
+    }
+
+    fn prove_assignment(&self, witness: &[Fr]) -> Result<Seal> {
+        let num_inputs = self.matrices.num_instance_variables;
+        ensure!(
+            witness.len() == num_inputs + self.matrices.num_witness_variables,
+            "witness has {} values, but the circuit has {} variables",
+            witness.len(),
+            num_inputs + self.matrices.num_witness_variables
+        );
+
+        tracing::debug!("groth16 prove");
+        let mut rng = rand::thread_rng();
+        let proof = Groth16::<Bn254, CircomReduction>::create_proof_with_reduction_and_matrices(
+            &self.proving_key,
+            Fr::rand(&mut rng),
+            Fr::rand(&mut rng),
+            &self.matrices,
+            num_inputs,
+            self.matrices.num_constraints,
+            witness,
+        )
+        .map_err(|err| anyhow!("failed to create proof: {err}"))?;
+
+        // Check the proof against the verifying key of the proving key, to catch a mismatched
+        // witness calculator or a malformed proving key before the seal is used.
+        let pvk = ark_groth16::prepare_verifying_key(&self.proving_key.vk);
+        let valid = Groth16::<Bn254>::verify_proof(&pvk, &proof, &witness[1..num_inputs])
+            .map_err(|err| anyhow!("failed to verify proof: {err}"))?;
+        ensure!(valid, "produced proof does not verify");
+
+        Ok(to_seal(&proof))
+    }
+}
+
+fn artifacts_dir() -> Result<PathBuf> {
+    if let Ok(dir) = std::env::var("RISC0_GROTH16_DIR") {
+        return Ok(dir.into());
+    }
+    let home = std::env::var("HOME")
+        .map_err(|_| anyhow!("set RISC0_GROTH16_DIR to the directory of the Groth16 artifacts"))?;
+    Ok(Path::new(&home).join(".risc0").join("groth16"))
+}
+
+/// Encode a proof as a [Seal], with each coordinate in big-endian format.
+fn to_seal(proof: &Proof<Bn254>) -> Seal {
+    fn fq(value: &Fq) -> Vec<u8> {
+        value.into_bigint().to_bytes_be()
+    }
+    fn g1(point: &G1Affine) -> Vec<Vec<u8>> {
+        vec![fq(&point.x), fq(&point.y)]
+    }
+    fn g2(point: &G2Affine) -> Vec<Vec<Vec<u8>>> {
+        vec![
+            vec![fq(&point.x.c1), fq(&point.x.c0)],
+            vec![fq(&point.y.c1), fq(&point.y.c0)],
+        ]
+    }
+
+    Seal {
+        a: g1(&proof.a),
+        b: g2(&proof.b),
+        c: g1(&proof.c),
+    }
+}
+
+/// Read the section table of a file in the iden3 binary format used by `.zkey` and `.wtns`
+/// files, returning the offset and size of each section by type.
+fn read_sections<R: Read + Seek>(
+    reader: &mut R,
+    magic: &[u8; 4],
+) -> Result<BTreeMap<u32, (u64, u64)>> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    ensure!(
+        &buf == magic,
+        "invalid file type: expected {}",
+        String::from_utf8_lossy(magic)
+    );
+    let _version = read_u32(reader)?;
+    let num_sections = read_u32(reader)?;
+
+    let mut sections = BTreeMap::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(reader)?;
+        let size = read_u64(reader)?;
+        let offset = reader.stream_position()?;
+        sections.insert(section_type, (offset, size));
+        reader.seek(SeekFrom::Current(size.try_into()?))?;
+    }
+    Ok(sections)
+}
+
+/// Seek to the start of the section with the given type.
+fn seek_section<R: Seek>(
+    reader: &mut R,
+    sections: &BTreeMap<u32, (u64, u64)>,
+    section_type: u32,
+) -> Result<()> {
+    let (offset, _) = sections
+        .get(&section_type)
+        .ok_or_else(|| anyhow!("missing section {section_type}"))?;
+    reader.seek(SeekFrom::Start(*offset))?;
+    Ok(())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+/// Read a field modulus, checking it is equal to the modulus of `F`.
+fn check_modulus<F: PrimeField, R: Read>(reader: &mut R) -> Result<()> {
+    let size = read_u32(reader)? as usize;
+    let mut modulus = vec![0u8; size];
+    reader.read_exact(&mut modulus)?;
+    ensure!(
+        modulus == F::MODULUS.to_bytes_le(),
+        "unexpected field modulus"
+    );
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The R1CS to QAP reduction used by snarkjs and rapidsnark.
+//!
+//! Proving keys produced by snarkjs encode the H query over the odd powers of a root of unity of
+//! twice the domain size, rather than over the evaluations of the vanishing polynomial as in the
+//! reduction used by arkworks. Proofs for those keys must compute H over the same coset.
+
+use ark_ff::PrimeField;
+use ark_groth16::r1cs_to_qap::{evaluate_constraint, LibsnarkReduction, R1CSToQAP};
+use ark_poly::EvaluationDomain;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystemRef, SynthesisError};
+
+pub(crate) struct CircomReduction;
+
+impl R1CSToQAP for CircomReduction {
+    fn instance_map_with_evaluation<F: PrimeField, D: EvaluationDomain<F>>(
+        cs: ConstraintSystemRef<F>,
+        t: &F,
+    ) -> Result<(Vec<F>, Vec<F>, Vec<F>, F, usize, usize), SynthesisError> {
+        LibsnarkReduction::instance_map_with_evaluation::<F, D>(cs, t)
+    }
+
+    fn witness_map_from_matrices<F: PrimeField, D: EvaluationDomain<F>>(
+        matrices: &ConstraintMatrices<F>,
+        num_inputs: usize,
+        num_constraints: usize,
+        full_assignment: &[F],
+    ) -> Result<Vec<F>, SynthesisError> {
+        let domain =
+            D::new(num_constraints + num_inputs).ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let domain_size = domain.size();
+
+        let mut a = vec![F::zero(); domain_size];
+        let mut b = vec![F::zero(); domain_size];
+        for (i, (at_i, bt_i)) in matrices.a.iter().zip(matrices.b.iter()).enumerate() {
+            a[i] = evaluate_constraint(at_i, full_assignment);
+            b[i] = evaluate_constraint(bt_i, full_assignment);
+        }
+        // The constraints for the public inputs, which snarkjs appends after the constraints of
+        // the circuit.
+        a[num_constraints..num_constraints + num_inputs]
+            .clone_from_slice(&full_assignment[..num_inputs]);
+
+        let mut c = vec![F::zero(); domain_size];
+        for (c_i, (a_i, b_i)) in c[..num_constraints].iter_mut().zip(a.iter().zip(b.iter())) {
+            *c_i = *a_i * b_i;
+        }
+
+        // Evaluate a, b and c over the coset of odd powers of a root of unity of twice the domain
+        // size, on which the vanishing polynomial of the domain is constant.
+        let root_of_unity = D::new(2 * domain_size)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?
+            .element(1);
+        let to_coset = |values: &mut Vec<F>| {
+            domain.ifft_in_place(values);
+            D::distribute_powers_and_mul_by_const(values, root_of_unity, F::one());
+            domain.fft_in_place(values);
+        };
+        to_coset(&mut a);
+        to_coset(&mut b);
+        to_coset(&mut c);
+
+        let mut h = domain.mul_polynomials_in_evaluation_domain(&a, &b);
+        for (h_i, c_i) in h.iter_mut().zip(c) {
+            *h_i -= c_i;
+        }
+        Ok(h)
+    }
+
+    fn h_query_scalars<F: PrimeField, D: EvaluationDomain<F>>(
+        max_power: usize,
+        t: F,
+        zt: F,
+        delta_inverse: F,
+    ) -> Result<Vec<F>, SynthesisError> {
+        LibsnarkReduction::h_query_scalars::<F, D>(max_power, t, zt, delta_inverse)
+    }
+}
@@ -0,0 +1,34 @@
+#!/bin/bash
+
+set -eoux
+
+# Generates the test fixtures for the multiplier circuit in multiplier.circom:
+#
+# * multiplier.zkey: a Groth16 proving key from a snarkjs setup.
+# * multiplier.wasm: the witness calculator compiled by `circom --wasm`.
+# * multiplier.wtns: the witness for a = 3 and b = 11, calculated by snarkjs.
+#
+# We presume circom and snarkjs are installed system wide, at the revisions used by
+# docker/prover.Dockerfile. This script is meant to be run from the `testdata` folder.
+
+BUILD_DIR=$(mktemp -d)
+trap 'rm -rf $BUILD_DIR' EXIT
+
+circom --r1cs --wasm multiplier.circom -o $BUILD_DIR
+cp $BUILD_DIR/multiplier_js/multiplier.wasm multiplier.wasm
+
+# A small powers of tau ceremony, with a single contribution
+snarkjs powersoftau new bn128 4 $BUILD_DIR/pot_0000.ptau
+snarkjs powersoftau contribute $BUILD_DIR/pot_0000.ptau $BUILD_DIR/pot_0001.ptau \
+    --name="Test contribution" -e="risc0-groth16 test"
+snarkjs powersoftau prepare phase2 $BUILD_DIR/pot_0001.ptau $BUILD_DIR/pot_final.ptau
+
+# The circuit specific setup, finalized as in scripts/run_ceremony.sh
+snarkjs groth16 setup $BUILD_DIR/multiplier.r1cs $BUILD_DIR/pot_final.ptau $BUILD_DIR/multiplier_0000.zkey
+snarkjs zkey contribute $BUILD_DIR/multiplier_0000.zkey $BUILD_DIR/multiplier_0001.zkey \
+    --name="Test contribution" -e="risc0-groth16 test"
+snarkjs zkey beacon $BUILD_DIR/multiplier_0001.zkey multiplier.zkey \
+    0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f 10 -n="Final Beacon phase2"
+
+echo '{ "a": "3", "b": "11" }' > $BUILD_DIR/input.json
+snarkjs wtns calculate multiplier.wasm $BUILD_DIR/input.json multiplier.wtns
@@ -0,0 +1,205 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests against the multiplier circuit in `testdata`, whose fixtures are generated with circom
+//! and snarkjs by `testdata/generate.sh`.
+
+use std::io::Cursor;
+
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_groth16::{
+    r1cs_to_qap::{evaluate_constraint, R1CSToQAP},
+    Groth16, Proof,
+};
+use ark_poly::{EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial};
+use serde_json::json;
+
+use super::{
+    reduction::CircomReduction, to_seal, witness::parse_inputs, witness::WitnessCalculator,
+    wtns::read_wtns, zkey::read_zkey, Prover,
+};
+use crate::{g1_from_bytes, g2_from_bytes, Seal};
+
+const ZKEY: &[u8] = include_bytes!("testdata/multiplier.zkey");
+const WTNS: &[u8] = include_bytes!("testdata/multiplier.wtns");
+const WASM: &[u8] = include_bytes!("testdata/multiplier.wasm");
+
+/// The signals for a = 3 and b = 11: the constant one, c, a and b.
+fn witness() -> Vec<Fr> {
+    [1u64, 33, 3, 11].map(Fr::from).to_vec()
+}
+
+fn prover() -> Prover {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("stark_verify_final.zkey"), ZKEY).unwrap();
+    std::fs::write(dir.path().join("stark_verify.wasm"), WASM).unwrap();
+    Prover::from_dir(dir.path()).unwrap()
+}
+
+fn verify(seal: &Seal, public_inputs: &[Fr]) -> bool {
+    let proof = Proof::<Bn254> {
+        a: g1_from_bytes(&seal.a).unwrap(),
+        b: g2_from_bytes(&seal.b).unwrap(),
+        c: g1_from_bytes(&seal.c).unwrap(),
+    };
+    let (proving_key, _) = read_zkey(&mut Cursor::new(ZKEY)).unwrap();
+    let pvk = ark_groth16::prepare_verifying_key(&proving_key.vk);
+    Groth16::<Bn254>::verify_proof(&pvk, &proof, public_inputs).unwrap()
+}
+
+#[test]
+fn seal_round_trip() {
+    let proof = Proof::<Bn254> {
+        a: G1Affine::generator(),
+        b: G2Affine::generator(),
+        c: (G1Affine::generator() + G1Affine::generator()).into(),
+    };
+    let seal = Seal::from_vec(&to_seal(&proof).to_vec()).unwrap();
+    assert_eq!(g1_from_bytes(&seal.a).unwrap(), proof.a);
+    assert_eq!(g2_from_bytes(&seal.b).unwrap(), proof.b);
+    assert_eq!(g1_from_bytes(&seal.c).unwrap(), proof.c);
+}
+
+#[test]
+fn zkey() {
+    let (proving_key, matrices) = read_zkey(&mut Cursor::new(ZKEY)).unwrap();
+
+    // snarkjs always uses a gamma of one, and beta and delta must match across both groups.
+    let vk = &proving_key.vk;
+    let same_scalar = |p1: G1Affine, p2: G2Affine| {
+        Bn254::pairing(p1, G2Affine::generator()) == Bn254::pairing(G1Affine::generator(), p2)
+    };
+    assert_eq!(vk.gamma_g2, G2Affine::generator());
+    assert!(same_scalar(proving_key.beta_g1, vk.beta_g2));
+    assert!(same_scalar(proving_key.delta_g1, vk.delta_g2));
+    assert!(!vk.alpha_g1.is_zero());
+
+    assert_eq!(vk.gamma_abc_g1.len(), 2);
+    assert_eq!(proving_key.a_query.len(), 4);
+    assert_eq!(proving_key.b_g1_query.len(), 4);
+    assert_eq!(proving_key.b_g2_query.len(), 4);
+    assert_eq!(proving_key.l_query.len(), 2);
+    assert_eq!(proving_key.h_query.len(), 4);
+    // Only b has a non-zero B polynomial.
+    assert!(proving_key.b_g1_query[..3].iter().all(|p| p.is_zero()));
+    assert!(!proving_key.b_g1_query[3].is_zero());
+    assert!(proving_key
+        .a_query
+        .iter()
+        .chain(&proving_key.h_query)
+        .all(|p| p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve()));
+
+    // The constraint a * b = c, without the constraints for the public signals.
+    assert_eq!(matrices.num_instance_variables, 2);
+    assert_eq!(matrices.num_witness_variables, 2);
+    assert_eq!(matrices.num_constraints, 1);
+    let satisfied = |assignment: &[Fr]| {
+        evaluate_constraint(&matrices.a[0], assignment)
+            * evaluate_constraint(&matrices.b[0], assignment)
+            == evaluate_constraint(&matrices.c[0], assignment)
+    };
+    let mut assignment = witness();
+    assert!(satisfied(&assignment));
+    assignment[1] = Fr::from(34u64);
+    assert!(!satisfied(&assignment));
+
+    // A truncated key, and a file of another type.
+    assert!(read_zkey(&mut Cursor::new(&ZKEY[..ZKEY.len() - 1])).is_err());
+    assert!(read_zkey(&mut Cursor::new(WTNS)).is_err());
+}
+
+#[test]
+fn wtns() {
+    assert_eq!(read_wtns(&mut Cursor::new(WTNS)).unwrap(), witness());
+    assert!(read_wtns(&mut Cursor::new(ZKEY)).is_err());
+}
+
+#[test]
+fn witness_calculator() {
+    let calculator = WitnessCalculator::new(WASM).unwrap();
+    let inputs = parse_inputs(&json!({ "a": "3", "b": ["11"] })).unwrap();
+    assert_eq!(calculator.calculate(&inputs).unwrap(), witness());
+
+    let inputs = parse_inputs(&json!({ "a": "3", "d": "11" })).unwrap();
+    let err = calculator.calculate(&inputs).unwrap_err();
+    assert!(
+        err.to_string().contains("input signal d not found"),
+        "{err}"
+    );
+
+    let inputs = parse_inputs(&json!({ "a": "3" })).unwrap();
+    assert!(calculator.calculate(&inputs).is_err());
+}
+
+#[test]
+fn circom_reduction() {
+    let (_, matrices) = read_zkey(&mut Cursor::new(ZKEY)).unwrap();
+    let h = CircomReduction::witness_map_from_matrices::<Fr, GeneralEvaluationDomain<Fr>>(
+        &matrices,
+        matrices.num_instance_variables,
+        matrices.num_constraints,
+        &witness(),
+    )
+    .unwrap();
+
+    // Interpolate A, B and C over the domain of the constraints, followed by the ones snarkjs
+    // adds for the public signals, and evaluate A * B - C over the odd powers of a root of unity
+    // of twice its size.
+    let domain = GeneralEvaluationDomain::<Fr>::new(4).unwrap();
+    let row = |i: usize| match i {
+        0 => (
+            evaluate_constraint(&matrices.a[0], &witness()),
+            evaluate_constraint(&matrices.b[0], &witness()),
+            evaluate_constraint(&matrices.c[0], &witness()),
+        ),
+        1 | 2 => (witness()[i - 1], Fr::from(0u64), Fr::from(0u64)),
+        _ => (Fr::from(0u64), Fr::from(0u64), Fr::from(0u64)),
+    };
+    let rows: Vec<_> = (0..4).map(row).collect();
+    let interpolate = |column: fn(&(Fr, Fr, Fr)) -> Fr| {
+        Evaluations::from_vec_and_domain(rows.iter().map(column).collect(), domain).interpolate()
+    };
+    let (a, b, c) = (
+        interpolate(|row| row.0),
+        interpolate(|row| row.1),
+        interpolate(|row| row.2),
+    );
+    let coset = GeneralEvaluationDomain::<Fr>::new(8).unwrap();
+    let expected: Vec<_> = (0..4)
+        .map(|i| {
+            let x = coset.element(2 * i + 1);
+            a.evaluate(&x) * b.evaluate(&x) - c.evaluate(&x)
+        })
+        .collect();
+    assert_eq!(h, expected);
+}
+
+#[test]
+fn prove_and_verify() {
+    let prover = prover();
+    let seal = prover
+        .prove_inputs(&json!({ "a": "3", "b": "11" }))
+        .unwrap();
+    assert!(verify(&seal, &[Fr::from(33u64)]));
+    assert!(!verify(&seal, &[Fr::from(34u64)]));
+
+    let seal = prover.prove_witness(&mut Cursor::new(WTNS)).unwrap();
+    assert!(verify(&seal, &[Fr::from(33u64)]));
+
+    // A witness that does not satisfy the constraint produces a proof that does not verify.
+    let mut witness = witness();
+    witness[1] = Fr::from(34u64);
+    assert!(prover.prove_assignment(&witness).is_err());
+}
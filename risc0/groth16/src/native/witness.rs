@@ -0,0 +1,221 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Witness calculator for circuits compiled to WebAssembly with `circom --wasm`.
+//!
+//! The module is run by an interpreter, so the witness is calculated in process on any
+//! architecture. The calculator exchanges field elements with the host one u32 digit at a time,
+//! least significant digit first, through the shared memory of the module.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use ark_bn254::Fr;
+use ark_ff::{BigInt, PrimeField};
+use num_bigint::BigUint;
+use serde_json::Value;
+use wasmi::{core::Trap, Engine, Linker, Module, Store, TypedFunc};
+
+/// The number of u32 digits of an element of [Fr].
+const FR_WORDS: usize = 8;
+
+pub(crate) struct WitnessCalculator {
+    engine: Engine,
+    module: Module,
+}
+
+/// The functions exported by a witness calculator.
+struct Exports {
+    get_field_num_len32: TypedFunc<(), i32>,
+    get_raw_prime: TypedFunc<(), ()>,
+    read_shared_rw_memory: TypedFunc<i32, i32>,
+    write_shared_rw_memory: TypedFunc<(i32, i32), ()>,
+    init: TypedFunc<i32, ()>,
+    get_input_signal_size: TypedFunc<(i32, i32), i32>,
+    set_input_signal: TypedFunc<(i32, i32, i32), ()>,
+    get_input_size: TypedFunc<(), i32>,
+    get_witness_size: TypedFunc<(), i32>,
+    get_witness: TypedFunc<i32, ()>,
+}
+
+impl WitnessCalculator {
+    /// Load a witness calculator from the bytes of its WebAssembly module.
+    pub(crate) fn new(wasm: &[u8]) -> Result<Self> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm)?;
+        Ok(Self { engine, module })
+    }
+
+    /// Calculate the values of all signals, starting with the constant one, given the values of
+    /// the input signals by name.
+    pub(crate) fn calculate(&self, inputs: &[(String, Vec<Fr>)]) -> Result<Vec<Fr>> {
+        let mut store = Store::new(&self.engine, ());
+        let mut linker = Linker::<()>::new(&self.engine);
+        linker.func_wrap(
+            "runtime",
+            "exceptionHandler",
+            |code: i32| -> Result<(), Trap> {
+                Err(Trap::new(format!(
+                    "witness calculation failed: {}",
+                    exception_message(code)
+                )))
+            },
+        )?;
+        for name in [
+            "printErrorMessage",
+            "writeBufferMessage",
+            "showSharedRWMemory",
+        ] {
+            linker.func_wrap("runtime", name, || {})?;
+        }
+        let instance = linker
+            .instantiate(&mut store, &self.module)?
+            .start(&mut store)?;
+        let exports = Exports {
+            get_field_num_len32: instance.get_typed_func(&store, "getFieldNumLen32")?,
+            get_raw_prime: instance.get_typed_func(&store, "getRawPrime")?,
+            read_shared_rw_memory: instance.get_typed_func(&store, "readSharedRWMemory")?,
+            write_shared_rw_memory: instance.get_typed_func(&store, "writeSharedRWMemory")?,
+            init: instance.get_typed_func(&store, "init")?,
+            get_input_signal_size: instance.get_typed_func(&store, "getInputSignalSize")?,
+            set_input_signal: instance.get_typed_func(&store, "setInputSignal")?,
+            get_input_size: instance.get_typed_func(&store, "getInputSize")?,
+            get_witness_size: instance.get_typed_func(&store, "getWitnessSize")?,
+            get_witness: instance.get_typed_func(&store, "getWitness")?,
+        };
+
+        ensure!(
+            exports.get_field_num_len32.call(&mut store, ())? as usize == FR_WORDS,
+            "witness calculator is not for the BN254 scalar field"
+        );
+        exports.get_raw_prime.call(&mut store, ())?;
+        ensure!(
+            exports.read(&mut store)? == Fr::MODULUS,
+            "witness calculator is not for the BN254 scalar field"
+        );
+
+        // Setting the last input signal runs the calculation.
+        exports.init.call(&mut store, 1)?;
+        let mut num_inputs = 0;
+        for (name, values) in inputs {
+            let (hash_msb, hash_lsb) = fnv1a(name);
+            let size = exports
+                .get_input_signal_size
+                .call(&mut store, (hash_msb, hash_lsb))?;
+            ensure!(size >= 0, "input signal {name} not found");
+            ensure!(
+                values.len() == size as usize,
+                "input signal {name} has {size} values, but {} were given",
+                values.len()
+            );
+            for (pos, value) in values.iter().enumerate() {
+                exports.write(&mut store, &value.into_bigint())?;
+                exports
+                    .set_input_signal
+                    .call(&mut store, (hash_msb, hash_lsb, pos as i32))
+                    .with_context(|| format!("failed to set input signal {name}[{pos}]"))?;
+            }
+            num_inputs += values.len();
+        }
+        let expected_inputs = exports.get_input_size.call(&mut store, ())? as usize;
+        ensure!(
+            num_inputs == expected_inputs,
+            "{num_inputs} input values were given, but the circuit has {expected_inputs}"
+        );
+
+        let witness_size = exports.get_witness_size.call(&mut store, ())?;
+        (0..witness_size)
+            .map(|i| {
+                exports.get_witness.call(&mut store, i)?;
+                Fr::from_bigint(exports.read(&mut store)?)
+                    .ok_or_else(|| anyhow!("witness value {i} is not reduced"))
+            })
+            .collect()
+    }
+}
+
+impl Exports {
+    /// Read a value from the shared memory.
+    fn read(&self, store: &mut Store<()>) -> Result<BigInt<4>> {
+        let mut limbs = [0u64; 4];
+        for i in 0..FR_WORDS {
+            let word = self.read_shared_rw_memory.call(&mut *store, i as i32)? as u32;
+            limbs[i / 2] |= (word as u64) << (32 * (i % 2));
+        }
+        Ok(BigInt(limbs))
+    }
+
+    /// Write a value to the shared memory.
+    fn write(&self, store: &mut Store<()>, value: &BigInt<4>) -> Result<()> {
+        for i in 0..FR_WORDS {
+            let word = (value.0[i / 2] >> (32 * (i % 2))) as u32;
+            self.write_shared_rw_memory
+                .call(&mut *store, (i as i32, word as i32))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the values of the input signals from the JSON accepted by circom witness generators,
+/// i.e. an object of decimal strings or nested arrays of them.
+pub(crate) fn parse_inputs(inputs: &Value) -> Result<Vec<(String, Vec<Fr>)>> {
+    fn flatten(value: &Value, values: &mut Vec<Fr>) -> Result<()> {
+        match value {
+            Value::Array(items) => items.iter().try_for_each(|item| flatten(item, values)),
+            Value::String(value) => {
+                values.push(Fr::from(value.parse::<BigUint>()?));
+                Ok(())
+            }
+            Value::Number(value) => {
+                let value = value
+                    .as_u64()
+                    .with_context(|| format!("invalid input value: {value}"))?;
+                values.push(Fr::from(value));
+                Ok(())
+            }
+            _ => bail!("invalid input value: {value}"),
+        }
+    }
+
+    let inputs = inputs
+        .as_object()
+        .context("input signals must be a JSON object")?;
+    inputs
+        .iter()
+        .map(|(name, value)| {
+            let mut values = Vec::new();
+            flatten(value, &mut values)?;
+            Ok((name.clone(), values))
+        })
+        .collect()
+}
+
+/// The 64-bit FNV-1a hash of a signal name, split into its most and least significant halves,
+/// by which the calculator looks up input signals.
+fn fnv1a(name: &str) -> (i32, i32) {
+    let hash = name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    ((hash >> 32) as i32, hash as i32)
+}
+
+fn exception_message(code: i32) -> &'static str {
+    match code {
+        1 => "signal not found",
+        2 => "too many signals set",
+        3 => "signal already set",
+        4 => "assert failed",
+        5 => "not enough memory",
+        6 => "input signal array access exceeds the size",
+        _ => "unknown error",
+    }
+}
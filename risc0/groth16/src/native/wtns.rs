@@ -0,0 +1,45 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reader for witnesses in the `.wtns` format written by circom witness generators.
+
+use std::io::{Read, Seek};
+
+use anyhow::Result;
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+
+use super::{check_modulus, read_sections, read_u32, seek_section};
+
+const HEADER: u32 = 1;
+const WITNESS: u32 = 2;
+
+/// Read the values of all signals of a witness, starting with the constant one.
+pub(crate) fn read_wtns<R: Read + Seek>(reader: &mut R) -> Result<Vec<Fr>> {
+    let sections = read_sections(reader, b"wtns")?;
+
+    seek_section(reader, &sections, HEADER)?;
+    check_modulus::<Fr, _>(reader)?;
+    let num_values = read_u32(reader)?;
+
+    // Unlike the proving key, the values of the witness are not in Montgomery form.
+    seek_section(reader, &sections, WITNESS)?;
+    let mut buf = [0u8; 32];
+    (0..num_values)
+        .map(|_| -> Result<Fr> {
+            reader.read_exact(&mut buf)?;
+            Ok(Fr::from_le_bytes_mod_order(&buf))
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reader for Groth16 proving keys in the snarkjs `.zkey` format.
+//!
+//! Field elements are stored in Montgomery form, and the constraint coefficients are stored
+//! multiplied by the Montgomery factor twice.
+
+use std::io::{Read, Seek};
+
+use anyhow::{ensure, Result};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInt, PrimeField, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{ConstraintMatrices, Matrix};
+
+use super::{check_modulus, read_sections, read_u32, read_u64, seek_section};
+
+const HEADER: u32 = 1;
+const GROTH16_HEADER: u32 = 2;
+const IC: u32 = 3;
+const COEFFS: u32 = 4;
+const POINTS_A: u32 = 5;
+const POINTS_B1: u32 = 6;
+const POINTS_B2: u32 = 7;
+const POINTS_C: u32 = 8;
+const POINTS_H: u32 = 9;
+
+const GROTH16_PROTOCOL: u32 = 1;
+
+struct Header {
+    n_vars: usize,
+    n_public: usize,
+    domain_size: usize,
+    alpha_g1: G1Affine,
+    beta_g1: G1Affine,
+    beta_g2: G2Affine,
+    gamma_g2: G2Affine,
+    delta_g1: G1Affine,
+    delta_g2: G2Affine,
+}
+
+/// Read a proving key and the constraint matrices of its circuit from a `.zkey` file.
+pub(crate) fn read_zkey<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<(ProvingKey<Bn254>, ConstraintMatrices<Fr>)> {
+    let sections = read_sections(reader, b"zkey")?;
+
+    seek_section(reader, &sections, HEADER)?;
+    ensure!(
+        read_u32(reader)? == GROTH16_PROTOCOL,
+        "only Groth16 proving keys are supported"
+    );
+
+    seek_section(reader, &sections, GROTH16_HEADER)?;
+    let header = read_header(reader)?;
+
+    seek_section(reader, &sections, IC)?;
+    let gamma_abc_g1 = read_points(reader, header.n_public + 1, read_g1)?;
+    seek_section(reader, &sections, POINTS_A)?;
+    let a_query = read_points(reader, header.n_vars, read_g1)?;
+    seek_section(reader, &sections, POINTS_B1)?;
+    let b_g1_query = read_points(reader, header.n_vars, read_g1)?;
+    seek_section(reader, &sections, POINTS_B2)?;
+    let b_g2_query = read_points(reader, header.n_vars, read_g2)?;
+    seek_section(reader, &sections, POINTS_C)?;
+    let l_query = read_points(reader, header.n_vars - header.n_public - 1, read_g1)?;
+    seek_section(reader, &sections, POINTS_H)?;
+    let h_query = read_points(reader, header.domain_size, read_g1)?;
+
+    seek_section(reader, &sections, COEFFS)?;
+    let matrices = read_coeffs(reader, &header)?;
+
+    let proving_key = ProvingKey {
+        vk: VerifyingKey {
+            alpha_g1: header.alpha_g1,
+            beta_g2: header.beta_g2,
+            gamma_g2: header.gamma_g2,
+            delta_g2: header.delta_g2,
+            gamma_abc_g1,
+        },
+        beta_g1: header.beta_g1,
+        delta_g1: header.delta_g1,
+        a_query,
+        b_g1_query,
+        b_g2_query,
+        h_query,
+        l_query,
+    };
+    Ok((proving_key, matrices))
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header> {
+    check_modulus::<Fq, _>(reader)?;
+    check_modulus::<Fr, _>(reader)?;
+    Ok(Header {
+        n_vars: read_u32(reader)? as usize,
+        n_public: read_u32(reader)? as usize,
+        domain_size: read_u32(reader)? as usize,
+        alpha_g1: read_g1(reader)?,
+        beta_g1: read_g1(reader)?,
+        beta_g2: read_g2(reader)?,
+        gamma_g2: read_g2(reader)?,
+        delta_g1: read_g1(reader)?,
+        delta_g2: read_g2(reader)?,
+    })
+}
+
+/// Read the coefficients of the A and B matrices. The C matrix is not needed to prove, since
+/// its evaluation is the product of the evaluations of A and B.
+fn read_coeffs<R: Read>(reader: &mut R, header: &Header) -> Result<ConstraintMatrices<Fr>> {
+    let num_coeffs = read_u32(reader)?;
+    let mut matrices: [Matrix<Fr>; 2] = [vec![], vec![]];
+    for _ in 0..num_coeffs {
+        let matrix = read_u32(reader)? as usize;
+        let constraint = read_u32(reader)? as usize;
+        let signal = read_u32(reader)? as usize;
+        let value = Fr::new_unchecked(Fr::new_unchecked(read_bigint(reader)?).into_bigint());
+
+        ensure!(matrix < 2, "invalid matrix index {matrix}");
+        let rows = &mut matrices[matrix];
+        if rows.len() <= constraint {
+            rows.resize(constraint + 1, vec![]);
+        }
+        rows[constraint].push((value, signal));
+    }
+
+    // snarkjs appends a constraint for each public input (and the constant one) after the
+    // constraints of the circuit. These are added back by the reduction.
+    let [mut a, mut b] = matrices;
+    let num_constraints = a.len().saturating_sub(header.n_public + 1);
+    a.resize(num_constraints, vec![]);
+    b.resize(num_constraints, vec![]);
+
+    Ok(ConstraintMatrices {
+        num_instance_variables: header.n_public + 1,
+        num_witness_variables: header.n_vars - header.n_public - 1,
+        num_constraints,
+        a_num_non_zero: a.iter().map(Vec::len).sum(),
+        b_num_non_zero: b.iter().map(Vec::len).sum(),
+        c_num_non_zero: 0,
+        a,
+        b,
+        c: vec![],
+    })
+}
+
+fn read_points<R: Read, T>(
+    reader: &mut R,
+    count: usize,
+    read: impl Fn(&mut R) -> Result<T>,
+) -> Result<Vec<T>> {
+    (0..count).map(|_| read(reader)).collect()
+}
+
+fn read_bigint<R: Read>(reader: &mut R) -> Result<BigInt<4>> {
+    let mut limbs = [0u64; 4];
+    for limb in limbs.iter_mut() {
+        *limb = read_u64(reader)?;
+    }
+    Ok(BigInt(limbs))
+}
+
+fn read_fq<R: Read>(reader: &mut R) -> Result<Fq> {
+    Ok(Fq::new_unchecked(read_bigint(reader)?))
+}
+
+fn read_fq2<R: Read>(reader: &mut R) -> Result<Fq2> {
+    Ok(Fq2::new(read_fq(reader)?, read_fq(reader)?))
+}
+
+// Points are not checked to be on the curve when they are read, since that is expensive for a
+// proving key with millions of points. Instead, each proof is verified after it is produced.
+fn read_g1<R: Read>(reader: &mut R) -> Result<G1Affine> {
+    let (x, y) = (read_fq(reader)?, read_fq(reader)?);
+    Ok(if x.is_zero() && y.is_zero() {
+        G1Affine::identity()
+    } else {
+        G1Affine::new_unchecked(x, y)
+    })
+}
+
+fn read_g2<R: Read>(reader: &mut R) -> Result<G2Affine> {
+    let (x, y) = (read_fq2(reader)?, read_fq2(reader)?);
+    Ok(if x.is_zero() && y.is_zero() {
+        G2Affine::identity()
+    } else {
+        G2Affine::new_unchecked(x, y)
+    })
+}
@@ -156,6 +156,27 @@ impl Verifier {
     }
 }
 
+/// Verifies a Groth16 proof of the given public inputs against the given verifying key.
+///
+/// In the zkVM guest, the curve arithmetic and pairing are accelerated with the bigint2 circuit,
+/// and the proof is checked without constructing a [Verifier]. Elsewhere, this is equivalent to
+/// calling [Verifier::verify] on a new [Verifier].
+pub fn verify(
+    seal: &Seal,
+    public_inputs: &[Fr],
+    verifying_key: &VerifyingKey,
+) -> Result<(), Error> {
+    #[cfg(target_os = "zkvm")]
+    {
+        crate::bn254::verify(seal, public_inputs, verifying_key)
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    {
+        Verifier::new(seal, public_inputs, verifying_key)?.verify()
+    }
+}
+
 /// Verifying key for Groth16 proofs.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Fr(#[serde(with = "serde_ark")] pub(crate) ark_bn254::Fr);
@@ -16,8 +16,8 @@ use std::{fs, io, path::PathBuf, rc::Rc};
 
 use clap::{Args, Parser, ValueEnum};
 use risc0_zkvm::{
-    compute_image_id, get_prover_server, ApiServer, ExecutorEnv, ExecutorImpl, ProverOpts,
-    ProverServer, VerifierContext,
+    compute_image_id, get_prover_server, ApiServer, Coordinator, ExecutorEnv, ExecutorImpl,
+    ProverOpts, ProverServer, VerifierContext, VerifierContextConfig,
 };
 
 /// Runs a RISC-V ELF binary within the RISC Zero ZKVM.
@@ -64,6 +64,39 @@ struct Cli {
     #[arg(long, env = "RISC0_PPROF_OUT")]
     pprof_out: Option<PathBuf>,
 
+    /// Wait for GDB to connect on this address (e.g. `localhost:9000`) and
+    /// let it control the execution of the guest.
+    #[arg(long)]
+    gdb: Option<String>,
+
+    /// Print a report of where the guest spent its cycles, instead of proving.
+    ///
+    /// The report needs the symbols of the guest, so it is only available
+    /// with `--elf`.
+    #[arg(long, value_enum, conflicts_with = "image")]
+    cycle_report: Option<ReportFormat>,
+
+    /// The number of functions listed in the text cycle report.
+    #[arg(long, default_value_t = 20)]
+    cycle_report_top: usize,
+
+    /// Record every syscall made by the guest, along with the host's reply,
+    /// to this file.
+    #[arg(long, conflicts_with = "replay_io")]
+    record_io: Option<PathBuf>,
+
+    /// Serve the replies to syscalls from a file written by `--record-io`,
+    /// re-executing the recorded guest without any host input.
+    #[arg(long)]
+    replay_io: Option<PathBuf>,
+
+    /// A JSON file with the verifier context configuration used while
+    /// proving, e.g. to accept a non-default max po2.
+    ///
+    /// Uses the default verifier context if not provided.
+    #[arg(long)]
+    verifier_context: Option<PathBuf>,
+
     /// The receipt kind produced by the r0vm prover
     #[arg(long, value_enum, default_value_t = ReceiptKind::Composite)]
     receipt_kind: ReceiptKind,
@@ -71,6 +104,36 @@ struct Cli {
     /// Compute the image_id for the specified ELF
     #[arg(long)]
     id: bool,
+
+    /// The host to connect to for requests when serving with `--port`.
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Keep serving requests with `--port` until the client goes away, as a
+    /// worker for a coordinator started with `--listen`.
+    #[arg(long, requires = "port")]
+    worker: bool,
+
+    /// Distribute proving across workers that connect to this address (e.g.
+    /// `0.0.0.0:9000`), instead of proving in this process.
+    ///
+    /// Workers are started with `r0vm --port <port> --host <host> --worker`.
+    #[arg(long, conflicts_with = "port")]
+    listen: Option<String>,
+
+    /// The number of workers to start on this host when distributing proving
+    /// with `--listen`.
+    #[arg(long, default_value_t = 0, requires = "listen")]
+    local_workers: usize,
+
+    /// The number of requests handed out to workers at once when distributing
+    /// proving with `--listen`. Defaults to the number of local workers.
+    ///
+    /// Any more jobs than local workers are expected to be taken by workers
+    /// on other hosts, so proving keeps waiting for them even once all of the
+    /// local workers have exited.
+    #[arg(long, requires = "listen")]
+    jobs: Option<usize>,
 }
 
 #[derive(Args)]
@@ -96,6 +159,14 @@ enum HashFn {
     Poseidon2,
 }
 
+#[derive(Clone, PartialEq, ValueEnum)]
+enum ReportFormat {
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+}
+
 #[derive(Clone, PartialEq, ValueEnum)]
 enum ReceiptKind {
     #[value(name = "composite")]
@@ -121,7 +192,7 @@ pub fn main() {
     }
 
     if let Some(port) = args.mode.port {
-        run_server(port);
+        run_server(&args.host, port, args.worker);
         return;
     }
 
@@ -145,6 +216,22 @@ pub fn main() {
             builder.enable_profiler(pprof_out);
         }
 
+        if let Some(gdb) = args.gdb.as_ref() {
+            builder.enable_gdb_server(gdb);
+        }
+
+        if args.cycle_report.is_some() {
+            builder.enable_cycle_report();
+        }
+
+        if let Some(record_io) = args.record_io.as_ref() {
+            builder.record_io(record_io);
+        }
+
+        if let Some(replay_io) = args.replay_io.as_ref() {
+            builder.replay_io(replay_io);
+        }
+
         builder.build().unwrap()
     };
 
@@ -162,8 +249,17 @@ pub fn main() {
         exec.run().unwrap()
     };
 
+    if let Some(format) = args.cycle_report.as_ref() {
+        let report = session.cycle_report.as_ref().unwrap();
+        match format {
+            ReportFormat::Text => print!("{}", report.to_text(args.cycle_report_top)),
+            ReportFormat::Json => println!("{}", serde_json::to_string_pretty(report).unwrap()),
+        }
+        return;
+    }
+
     let prover = args.get_prover();
-    let ctx = VerifierContext::default();
+    let ctx = args.get_verifier_context();
     let receipt = prover.prove_session(&ctx, &session).unwrap().receipt;
 
     let receipt_data = bincode::serialize(&receipt).unwrap();
@@ -182,24 +278,60 @@ pub fn main() {
 
 impl Cli {
     fn get_prover(&self) -> Rc<dyn ProverServer> {
+        let opts = self.get_prover_opts();
+        let Some(addr) = self.listen.as_ref() else {
+            return get_prover_server(&opts).unwrap();
+        };
+
+        let jobs = self.jobs.unwrap_or(self.local_workers);
+        let mut coordinator = Coordinator::bind(addr, opts)
+            .unwrap()
+            .with_jobs(jobs)
+            .with_remote_workers(jobs > self.local_workers);
+        let server_path = std::env::current_exe().unwrap();
+        coordinator
+            .spawn_local_workers(server_path, self.local_workers)
+            .unwrap();
+        if self.verbose > 0 {
+            eprintln!(
+                "Listening for workers on {}",
+                coordinator.local_addr().unwrap()
+            );
+        }
+        Rc::new(coordinator)
+    }
+
+    fn get_prover_opts(&self) -> ProverOpts {
         let hashfn = match self.hashfn {
             HashFn::Sha256 => "sha-256",
             HashFn::Poseidon2 => "poseidon2",
         };
-        let opts = ProverOpts::default()
+        ProverOpts::default()
             .with_hashfn(hashfn.to_string())
             .with_prove_guest_errors(self.prove_guest_errors)
             .with_receipt_kind(match self.receipt_kind {
                 ReceiptKind::Composite => risc0_zkvm::ReceiptKind::Composite,
                 ReceiptKind::Succinct => risc0_zkvm::ReceiptKind::Succinct,
                 ReceiptKind::Groth16 => risc0_zkvm::ReceiptKind::Groth16,
-            });
-        get_prover_server(&opts).unwrap()
+            })
+    }
+
+    fn get_verifier_context(&self) -> VerifierContext {
+        let Some(path) = self.verifier_context.as_ref() else {
+            return VerifierContext::default();
+        };
+        let config: VerifierContextConfig = serde_json::from_slice(&fs::read(path).unwrap())
+            .expect("Unable to parse verifier context file");
+        VerifierContext::from_config(&config).unwrap()
     }
 }
 
-fn run_server(port: u16) {
-    let addr = format!("127.0.0.1:{port}");
+fn run_server(host: &str, port: u16, worker: bool) {
+    let addr = format!("{host}:{port}");
     let server = ApiServer::new_tcp(addr);
-    server.run().unwrap()
+    if worker {
+        server.serve().unwrap()
+    } else {
+        server.run().unwrap()
+    }
 }
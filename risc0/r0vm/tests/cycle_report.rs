@@ -0,0 +1,57 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use assert_fs::{fixture::PathChild, TempDir};
+use risc0_zkvm::serde::to_vec;
+use risc0_zkvm_methods::{multi_test::MultiTestSpec, MULTI_TEST_PATH};
+
+#[test]
+fn cycle_report_skips_proving() {
+    let temp = TempDir::new().unwrap();
+    let receipt_file = temp.child("receipt.dat");
+    let input = to_vec(&MultiTestSpec::DoNothing).unwrap();
+
+    let output = Command::cargo_bin("r0vm")
+        .unwrap()
+        .arg("--elf")
+        .arg(MULTI_TEST_PATH)
+        .args(["--cycle-report", "json", "--receipt"])
+        .arg(&*receipt_file)
+        .write_stdin(bytemuck::cast_slice(&input))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(report.is_object());
+    assert!(!receipt_file.path().exists());
+}
+
+#[test]
+fn cycle_report_requires_elf() {
+    let temp = TempDir::new().unwrap();
+    let image_file = temp.child("image.bin");
+
+    let output = Command::cargo_bin("r0vm")
+        .unwrap()
+        .arg("--image")
+        .arg(&*image_file)
+        .args(["--cycle-report", "text"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("cannot be used with"), "{stderr}");
+}
@@ -0,0 +1,45 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use assert_cmd::Command;
+use assert_fs::{fixture::PathChild, TempDir};
+use risc0_zkvm::{serde::to_vec, Receipt};
+use risc0_zkvm_methods::{multi_test::MultiTestSpec, MULTI_TEST_ID, MULTI_TEST_PATH};
+
+#[test]
+fn prove_with_local_workers() {
+    let temp = TempDir::new().unwrap();
+    let receipt_file = temp.child("receipt.dat");
+    let input = to_vec(&MultiTestSpec::DoNothing).unwrap();
+
+    let mut cmd = Command::cargo_bin("r0vm").unwrap();
+    cmd.arg("--elf")
+        .arg(MULTI_TEST_PATH)
+        .arg("--listen")
+        .arg("127.0.0.1:0")
+        .arg("--local-workers")
+        .arg("2")
+        .arg("--receipt-kind")
+        .arg("succinct")
+        .arg("--receipt")
+        .arg(&*receipt_file)
+        .write_stdin(bytemuck::cast_slice(&input));
+
+    cmd.assert().success();
+
+    let data = std::fs::read(receipt_file).unwrap();
+    let receipt: Receipt = bincode::deserialize(&data).unwrap();
+    receipt.inner.succinct().unwrap();
+    receipt.verify(MULTI_TEST_ID).unwrap();
+}
@@ -0,0 +1,78 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cooperative cancellation of proofs.
+//!
+//! A flag installed on the current thread with [with_cancel_flag] is checked by the FRI protocol
+//! between folding rounds and queries. Once the flag is set, the remaining work is skipped and
+//! the seal returned by [Prover::finalize](super::Prover::finalize) is invalid, so callers must
+//! check [is_cancelled] and discard it.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+thread_local! {
+    static CANCEL_FLAG: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed flag, even if the closure panics.
+struct Restore(Option<Arc<AtomicBool>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CANCEL_FLAG.with(|cell| *cell.borrow_mut() = self.0.take());
+    }
+}
+
+/// Run `f` with the given cancellation flag installed on the current thread.
+pub fn with_cancel_flag<R>(flag: Arc<AtomicBool>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CANCEL_FLAG.with(|cell| cell.replace(Some(flag))));
+    f()
+}
+
+/// Returns true if the flag installed on the current thread is set.
+pub fn is_cancelled() -> bool {
+    CANCEL_FLAG.with(|cell| {
+        cell.borrow()
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_flags() {
+        assert!(!is_cancelled());
+        let outer = Arc::new(AtomicBool::new(true));
+        with_cancel_flag(outer, || {
+            assert!(is_cancelled());
+            let inner = Arc::new(AtomicBool::new(false));
+            with_cancel_flag(inner.clone(), || {
+                assert!(!is_cancelled());
+                inner.store(true, Ordering::Relaxed);
+                assert!(is_cancelled());
+            });
+            assert!(is_cancelled());
+        });
+        assert!(!is_cancelled());
+    }
+}
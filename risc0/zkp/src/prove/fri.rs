@@ -20,7 +20,7 @@ use tracing::debug;
 use crate::{
     core::log2_ceil,
     hal::{Buffer, Hal},
-    prove::{merkle::MerkleTreeProver, write_iop::WriteIOP},
+    prove::{cancel::is_cancelled, merkle::MerkleTreeProver, write_iop::WriteIOP},
     FRI_FOLD, FRI_MIN_DEGREE, INV_RATE, QUERIES,
 };
 
@@ -97,6 +97,9 @@ pub fn fri_prove<H: Hal, F>(
     let mut rounds = Vec::new();
     let mut coeffs = coeffs.clone();
     while coeffs.size() / ext_size > FRI_MIN_DEGREE {
+        if is_cancelled() {
+            return;
+        }
         let round = ProveRoundInfo::new(hal, iop, &coeffs);
         coeffs = round.coeffs.clone();
         rounds.push(round);
@@ -114,6 +117,9 @@ pub fn fri_prove<H: Hal, F>(
     // Do queries
     debug!("Doing Queries");
     for _ in 0..QUERIES {
+        if is_cancelled() {
+            return;
+        }
         // Get a 'random' index.
         let mut pos = iop.random_bits(log2_ceil(orig_domain)) as usize;
         // Do the 'inner' proof for this index
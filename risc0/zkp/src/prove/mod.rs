@@ -21,6 +21,7 @@
 
 pub mod accum;
 pub mod adapter;
+pub mod cancel;
 pub mod executor;
 mod fri;
 mod merkle;
@@ -108,6 +108,9 @@ impl<'a, H: Hal> Prover<'a, H> {
     }
 
     /// Generates the proof and returns the seal.
+    ///
+    /// If the proof is cancelled with a [cancel](super::cancel) flag, the seal is incomplete and
+    /// must be discarded.
     pub fn finalize<C>(mut self, globals: &[&H::Buffer<H::Elem>], circuit_hal: &C) -> Vec<u32>
     where
         C: CircuitHal<H>,
@@ -34,7 +34,8 @@ use risc0_zkvm::{
         memory_barrier, sha,
     },
     sha::{Digest, Sha256},
-    Assumption, ReceiptClaim,
+    Assumption, Groth16Receipt, Groth16ReceiptVerifierParameters, ReceiptClaim, Unknown,
+    VerifierContext,
 };
 use risc0_zkvm_methods::multi_test::{MultiTestSpec, SYS_MULTI_TEST, SYS_MULTI_TEST_WORDS};
 use risc0_zkvm_platform::{
@@ -115,13 +116,41 @@ fn main() {
         MultiTestSpec::Fault => unsafe {
             asm!("sw x0, 1(x0)");
         },
+        MultiTestSpec::Groth16ReceiptVerify {
+            receipt_words,
+            verifier_parameters_words,
+        } => {
+            let receipt: Groth16Receipt<Unknown> =
+                risc0_zkvm::serde::from_slice(&receipt_words).unwrap();
+            let params: Groth16ReceiptVerifierParameters =
+                risc0_zkvm::serde::from_slice(&verifier_parameters_words).unwrap();
+            receipt
+                .verify_integrity_with_context(
+                    &VerifierContext::empty().with_groth16_verifier_parameters(params),
+                )
+                .unwrap();
+        }
+        MultiTestSpec::Groth16Verify {
+            seal,
+            public_inputs_words,
+            verifying_key_words,
+        } => {
+            let seal = risc0_groth16::Seal::from_vec(&seal).unwrap();
+            let public_inputs: alloc::vec::Vec<risc0_groth16::Fr> =
+                risc0_zkvm::serde::from_slice(&public_inputs_words).unwrap();
+            let verifying_key: risc0_groth16::VerifyingKey =
+                risc0_zkvm::serde::from_slice(&verifying_key_words).unwrap();
+            risc0_groth16::verify(&seal, &public_inputs, &verifying_key).unwrap();
+        }
         MultiTestSpec::Halt(exit_code) => {
             env::exit(exit_code);
         }
         MultiTestSpec::PauseResume(exit_code) => {
             env::log("before");
+            env::commit_slice(b"before pause");
             env::pause(exit_code);
             env::log("after");
+            env::commit_slice(b"after pause");
         }
         MultiTestSpec::ReadWriteMem { values } => {
             for (addr, value) in values.into_iter() {
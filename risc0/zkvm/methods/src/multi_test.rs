@@ -50,6 +50,18 @@ pub enum MultiTestSpec {
     },
     EventTrace,
     Fault,
+    Groth16ReceiptVerify {
+        // Groth16Receipt<Unknown> and Groth16ReceiptVerifierParameters: Fields are serialized to
+        // avoid circular dependency issues.
+        receipt_words: Vec<u32>,
+        verifier_parameters_words: Vec<u32>,
+    },
+    Groth16Verify {
+        seal: Vec<u8>,
+        // Vec<Fr> and VerifyingKey: Fields are serialized to avoid circular dependency issues.
+        public_inputs_words: Vec<u32>,
+        verifying_key_words: Vec<u32>,
+    },
     Halt(u8),
     LibM,
     Oom,
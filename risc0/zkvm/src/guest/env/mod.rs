@@ -179,6 +179,10 @@ pub fn pause(exit_code: u8) {
 }
 
 /// Exchange data with the host.
+///
+/// The address and length of `to_host` are passed in registers `a3` and `a4`.
+/// Declare the name of a custom syscall with [declare_syscall][crate::declare_syscall],
+/// and on the host side register a handler for it with `ExecutorEnvBuilder::syscall`.
 pub fn syscall(syscall: SyscallName, to_host: &[u8], from_host: &mut [u32]) -> syscall::Return {
     unsafe {
         syscall_2(
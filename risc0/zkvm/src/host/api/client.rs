@@ -30,7 +30,7 @@ use crate::{
         client::{env::ProveZkrRequest, prove::get_r0vm_path},
     },
     receipt::{AssumptionReceipt, SegmentReceipt, SuccinctReceipt},
-    ExecutorEnv, Journal, ProveInfo, ProverOpts, Receipt, ReceiptClaim,
+    Cancelled, ExecutorEnv, Journal, ProgressEvent, ProveInfo, ProverOpts, Receipt, ReceiptClaim,
 };
 
 /// A client implementation for interacting with a zkVM server.
@@ -106,7 +106,7 @@ impl Client {
         };
         conn.send(request)?;
 
-        let asset = self.prove_handler(&mut conn, env)?;
+        let asset = self.prove_handler(&mut conn, env, opts)?;
 
         let code = conn.close()?;
         if code != 0 {
@@ -554,6 +554,17 @@ impl Client {
         env: &ExecutorEnv<'_>,
         binary: pb::api::Asset,
     ) -> Result<pb::api::ExecutorEnv> {
+        if !env.syscalls.borrow().inner.is_empty() {
+            bail!("custom syscalls are not supported by the external executor");
+        }
+        if env.gdb_listener.is_some() {
+            bail!("a GDB listener cannot be passed to the external executor");
+        }
+        #[cfg(feature = "prove")]
+        if !env.session_hooks.is_empty() {
+            bail!("session hooks are not supported by the external executor");
+        }
+
         Ok(pb::api::ExecutorEnv {
             binary: Some(binary),
             env_vars: env.env_vars.clone(),
@@ -570,6 +581,18 @@ impl Client {
                 .as_ref()
                 .map(|x| x.to_string_lossy().into())
                 .unwrap_or_default(),
+            gdb_addr: env.gdb_addr.clone().unwrap_or_default(),
+            cycle_report: env.cycle_report,
+            record_io: env
+                .record_io
+                .as_ref()
+                .map(|x| x.to_string_lossy().into())
+                .unwrap_or_default(),
+            replay_io: env
+                .replay_io
+                .as_ref()
+                .map(|x| x.to_string_lossy().into())
+                .unwrap_or_default(),
             assumptions: env
                 .assumptions
                 .borrow()
@@ -670,6 +693,7 @@ impl Client {
                                             .ok_or(malformed_err())?
                                             .try_into()?,
                                         receipt_claim,
+                                        cycle_report: session.cycle_report.map(Into::into),
                                     })
                                 }
                                 None => Err(malformed_err()),
@@ -678,6 +702,9 @@ impl Client {
                         pb::api::client_callback::Kind::ProveDone(_) => {
                             return Err(anyhow!("Illegal client callback"))
                         }
+                        pb::api::client_callback::Kind::Progress(_) => {
+                            return Err(anyhow!("Illegal client callback"))
+                        }
                     }
                 }
                 pb::api::server_reply::Kind::Error(err) => return Err(err.into()),
@@ -689,6 +716,7 @@ impl Client {
         &self,
         conn: &mut ConnectionWrapper,
         env: &ExecutorEnv<'_>,
+        opts: &ProverOpts,
     ) -> Result<pb::api::Asset> {
         loop {
             let reply: pb::api::ServerReply = conn.recv()?;
@@ -711,8 +739,20 @@ impl Client {
                         pb::api::client_callback::Kind::ProveDone(done) => {
                             return done.prove_info.ok_or(malformed_err())
                         }
+                        pb::api::client_callback::Kind::Progress(progress) => {
+                            let event: ProgressEvent =
+                                progress.event.ok_or(malformed_err())?.try_into()?;
+                            opts.report(event);
+                            let msg = pb::api::OnProgressReply {
+                                cancel: opts.is_cancelled(),
+                            };
+                            conn.send(msg)?;
+                        }
                     }
                 }
+                pb::api::server_reply::Kind::Error(_) if opts.is_cancelled() => {
+                    return Err(Cancelled.into())
+                }
                 pb::api::server_reply::Kind::Error(err) => return Err(err.into()),
             }
         }
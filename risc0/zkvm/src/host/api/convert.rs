@@ -29,8 +29,9 @@ use crate::{
         SuccinctReceipt,
     },
     receipt_claim::Unknown,
-    Assumption, Assumptions, ExitCode, Groth16Receipt, Input, Journal, MaybePruned, Output,
-    ProveInfo, ProverOpts, Receipt, ReceiptClaim, ReceiptKind, SessionStats, TraceEvent,
+    Assumption, Assumptions, CycleReport, ExitCode, FunctionCycles, Groth16Receipt, Input, Journal,
+    MaybePruned, Output, ProgressEvent, ProveInfo, ProverOpts, Receipt, ReceiptClaim, ReceiptKind,
+    SegmentCycles, SessionStats, SyscallCycles, TraceEvent,
 };
 
 mod ver {
@@ -259,22 +260,72 @@ impl TryFrom<pb::api::ProverOpts> for ProverOpts {
                 .max_segment_po2
                 .try_into()
                 .map_err(|_| malformed_err())?,
+            recursion_workers: opts
+                .recursion_workers
+                .try_into()
+                .map_err(|_| malformed_err())?,
+            checkpoint_dir: (!opts.checkpoint_dir.is_empty()).then(|| opts.checkpoint_dir.into()),
+            cancellation_token: None,
+            progress: None,
         })
     }
 }
 
 impl From<ProverOpts> for pb::api::ProverOpts {
     fn from(opts: ProverOpts) -> Self {
+        let report_progress = opts.progress.is_some() || opts.cancellation_token.is_some();
         Self {
             hashfn: opts.hashfn,
             prove_guest_errors: opts.prove_guest_errors,
             receipt_kind: opts.receipt_kind as i32,
             control_ids: opts.control_ids.into_iter().map(Into::into).collect(),
             max_segment_po2: opts.max_segment_po2 as u64,
+            recursion_workers: opts.recursion_workers as u64,
+            checkpoint_dir: opts
+                .checkpoint_dir
+                .map(|dir| dir.to_string_lossy().into())
+                .unwrap_or_default(),
+            report_progress,
         }
     }
 }
 
+impl From<ProgressEvent> for pb::api::ProgressEvent {
+    fn from(event: ProgressEvent) -> Self {
+        Self {
+            kind: Some(match event {
+                ProgressEvent::SegmentProved { index } => {
+                    pb::api::progress_event::Kind::SegmentProved(index)
+                }
+                ProgressEvent::LiftDone { index } => pb::api::progress_event::Kind::LiftDone(index),
+                ProgressEvent::JoinDone => pb::api::progress_event::Kind::JoinDone(()),
+                ProgressEvent::ResolveDone => pb::api::progress_event::Kind::ResolveDone(()),
+                ProgressEvent::Groth16Started => pb::api::progress_event::Kind::Groth16Started(()),
+                ProgressEvent::ReceiptProduced { bytes } => {
+                    pb::api::progress_event::Kind::ReceiptProduced(bytes)
+                }
+            }),
+        }
+    }
+}
+
+impl TryFrom<pb::api::ProgressEvent> for ProgressEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(event: pb::api::ProgressEvent) -> Result<Self> {
+        Ok(match event.kind.ok_or(malformed_err())? {
+            pb::api::progress_event::Kind::SegmentProved(index) => Self::SegmentProved { index },
+            pb::api::progress_event::Kind::LiftDone(index) => Self::LiftDone { index },
+            pb::api::progress_event::Kind::JoinDone(()) => Self::JoinDone,
+            pb::api::progress_event::Kind::ResolveDone(()) => Self::ResolveDone,
+            pb::api::progress_event::Kind::Groth16Started(()) => Self::Groth16Started,
+            pb::api::progress_event::Kind::ReceiptProduced(bytes) => {
+                Self::ReceiptProduced { bytes }
+            }
+        })
+    }
+}
+
 impl From<semver::Version> for pb::base::SemanticVersion {
     fn from(value: semver::Version) -> Self {
         Self {
@@ -328,6 +379,70 @@ impl TryFrom<pb::core::SessionStats> for SessionStats {
     }
 }
 
+impl From<CycleReport> for pb::api::CycleReport {
+    fn from(value: CycleReport) -> Self {
+        Self {
+            functions: value
+                .functions
+                .into_iter()
+                .map(|func| pb::api::FunctionCycles {
+                    name: func.name,
+                    self_cycles: func.self_cycles,
+                    inclusive_cycles: func.inclusive_cycles,
+                })
+                .collect(),
+            syscalls: value
+                .syscalls
+                .into_iter()
+                .map(|syscall| pb::api::SyscallCycles {
+                    name: syscall.name,
+                    calls: syscall.calls,
+                    cycles: syscall.cycles,
+                })
+                .collect(),
+            segments: value
+                .segments
+                .into_iter()
+                .map(|segment| pb::api::SegmentCycles {
+                    index: segment.index,
+                    po2: segment.po2,
+                    user_cycles: segment.user_cycles,
+                    paging_cycles: segment.paging_cycles,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<pb::api::CycleReport> for CycleReport {
+    fn from(value: pb::api::CycleReport) -> Self {
+        Self {
+            functions: value
+                .functions
+                .into_iter()
+                .map(|func| FunctionCycles::new(func.name, func.self_cycles, func.inclusive_cycles))
+                .collect(),
+            syscalls: value
+                .syscalls
+                .into_iter()
+                .map(|syscall| SyscallCycles::new(syscall.name, syscall.calls, syscall.cycles))
+                .collect(),
+            segments: value
+                .segments
+                .into_iter()
+                .map(|segment| {
+                    SegmentCycles::new(
+                        segment.index,
+                        segment.po2,
+                        segment.user_cycles,
+                        segment.paging_cycles,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
 impl From<ProveInfo> for pb::core::ProveInfo {
     fn from(value: ProveInfo) -> Self {
         Self {
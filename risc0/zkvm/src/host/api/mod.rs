@@ -41,7 +41,7 @@ use lazy_regex::regex_captures;
 use prost::Message;
 use semver::Version;
 
-use crate::{get_version, ExitCode, Journal, ReceiptClaim};
+use crate::{get_version, CycleReport, ExitCode, Journal, ReceiptClaim};
 
 mod pb {
     pub(crate) mod api {
@@ -79,6 +79,7 @@ impl RootMessage for pb::api::ServerRequest {}
 impl RootMessage for pb::api::ServerReply {}
 impl RootMessage for pb::api::GenericReply {}
 impl RootMessage for pb::api::OnIoReply {}
+impl RootMessage for pb::api::OnProgressReply {}
 impl RootMessage for pb::api::ProveSegmentReply {}
 impl RootMessage for pb::api::ProveZkrReply {}
 impl RootMessage for pb::api::LiftRequest {}
@@ -292,6 +293,88 @@ impl Connector for TcpConnector {
     }
 }
 
+/// Accepts connections from servers that dial in to a listener, such as `r0vm --port` workers.
+#[cfg(feature = "prove")]
+pub(crate) struct ListenerConnector {
+    listener: TcpListener,
+    read_timeout: Option<Duration>,
+    accept_timeout: Option<Duration>,
+    servers_alive: Option<Arc<dyn Fn() -> bool + Send + Sync>>,
+}
+
+/// How often [ListenerConnector] checks for a connection while waiting for one.
+#[cfg(feature = "prove")]
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[cfg(feature = "prove")]
+impl ListenerConnector {
+    /// Construct a [ListenerConnector] that fails to receive a message from a server when none
+    /// arrives within `read_timeout`.
+    pub(crate) fn new(listener: TcpListener, read_timeout: Option<Duration>) -> Self {
+        Self {
+            listener,
+            read_timeout,
+            accept_timeout: None,
+            servers_alive: None,
+        }
+    }
+
+    /// Return a [ListenerConnector] that fails to connect when no server dials in within
+    /// `accept_timeout`.
+    pub(crate) fn with_accept_timeout(self, accept_timeout: Option<Duration>) -> Self {
+        Self {
+            accept_timeout,
+            ..self
+        }
+    }
+
+    /// Return a [ListenerConnector] that fails to connect as soon as `servers_alive` returns
+    /// false, i.e. when no server is left that could dial in.
+    pub(crate) fn with_servers_alive(
+        self,
+        servers_alive: Arc<dyn Fn() -> bool + Send + Sync>,
+    ) -> Self {
+        Self {
+            servers_alive: Some(servers_alive),
+            ..self
+        }
+    }
+}
+
+#[cfg(feature = "prove")]
+impl Connector for ListenerConnector {
+    fn connect(&self) -> Result<ConnectionWrapper> {
+        // Poll rather than block, so that waiting for a server can time out.
+        self.listener.set_nonblocking(true)?;
+        let start = std::time::Instant::now();
+        let (stream, addr) = loop {
+            match self.listener.accept() {
+                Ok(accepted) => break accepted,
+                Err(err) if err.kind() == IoErrorKind::WouldBlock => {}
+                Err(err) => return Err(err.into()),
+            }
+            if let Some(servers_alive) = &self.servers_alive {
+                if !servers_alive() {
+                    bail!("no servers are left to connect");
+                }
+            }
+            if let Some(accept_timeout) = self.accept_timeout {
+                if start.elapsed() >= accept_timeout {
+                    bail!("no server connected within {accept_timeout:?}");
+                }
+            }
+            thread::sleep(ACCEPT_POLL_INTERVAL);
+        };
+        tracing::debug!("accepted connection from {addr}");
+        // Accepted streams inherit the non-blocking mode of the listener on some platforms.
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(self.read_timeout)?;
+        Ok(ConnectionWrapper::new(Arc::new(Mutex::new(
+            TcpConnection::new(stream),
+        ))))
+    }
+}
+
 struct ParentProcessConnection {
     child: Child,
     stream: TcpStream,
@@ -393,6 +476,7 @@ pub enum AssetRequest {
 
 /// Provides information about the result of execution.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct SessionInfo {
     /// The number of user cycles for each segment.
     pub segments: Vec<SegmentInfo>,
@@ -406,6 +490,10 @@ pub struct SessionInfo {
     /// The [ReceiptClaim] associated with the executed session. This receipt claim is what will be
     /// proven if this session is passed to the Prover.
     pub receipt_claim: Option<ReceiptClaim>,
+
+    /// The [CycleReport] collected during execution, if requested with
+    /// [ExecutorEnvBuilder::enable_cycle_report][crate::ExecutorEnvBuilder::enable_cycle_report].
+    pub cycle_report: Option<CycleReport>,
 }
 
 impl SessionInfo {
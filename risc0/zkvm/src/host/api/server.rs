@@ -35,9 +35,9 @@ use crate::{
     },
     prove_zkr,
     recursion::identity_p254,
-    AssetRequest, Assumption, ExecutorEnv, ExecutorImpl, InnerAssumptionReceipt, ProverOpts,
-    Receipt, ReceiptClaim, Segment, SegmentReceipt, Session, SuccinctReceipt, TraceCallback,
-    TraceEvent, VerifierContext,
+    AssetRequest, Assumption, CancellationToken, ExecutorEnv, ExecutorImpl, InnerAssumptionReceipt,
+    ProverOpts, Receipt, ReceiptClaim, Segment, SegmentReceipt, Session, SuccinctReceipt,
+    TraceCallback, TraceEvent, VerifierContext,
 };
 
 /// A server implementation for handling requests by clients of the zkVM.
@@ -242,8 +242,31 @@ impl Server {
     /// Start the [Server] and run until all requests are complete.
     pub fn run(&self) -> Result<()> {
         tracing::debug!("connect");
-        let mut conn = self.connector.connect()?;
+        let conn = self.connector.connect()?;
+        self.handle(conn)
+    }
+
+    /// Serve requests one connection at a time, until connecting to the client fails.
+    ///
+    /// This runs the [Server] as a worker for a client that hands out one request per connection,
+    /// such as a [Coordinator][crate::Coordinator]. A failed request is logged, after which the
+    /// server connects again to wait for the next one.
+    pub fn serve(&self) -> Result<()> {
+        loop {
+            let conn = match self.connector.connect() {
+                Ok(conn) => conn,
+                Err(err) => {
+                    tracing::debug!("stopped serving: {err}");
+                    return Ok(());
+                }
+            };
+            if let Err(err) = self.handle(conn) {
+                tracing::warn!("failed to handle request: {err}");
+            }
+        }
+    }
 
+    fn handle(&self, mut conn: ConnectionWrapper) -> Result<()> {
         let server_version = get_version().map_err(|err| anyhow!(err))?;
 
         let request: pb::api::HelloRequest = conn.recv()?;
@@ -337,6 +360,7 @@ impl Server {
                                         .into(),
                                     "session_info.claim",
                                 )?),
+                                cycle_report: session.cycle_report.map(Into::into),
                             }),
                         },
                     )),
@@ -365,7 +389,12 @@ impl Server {
             let binary = env_request.binary.ok_or(malformed_err())?;
             let bytes = binary.as_bytes()?;
 
-            let opts: ProverOpts = request.opts.ok_or(malformed_err())?.try_into()?;
+            let opts_request = request.opts.ok_or(malformed_err())?;
+            let report_progress = opts_request.report_progress;
+            let mut opts: ProverOpts = opts_request.try_into()?;
+            if report_progress {
+                opts = with_progress_proxy(opts, conn);
+            }
             let prover = get_prover_server(&opts)?;
             let ctx = VerifierContext::default();
             let prove_info = prover.prove_with_ctx(env, &ctx, &bytes)?;
@@ -738,6 +767,18 @@ fn build_env<'a>(
     if !request.segment_path.is_empty() {
         env_builder.segment_path(Path::new(&request.segment_path));
     }
+    if !request.gdb_addr.is_empty() {
+        env_builder.enable_gdb_server(&request.gdb_addr);
+    }
+    if request.cycle_report {
+        env_builder.enable_cycle_report();
+    }
+    if !request.record_io.is_empty() {
+        env_builder.record_io(Path::new(&request.record_io));
+    }
+    if !request.replay_io.is_empty() {
+        env_builder.replay_io(Path::new(&request.replay_io));
+    }
     if request.coprocessor {
         let proxy = CoprocessorProxy::new(conn.clone());
         env_builder.coprocessor_callback(proxy);
@@ -941,6 +982,32 @@ fn execute_default(
     })
 }
 
+/// Forwards progress events to the client, and cancels proving when the client asks to.
+fn with_progress_proxy(opts: ProverOpts, conn: &ConnectionWrapper) -> ProverOpts {
+    let token = CancellationToken::new();
+    let conn = conn.clone();
+    let cancel = token.clone();
+    opts.with_cancellation_token(token)
+        .with_progress(move |event| {
+            let msg = pb::api::ServerReply {
+                kind: Some(pb::api::server_reply::Kind::Ok(pb::api::ClientCallback {
+                    kind: Some(pb::api::client_callback::Kind::Progress(
+                        pb::api::OnProgress {
+                            event: Some(event.clone().into()),
+                        },
+                    )),
+                })),
+            };
+
+            tracing::trace!("tx: {msg:?}");
+            match conn.clone().send_recv::<_, pb::api::OnProgressReply>(msg) {
+                Ok(reply) if reply.cancel => cancel.cancel(),
+                Ok(_) => {}
+                Err(err) => tracing::warn!("failed to report progress: {err}"),
+            }
+        })
+}
+
 fn send_segment_done_msg(
     conn: &mut ConnectionWrapper,
     segment: Segment,
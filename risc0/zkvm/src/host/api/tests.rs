@@ -15,11 +15,12 @@
 use std::{
     cell::RefCell,
     collections::BTreeMap,
-    net::{SocketAddr, TcpListener},
+    net::{SocketAddr, TcpListener, TcpStream},
     path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex},
     thread,
+    time::Duration,
 };
 
 use anyhow::Result;
@@ -39,9 +40,9 @@ use super::{Asset, AssetRequest, ConnectionWrapper, Connector, TcpConnection};
 use crate::{
     receipt::SuccinctReceipt,
     recursion::{prove::zkr::test_recursion_circuit, MerkleGroup},
-    register_zkr, ApiClient, ApiServer, CoprocessorCallback, ExecutorEnv, InnerReceipt,
-    ProveZkrRequest, ProverOpts, Receipt, ReceiptClaim, SegmentReceipt, SessionInfo,
-    SuccinctReceiptVerifierParameters, Unknown, VerifierContext,
+    register_zkr, ApiClient, ApiServer, Coordinator, CoprocessorCallback, ExecutorEnv,
+    InnerReceipt, ProveZkrRequest, ProverOpts, ProverServer, Receipt, ReceiptClaim, SegmentReceipt,
+    SessionInfo, SuccinctReceiptVerifierParameters, Unknown, VerifierContext,
 };
 
 struct TestClientConnector {
@@ -514,3 +515,80 @@ async fn redis_asset_external_node() {
     let mut client = TestClient::new();
     let _session_info = client.execute_redis(env, binary, redis_params);
 }
+
+#[test]
+fn distributed_prove() {
+    let coordinator = Coordinator::bind("127.0.0.1:0", ProverOpts::succinct())
+        .unwrap()
+        .with_jobs(2);
+    let addr = coordinator.local_addr().unwrap();
+
+    // A worker that goes away before handling its request, which is then retried on another.
+    TcpStream::connect(addr).unwrap();
+    let workers: Vec<_> = (0..2)
+        .map(|_| thread::spawn(move || ApiServer::new_tcp(addr.to_string()).serve().unwrap()))
+        .collect();
+
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::BusyLoop { cycles: 1 << 17 })
+        .unwrap()
+        .segment_limit_po2(16)
+        .build()
+        .unwrap();
+    let receipt = coordinator.prove(env, MULTI_TEST_ELF).unwrap().receipt;
+    receipt.inner.succinct().unwrap();
+    receipt.verify(MULTI_TEST_ID).unwrap();
+
+    // Workers stop once the coordinator stops listening.
+    drop(coordinator);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn distributed_prove_without_workers() {
+    let env = || {
+        ExecutorEnv::builder()
+            .write(&MultiTestSpec::DoNothing)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    let coordinator = Coordinator::bind("127.0.0.1:0", ProverOpts::succinct())
+        .unwrap()
+        .with_max_attempts(1)
+        .with_accept_timeout(Some(Duration::from_millis(100)));
+    let err = coordinator.prove(env(), MULTI_TEST_ELF).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("no server connected"),
+        "{err:#}"
+    );
+
+    // Local workers that exit without connecting fail the request rather than waiting for the
+    // accept timeout.
+    let mut coordinator = Coordinator::bind("127.0.0.1:0", ProverOpts::succinct())
+        .unwrap()
+        .with_max_attempts(1)
+        .with_accept_timeout(None);
+    coordinator.spawn_local_workers("true", 1).unwrap();
+    let err = coordinator.prove(env(), MULTI_TEST_ELF).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("no servers are left"),
+        "{err:#}"
+    );
+
+    // With remote workers expected, the request keeps waiting for one to connect.
+    let mut coordinator = Coordinator::bind("127.0.0.1:0", ProverOpts::succinct())
+        .unwrap()
+        .with_max_attempts(1)
+        .with_accept_timeout(Some(Duration::from_millis(100)))
+        .with_remote_workers(true);
+    coordinator.spawn_local_workers("true", 1).unwrap();
+    let err = coordinator.prove(env(), MULTI_TEST_ELF).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("no server connected"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,191 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A summary of where the guest spent its cycles.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+/// A summary of where the guest spent its cycles, collected during execution.
+///
+/// Function and syscall cycles are user cycles. Paging cycles are reported for
+/// each segment alongside the user cycles of that segment.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CycleReport {
+    /// Cycles attributed to each guest function, sorted by self cycles in
+    /// descending order.
+    pub functions: Vec<FunctionCycles>,
+
+    /// Cycles spent in each kind of system call, sorted by cycles in
+    /// descending order.
+    pub syscalls: Vec<SyscallCycles>,
+
+    /// Cycles spent in each segment, in execution order.
+    pub segments: Vec<SegmentCycles>,
+}
+
+/// Cycles attributed to a single guest function.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct FunctionCycles {
+    /// The demangled name of the function.
+    pub name: String,
+
+    /// Cycles spent executing the function itself.
+    pub self_cycles: u64,
+
+    /// Cycles spent executing the function and everything it calls.
+    pub inclusive_cycles: u64,
+}
+
+/// Cycles spent in a single kind of system call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SyscallCycles {
+    /// The name of the system call, e.g. `SYS_READ` or `sha`.
+    pub name: String,
+
+    /// The number of times the system call was made.
+    pub calls: u64,
+
+    /// The total number of cycles spent in the system call.
+    pub cycles: u64,
+}
+
+/// Cycles spent in a single segment.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SegmentCycles {
+    /// The index of the segment within the session.
+    pub index: u32,
+
+    /// The number of cycles used for proving in powers of 2.
+    pub po2: u32,
+
+    /// The number of user cycles.
+    pub user_cycles: u64,
+
+    /// The number of cycles needed for paging operations.
+    pub paging_cycles: u64,
+}
+
+impl FunctionCycles {
+    pub(crate) fn new(name: String, self_cycles: u64, inclusive_cycles: u64) -> Self {
+        Self {
+            name,
+            self_cycles,
+            inclusive_cycles,
+        }
+    }
+}
+
+impl SyscallCycles {
+    pub(crate) fn new(name: String, calls: u64, cycles: u64) -> Self {
+        Self {
+            name,
+            calls,
+            cycles,
+        }
+    }
+}
+
+impl SegmentCycles {
+    pub(crate) fn new(index: u32, po2: u32, user_cycles: u64, paging_cycles: u64) -> Self {
+        Self {
+            index,
+            po2,
+            user_cycles,
+            paging_cycles,
+        }
+    }
+}
+
+impl CycleReport {
+    /// The total number of user cycles across all segments.
+    pub fn user_cycles(&self) -> u64 {
+        self.segments.iter().map(|s| s.user_cycles).sum()
+    }
+
+    /// The total number of paging cycles across all segments.
+    pub fn paging_cycles(&self) -> u64 {
+        self.segments.iter().map(|s| s.paging_cycles).sum()
+    }
+
+    /// Format the report as text, listing the `top` functions by self cycles
+    /// and by inclusive cycles.
+    pub fn to_text(&self, top: usize) -> String {
+        let total = self.user_cycles().max(1);
+        let percent = |cycles: u64| cycles as f64 * 100.0 / total as f64;
+        let mut out = String::new();
+
+        writeln!(out, "Top {top} functions by self cycles:").unwrap();
+        writeln!(out, "{:>12} {:>7}  function", "cycles", "%").unwrap();
+        for func in self.functions.iter().take(top) {
+            let cycles = func.self_cycles;
+            writeln!(out, "{cycles:>12} {:>6.2}%  {}", percent(cycles), func.name).unwrap();
+        }
+
+        let mut inclusive: Vec<_> = self.functions.iter().collect();
+        inclusive.sort_by(|a, b| b.inclusive_cycles.cmp(&a.inclusive_cycles));
+        writeln!(out, "\nTop {top} functions by inclusive cycles:").unwrap();
+        writeln!(out, "{:>12} {:>7}  function", "cycles", "%").unwrap();
+        for func in inclusive.into_iter().take(top) {
+            let cycles = func.inclusive_cycles;
+            writeln!(out, "{cycles:>12} {:>6.2}%  {}", percent(cycles), func.name).unwrap();
+        }
+
+        writeln!(out, "\nSyscalls:").unwrap();
+        writeln!(out, "{:>12} {:>7} {:>8}  syscall", "cycles", "%", "calls").unwrap();
+        for syscall in self.syscalls.iter() {
+            let cycles = syscall.cycles;
+            writeln!(
+                out,
+                "{cycles:>12} {:>6.2}% {:>8}  {}",
+                percent(cycles),
+                syscall.calls,
+                syscall.name
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "\nSegments:").unwrap();
+        writeln!(
+            out,
+            "{:>7} {:>4} {:>12} {:>12}",
+            "segment", "po2", "user", "paging"
+        )
+        .unwrap();
+        for segment in self.segments.iter() {
+            writeln!(
+                out,
+                "{:>7} {:>4} {:>12} {:>12}",
+                segment.index, segment.po2, segment.user_cycles, segment.paging_cycles
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "{:>7} {:>4} {:>12} {:>12}",
+            "total",
+            "",
+            self.user_cycles(),
+            self.paging_cycles()
+        )
+        .unwrap();
+
+        out
+    }
+}
@@ -19,12 +19,13 @@ use std::{
     collections::HashMap,
     io::{BufRead, BufReader, Cursor, Read, Write},
     mem,
+    net::TcpListener,
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use bytemuck::Pod;
 use bytes::Bytes;
 use risc0_zkp::core::digest::Digest;
@@ -36,6 +37,7 @@ use crate::{
     host::client::{
         posix_io::PosixIo,
         slice_io::{slice_io_from_fn, SliceIo, SliceIoTable},
+        syscall::{CustomSyscallTable, Syscall},
     },
     serde::to_vec,
     AssumptionReceipt, TraceCallback,
@@ -129,14 +131,22 @@ pub struct ExecutorEnv<'a> {
     pub(crate) session_limit: Option<u64>,
     pub(crate) posix_io: Rc<RefCell<PosixIo<'a>>>,
     pub(crate) slice_io: Rc<RefCell<SliceIoTable<'a>>>,
+    pub(crate) syscalls: Rc<RefCell<CustomSyscallTable<'a>>>,
     pub(crate) input: Vec<u8>,
     pub(crate) trace: Vec<Rc<RefCell<dyn TraceCallback + 'a>>>,
     pub(crate) assumptions: Rc<RefCell<AssumptionReceipts>>,
     pub(crate) segment_path: Option<SegmentPath>,
     pub(crate) pprof_out: Option<PathBuf>,
+    pub(crate) gdb_addr: Option<String>,
+    pub(crate) gdb_listener: Option<TcpListener>,
+    pub(crate) cycle_report: bool,
+    pub(crate) record_io: Option<PathBuf>,
+    pub(crate) replay_io: Option<PathBuf>,
     pub(crate) input_digest: Option<Digest>,
     pub(crate) coprocessor: Option<CoprocessorCallbackRef<'a>>,
     pub(crate) keccak_coprocessor: Option<KeccakCoprocessorCallbackRef<'a>>,
+    #[cfg(feature = "prove")]
+    pub(crate) session_hooks: Vec<Box<dyn crate::SessionEvents>>,
 }
 
 impl<'a> ExecutorEnv<'a> {
@@ -170,6 +180,12 @@ impl<'a> ExecutorEnvBuilder<'a> {
     pub fn build(&mut self) -> Result<ExecutorEnv<'a>> {
         let mut inner = mem::take(&mut self.inner);
 
+        inner.syscalls.borrow().check(&inner.slice_io.borrow())?;
+        ensure!(
+            inner.record_io.is_none() || inner.replay_io.is_none(),
+            "I/O cannot be recorded and replayed at the same time"
+        );
+
         if !inner.input.is_empty() {
             let reader = Cursor::new(inner.input.clone());
             inner
@@ -396,6 +412,49 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Add a handler for a custom system call.
+    ///
+    /// The `name` must match the name of a syscall declared in the guest with
+    /// [declare_syscall][crate::declare_syscall]. Unlike [Self::slice_io], the
+    /// handler has read access to the guest registers, memory and cycle count.
+    /// Custom syscalls are only supported by the local executor, and may not
+    /// replace a built-in syscall, a [Self::slice_io] handler or another custom
+    /// syscall.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use risc0_zkvm::{declare_syscall, ExecutorEnv, Syscall, SyscallContext};
+    ///
+    /// declare_syscall!(SYS_CYCLES);
+    ///
+    /// struct Cycles;
+    ///
+    /// impl Syscall for Cycles {
+    ///     fn syscall(
+    ///         &mut self,
+    ///         _syscall: &str,
+    ///         ctx: &mut dyn SyscallContext,
+    ///         _to_guest: &mut [u32],
+    ///     ) -> anyhow::Result<(u32, u32)> {
+    ///         let cycle = ctx.get_cycle();
+    ///         Ok(((cycle >> 32) as u32, cycle as u32))
+    ///     }
+    /// }
+    ///
+    /// let env = ExecutorEnv::builder()
+    ///     .syscall(SYS_CYCLES, Cycles)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn syscall<C: AsRef<str>>(&mut self, name: C, handler: impl Syscall + 'a) -> &mut Self {
+        self.inner
+            .syscalls
+            .borrow_mut()
+            .with_handler(name.as_ref(), handler);
+        self
+    }
+
     /// Add an [AssumptionReceipt] to the [ExecutorEnv], for use in [composition].
     ///
     /// During execution, when the guest calls `env::verify` or `env::verify_integrity`, this
@@ -416,6 +475,18 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Add a [SessionEvents][crate::SessionEvents] hook, which is fired for each segment while
+    /// it is proven.
+    ///
+    /// The hook is attached to the [Session][crate::Session] produced by execution, so it also
+    /// fires when the local prover proves the environment through [crate::Prover::prove] or
+    /// [crate::Prover::prove_streaming]. Session hooks are only supported by the local prover.
+    #[cfg(feature = "prove")]
+    pub fn session_hook(&mut self, hook: impl crate::SessionEvents + 'static) -> &mut Self {
+        self.inner.session_hooks.push(Box::new(hook));
+        self
+    }
+
     /// Add a callback handler for raw trace messages.
     pub fn trace_callback(&mut self, callback: impl TraceCallback + 'a) -> &mut Self {
         self.inner.trace.push(Rc::new(RefCell::new(callback)));
@@ -434,6 +505,61 @@ impl<'a> ExecutorEnvBuilder<'a> {
         self
     }
 
+    /// Collect a [CycleReport][crate::CycleReport] during execution.
+    ///
+    /// The report attributes user cycles to guest functions and syscalls, and
+    /// breaks down the cycles of each segment. It is returned as part of the
+    /// [Session][crate::Session] or [SessionInfo][crate::SessionInfo]. The
+    /// report is only collected when executing an ELF binary, since symbol
+    /// information is needed to attribute cycles to functions.
+    pub fn enable_cycle_report(&mut self) -> &mut Self {
+        self.inner.cycle_report = true;
+        self
+    }
+
+    /// Serve the GDB remote serial protocol on the specified address, e.g. `localhost:9000`.
+    ///
+    /// The executor waits for GDB to connect before running the guest, after which GDB is in
+    /// control of execution. Breakpoints, single-stepping, and reading registers and memory are
+    /// supported. Connect with `target remote localhost:9000` after loading the guest ELF with
+    /// `file`.
+    pub fn enable_gdb_server<A: AsRef<str>>(&mut self, addr: A) -> &mut Self {
+        self.inner.gdb_addr = Some(addr.as_ref().to_string());
+        self
+    }
+
+    /// Serve the GDB remote serial protocol on a listener that is already bound.
+    ///
+    /// This is the same as [ExecutorEnvBuilder::enable_gdb_server], except that the caller binds
+    /// the listener, e.g. to port 0 to let the operating system choose a free port. It is only
+    /// supported by the local executor.
+    pub fn enable_gdb_server_on(&mut self, listener: TcpListener) -> &mut Self {
+        self.inner.gdb_listener = Some(listener);
+        self
+    }
+
+    /// Record every syscall made by the guest to the specified path.
+    ///
+    /// The log contains the name, arguments and guest memory read by each syscall, along with the
+    /// reply of the host, including the output of `sys_random`. Use
+    /// [ExecutorEnvBuilder::replay_io] to re-execute the same guest from the log.
+    pub fn record_io<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.inner.record_io = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Serve the replies of syscalls from a log written by [ExecutorEnvBuilder::record_io].
+    ///
+    /// This re-executes the recorded guest bit-for-bit, without any of the host-side handlers
+    /// that were present during recording, e.g. stdin, [ExecutorEnvBuilder::read_fd],
+    /// [ExecutorEnvBuilder::slice_io] or coprocessor callbacks. Assumptions are not part of the
+    /// log and must be added again. Execution fails if the guest makes a syscall that differs
+    /// from the log.
+    pub fn replay_io<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.inner.replay_io = Some(path.as_ref().to_path_buf());
+        self
+    }
+
     /// Set the input digest.
     pub fn input_digest(&mut self, digest: Digest) -> &mut Self {
         self.inner.input_digest = Some(digest);
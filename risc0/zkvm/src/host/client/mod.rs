@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod cycle_report;
 pub(crate) mod env;
 pub(crate) mod posix_io;
 pub(crate) mod prove;
 pub(crate) mod slice_io;
+pub(crate) mod syscall;
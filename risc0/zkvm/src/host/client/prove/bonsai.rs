@@ -15,21 +15,30 @@
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
-use bonsai_sdk::blocking::Client;
+use bonsai_sdk::{
+    blocking::{Client, SessionId},
+    RetryPolicy, SdkErr,
+};
 
 use super::Prover;
 use crate::{
-    compute_image_id, is_dev_mode, AssumptionReceipt, ExecutorEnv, InnerAssumptionReceipt,
-    InnerReceipt, ProveInfo, ProverOpts, Receipt, ReceiptKind, SessionStats, VerifierContext,
-    VERSION,
+    compute_image_id, is_dev_mode, sha::Digest, AssumptionReceipt, ExecutorEnv,
+    InnerAssumptionReceipt, InnerReceipt, ProveInfo, ProverOpts, Receipt, ReceiptKind,
+    SessionStats, VerifierContext, VERSION,
 };
 
 /// An implementation of a [Prover] that runs proof workloads via Bonsai.
 ///
 /// Requires `BONSAI_API_URL` and `BONSAI_API_KEY` environment variables to
 /// submit proving sessions to Bonsai.
+///
+/// Requests to Bonsai that are safe to repeat, such as uploads and polling the status of a
+/// session, are retried according to a [RetryPolicy]. If waiting for a session still fails because
+/// the connection to Bonsai was lost, the error includes the session UUID, and
+/// [BonsaiProver::resume_session] can be used to finish waiting for it and download the receipt.
 pub struct BonsaiProver {
     name: String,
+    retry_policy: RetryPolicy,
 }
 
 impl BonsaiProver {
@@ -37,71 +46,50 @@ impl BonsaiProver {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
+            retry_policy: RetryPolicy::default(),
         }
     }
-}
 
-// Only proven assumptions that are succinct are supported by Bonsai.
-fn get_inner_assumption_receipt(assumption: &AssumptionReceipt) -> Result<&InnerAssumptionReceipt> {
-    match assumption {
-        AssumptionReceipt::Proven(receipt) => {
-            if !matches!(receipt, InnerAssumptionReceipt::Succinct(_)) {
-                bail!(
-                    "Bonsai only supports succinct assumption receipts. \
-                    Use `ProverOpts::succinct()` when proving any assumptions."
-                );
-            };
-            Ok(receipt)
-        }
-        AssumptionReceipt::Unresolved(_) => {
-            bail!("only proven assumptions can be uploaded to Bonsai.")
+    /// Return this [BonsaiProver] with the given [RetryPolicy] for requests to Bonsai.
+    #[stability::unstable]
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
         }
     }
-}
-
-impl Prover for BonsaiProver {
-    fn get_name(&self) -> String {
-        self.name.clone()
-    }
 
-    fn prove_with_ctx(
+    /// Wait for an existing Bonsai session to finish, and download and verify its receipt.
+    ///
+    /// This reattaches to a session started by an earlier call to [Prover::prove] or by another
+    /// client, for example after polling failed with a dropped connection. The receipt is
+    /// verified against the given image ID, and compressed to Groth16 on Bonsai if requested by
+    /// `opts`.
+    #[stability::unstable]
+    pub fn resume_session(
         &self,
-        env: ExecutorEnv<'_>,
+        session_uuid: &str,
         ctx: &VerifierContext,
-        elf: &[u8],
+        image_id: impl Into<Digest>,
         opts: &ProverOpts,
     ) -> Result<ProveInfo> {
-        let client = Client::from_env(VERSION)?;
-
-        // Compute the ImageID and upload the ELF binary
-        let image_id = compute_image_id(elf)?;
-        let image_id_hex = hex::encode(image_id);
-        client.upload_img(&image_id_hex, elf.to_vec())?;
-
-        // upload input data
-        let input_id = client.upload_input(env.input)?;
-
-        // upload receipts
-        let mut receipts_ids = vec![];
-        for assumption in env.assumptions.borrow().0.iter() {
-            let inner_receipt = get_inner_assumption_receipt(assumption)?;
-            let serialized_receipt = bincode::serialize(inner_receipt)?;
-            let receipt_id = client.upload_receipt(serialized_receipt)?;
-            receipts_ids.push(receipt_id);
-        }
+        let client = self.client()?;
+        let session = SessionId::new(session_uuid.to_string());
+        self.wait_for_session(&client, &session, ctx, image_id.into(), opts)
+    }
 
-        // While this is the executor, we want to start a session on the bonsai prover.
-        // By doing so, we can return a session ID so that the prover can use it to
-        // retrieve the receipt.
-        let session = client.create_session_with_limit(
-            image_id_hex,
-            input_id,
-            receipts_ids,
-            false,
-            env.session_limit,
-        )?;
-        tracing::debug!("Bonsai proving SessionID: {}", session.uuid);
+    fn client(&self) -> Result<Client> {
+        Ok(Client::from_env(VERSION)?.with_retry_policy(self.retry_policy))
+    }
 
+    fn wait_for_session(
+        &self,
+        client: &Client,
+        session: &SessionId,
+        ctx: &VerifierContext,
+        image_id: Digest,
+        opts: &ProverOpts,
+    ) -> Result<ProveInfo> {
         // TODO(#1759): Improve upon this polling solution.
         let polling_interval = if let Ok(ms) = std::env::var("BONSAI_POLL_INTERVAL_MS") {
             Duration::from_millis(ms.parse().context("invalid bonsai poll interval")?)
@@ -111,7 +99,7 @@ impl Prover for BonsaiProver {
         let succinct_prove_info = loop {
             // The session has already been started in the executor. Poll bonsai to check if
             // the proof request succeeded.
-            let res = session.status(&client)?;
+            let res = session.status(client)?;
             if res.status == "RUNNING" {
                 std::thread::sleep(polling_interval);
                 continue;
@@ -170,9 +158,9 @@ impl Prover for BonsaiProver {
         }
 
         // Request that Bonsai compress further, to Groth16.
-        let snark_session = client.create_snark(session.uuid)?;
+        let snark_session = client.create_snark(session.uuid.clone())?;
         let snark_receipt_url = loop {
-            let res = snark_session.status(&client)?;
+            let res = snark_session.status(client)?;
             match res.status.as_str() {
                 "RUNNING" => {
                     std::thread::sleep(polling_interval);
@@ -217,6 +205,89 @@ impl Prover for BonsaiProver {
             stats: succinct_prove_info.stats,
         })
     }
+}
+
+// Only proven assumptions that are succinct are supported by Bonsai.
+fn get_inner_assumption_receipt(assumption: &AssumptionReceipt) -> Result<&InnerAssumptionReceipt> {
+    match assumption {
+        AssumptionReceipt::Proven(receipt) => {
+            if !matches!(receipt, InnerAssumptionReceipt::Succinct(_)) {
+                bail!(
+                    "Bonsai only supports succinct assumption receipts. \
+                    Use `ProverOpts::succinct()` when proving any assumptions."
+                );
+            };
+            Ok(receipt)
+        }
+        AssumptionReceipt::Unresolved(_) => {
+            bail!("only proven assumptions can be uploaded to Bonsai.")
+        }
+    }
+}
+
+// Whether an error came from the connection to Bonsai, such as a timeout or a dropped connection,
+// rather than from the session.
+fn is_transport_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|cause| matches!(cause.downcast_ref::<SdkErr>(), Some(SdkErr::HttpErr(_))))
+}
+
+impl Prover for BonsaiProver {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn prove_with_ctx(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+        opts: &ProverOpts,
+    ) -> Result<ProveInfo> {
+        let client = self.client()?;
+
+        // Compute the ImageID and upload the ELF binary
+        let image_id = compute_image_id(elf)?;
+        let image_id_hex = hex::encode(image_id);
+        client.upload_img(&image_id_hex, elf.to_vec())?;
+
+        // upload input data
+        let input_id = client.upload_input(env.input)?;
+
+        // upload receipts
+        let mut receipts_ids = vec![];
+        for assumption in env.assumptions.borrow().0.iter() {
+            let inner_receipt = get_inner_assumption_receipt(assumption)?;
+            let serialized_receipt = bincode::serialize(inner_receipt)?;
+            let receipt_id = client.upload_receipt(serialized_receipt)?;
+            receipts_ids.push(receipt_id);
+        }
+
+        // While this is the executor, we want to start a session on the bonsai prover.
+        // By doing so, we can return a session ID so that the prover can use it to
+        // retrieve the receipt.
+        let session = client.create_session_with_limit(
+            image_id_hex,
+            input_id,
+            receipts_ids,
+            false,
+            env.session_limit,
+        )?;
+        tracing::debug!("Bonsai proving SessionID: {}", session.uuid);
+
+        self.wait_for_session(&client, &session, ctx, image_id, opts)
+            .map_err(|err| {
+                // Resuming only helps if the session itself may still succeed.
+                if !is_transport_error(&err) {
+                    return err;
+                }
+                err.context(format!(
+                    "failed to wait for Bonsai session {}; \
+                    use BonsaiProver::resume_session to resume waiting for it",
+                    session.uuid
+                ))
+            })
+    }
 
     fn compress(&self, opts: &ProverOpts, receipt: &Receipt) -> Result<Receipt> {
         match (&receipt.inner, opts.receipt_kind) {
@@ -0,0 +1,161 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handles to a [Prover] or [Executor] that can be shared across threads and async tasks.
+
+use std::{ops::Deref, sync::Arc};
+
+#[cfg(feature = "async")]
+use anyhow::Result;
+
+use super::{Executor, Prover};
+
+#[cfg(feature = "async")]
+use crate::{ExecutorEnv, ProveInfo, ProverOpts, Receipt, SessionInfo, VerifierContext};
+
+/// A [Prover] that is [Send] and [Sync], so it can be shared across threads and async tasks.
+///
+/// A handle is cheap to clone, and dereferences to the underlying [Prover]. With the `async`
+/// feature, it can also prove from async code, running the work on the Tokio blocking pool.
+///
+/// Neither an [ExecutorEnv] nor a [VerifierContext] can be sent between threads, so the async
+/// methods take functions that construct them on the thread that does the work.
+#[derive(Clone)]
+pub struct ProverHandle {
+    inner: Arc<dyn Prover + Send + Sync>,
+}
+
+impl ProverHandle {
+    /// Construct a [ProverHandle] for the given [Prover].
+    pub fn new<P: Prover + Send + Sync + 'static>(prover: P) -> Self {
+        Self {
+            inner: Arc::new(prover),
+        }
+    }
+
+    /// Prove zkVM execution of the specified ELF binary on the blocking pool, using the default
+    /// [VerifierContext] and [ProverOpts].
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn prove_async<F, E>(&self, env: F, elf: E) -> Result<ProveInfo>
+    where
+        F: FnOnce() -> Result<ExecutorEnv<'static>> + Send + 'static,
+        E: AsRef<[u8]> + Send + 'static,
+    {
+        self.prove_with_ctx_async(env, VerifierContext::default, elf, ProverOpts::default())
+            .await
+    }
+
+    /// Prove zkVM execution of the specified ELF binary on the blocking pool, using the
+    /// specified [VerifierContext] and [ProverOpts].
+    ///
+    /// This is the async variant of [Prover::prove_with_ctx]. The [ExecutorEnv] and
+    /// [VerifierContext] are constructed by `env` and `ctx` on the blocking pool, e.g.
+    /// `VerifierContext::default` can be passed as `ctx`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn prove_with_ctx_async<F, C, E>(
+        &self,
+        env: F,
+        ctx: C,
+        elf: E,
+        opts: ProverOpts,
+    ) -> Result<ProveInfo>
+    where
+        F: FnOnce() -> Result<ExecutorEnv<'static>> + Send + 'static,
+        C: FnOnce() -> VerifierContext + Send + 'static,
+        E: AsRef<[u8]> + Send + 'static,
+    {
+        let prover = self.inner.clone();
+        tokio::task::spawn_blocking(move || {
+            prover.prove_with_ctx(env()?, &ctx(), elf.as_ref(), &opts)
+        })
+        .await?
+    }
+
+    /// Compress a [Receipt] on the blocking pool.
+    ///
+    /// This is the async variant of [Prover::compress].
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn compress_async(&self, opts: ProverOpts, receipt: Receipt) -> Result<Receipt> {
+        let prover = self.inner.clone();
+        tokio::task::spawn_blocking(move || prover.compress(&opts, &receipt)).await?
+    }
+}
+
+impl From<Arc<dyn Prover + Send + Sync>> for ProverHandle {
+    fn from(inner: Arc<dyn Prover + Send + Sync>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Deref for ProverHandle {
+    type Target = dyn Prover + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref()
+    }
+}
+
+/// An [Executor] that is [Send] and [Sync], so it can be shared across threads and async tasks.
+///
+/// A handle is cheap to clone, and dereferences to the underlying [Executor]. With the `async`
+/// feature, it can also execute from async code, running the work on the Tokio blocking pool.
+#[derive(Clone)]
+pub struct ExecutorHandle {
+    inner: Arc<dyn Executor + Send + Sync>,
+}
+
+impl ExecutorHandle {
+    /// Construct an [ExecutorHandle] for the given [Executor].
+    pub fn new<E: Executor + Send + Sync + 'static>(executor: E) -> Self {
+        Self {
+            inner: Arc::new(executor),
+        }
+    }
+
+    /// Execute the specified ELF binary on the blocking pool.
+    ///
+    /// This is the async variant of [Executor::execute]. The [ExecutorEnv] is constructed by
+    /// `env` on the blocking pool.
+    ///
+    /// Must be called from within a Tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn execute_async<F, E>(&self, env: F, elf: E) -> Result<SessionInfo>
+    where
+        F: FnOnce() -> Result<ExecutorEnv<'static>> + Send + 'static,
+        E: AsRef<[u8]> + Send + 'static,
+    {
+        let executor = self.inner.clone();
+        tokio::task::spawn_blocking(move || executor.execute(env()?, elf.as_ref())).await?
+    }
+}
+
+impl From<Arc<dyn Executor + Send + Sync>> for ExecutorHandle {
+    fn from(inner: Arc<dyn Executor + Send + Sync>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Deref for ExecutorHandle {
+    type Target = dyn Executor + Send + Sync;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref()
+    }
+}
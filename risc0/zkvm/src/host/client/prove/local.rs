@@ -46,6 +46,17 @@ impl Prover for LocalProver {
         get_prover_server(opts)?.prove_with_ctx(env, ctx, elf)
     }
 
+    fn prove_streaming(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+        opts: &ProverOpts,
+        queue_depth: usize,
+    ) -> Result<ProveInfo> {
+        get_prover_server(opts)?.prove_streaming(env, ctx, elf, queue_depth)
+    }
+
     fn get_name(&self) -> String {
         self.name.clone()
     }
@@ -72,6 +83,7 @@ impl Executor for LocalProver {
             journal: session.journal.unwrap_or_default(),
             exit_code: session.exit_code,
             receipt_claim: Some(receipt_claim),
+            cycle_report: session.cycle_report,
         })
     }
 }
@@ -15,10 +15,16 @@
 #[cfg(feature = "bonsai")]
 pub(crate) mod bonsai;
 pub(crate) mod external;
+pub(crate) mod handle;
 #[cfg(feature = "prove")]
 pub(crate) mod local;
+pub(crate) mod progress;
 
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
 
 use anyhow::{anyhow, Result};
 use risc0_build::risc0_data;
@@ -30,7 +36,11 @@ use risc0_zkp::core::digest::Digest;
 #[cfg(feature = "bonsai")]
 use {self::bonsai::BonsaiProver, crate::is_dev_mode};
 
-use self::external::ExternalProver;
+use self::{
+    external::ExternalProver,
+    handle::{ExecutorHandle, ProverHandle},
+    progress::{CancellationToken, Cancelled, ProgressCallback, ProgressEvent},
+};
 
 use crate::{
     get_version, host::prove_info::ProveInfo, receipt::DEFAULT_MAX_PO2, ExecutorEnv, Receipt,
@@ -118,6 +128,25 @@ pub trait Prover {
         opts: &ProverOpts,
     ) -> Result<ProveInfo>;
 
+    /// Prove zkVM execution of the specified ELF binary, proving each segment while execution is
+    /// still running.
+    ///
+    /// At most `queue_depth` segments wait to be proven at any time, which bounds peak memory by
+    /// the queue depth rather than by the length of the session.
+    ///
+    /// Provers that do not support pipelining fall back to [Prover::prove_with_ctx], which
+    /// executes the whole session before proving it and ignores `queue_depth`.
+    fn prove_streaming(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+        opts: &ProverOpts,
+        _queue_depth: usize,
+    ) -> Result<ProveInfo> {
+        self.prove_with_ctx(env, ctx, elf, opts)
+    }
+
     /// Compress a [Receipt], proving the same computation using a smaller representation.
     ///
     /// Proving will, by default, produce a [CompositeReceipt](crate::CompositeReceipt), which
@@ -178,6 +207,24 @@ pub struct ProverOpts {
 
     /// Maximum cycle count, as a power of two (po2) that these prover options support.
     pub(crate) max_segment_po2: usize,
+
+    /// Maximum number of lift and join programs to prove concurrently when compressing a
+    /// composite receipt into a succinct receipt.
+    #[serde(default)]
+    pub(crate) recursion_workers: usize,
+
+    /// Directory in which intermediate receipts are persisted, allowing an interrupted proof to
+    /// be resumed without proving everything again.
+    #[serde(default)]
+    pub(crate) checkpoint_dir: Option<PathBuf>,
+
+    /// Token checked while proving, which stops the prover once cancelled.
+    #[serde(skip)]
+    pub(crate) cancellation_token: Option<CancellationToken>,
+
+    /// Callback that receives progress events while proving.
+    #[serde(skip)]
+    pub(crate) progress: Option<ProgressCallback>,
 }
 
 /// An enumeration of receipt kinds that can be requested to be generated.
@@ -215,6 +262,10 @@ impl Default for ProverOpts {
             receipt_kind: ReceiptKind::Composite,
             control_ids: ALLOWED_CONTROL_IDS.to_vec(),
             max_segment_po2: DEFAULT_MAX_PO2,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 }
@@ -236,6 +287,10 @@ impl ProverOpts {
                 .unwrap()
                 .collect(),
             max_segment_po2: po2_max,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 
@@ -255,6 +310,10 @@ impl ProverOpts {
             receipt_kind: ReceiptKind::Composite,
             control_ids: risc0_circuit_rv32im::control_ids("sha-256", DEFAULT_MAX_PO2).collect(),
             max_segment_po2: DEFAULT_MAX_PO2,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 
@@ -267,6 +326,10 @@ impl ProverOpts {
             receipt_kind: ReceiptKind::Composite,
             control_ids: ALLOWED_CONTROL_IDS.to_vec(),
             max_segment_po2: DEFAULT_MAX_PO2,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 
@@ -279,6 +342,10 @@ impl ProverOpts {
             receipt_kind: ReceiptKind::Succinct,
             control_ids: ALLOWED_CONTROL_IDS.to_vec(),
             max_segment_po2: DEFAULT_MAX_PO2,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 
@@ -293,6 +360,10 @@ impl ProverOpts {
             receipt_kind: ReceiptKind::Groth16,
             control_ids: ALLOWED_CONTROL_IDS.to_vec(),
             max_segment_po2: DEFAULT_MAX_PO2,
+            recursion_workers: 1,
+            checkpoint_dir: None,
+            cancellation_token: None,
+            progress: None,
         }
     }
 
@@ -337,6 +408,96 @@ impl ProverOpts {
         }
     }
 
+    /// Return [ProverOpts] with the recursion_workers set to the given value.
+    ///
+    /// Each worker proves one lift or join program at a time, so the memory required for
+    /// compression grows with the number of workers. The default is a single worker.
+    #[stability::unstable]
+    pub fn with_recursion_workers(self, recursion_workers: usize) -> Self {
+        Self {
+            recursion_workers,
+            ..self
+        }
+    }
+
+    /// Return [ProverOpts] with the checkpoint_dir set to the given value.
+    ///
+    /// Each segment receipt, lifted, joined and resolved succinct receipt, and ZKR receipt is
+    /// persisted to this directory as soon as it is proven. Proving the same ELF binary with the
+    /// same input again with this directory skips all work that is already checkpointed, after
+    /// verifying the stored receipts.
+    #[stability::unstable]
+    pub fn with_checkpoint_dir<P: AsRef<Path>>(self, checkpoint_dir: P) -> Self {
+        Self {
+            checkpoint_dir: Some(checkpoint_dir.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    /// Return [ProverOpts] with the cancellation_token set to the given value.
+    ///
+    /// Once the token is cancelled, proving stops and fails with a [Cancelled] error. The token
+    /// is checked between segments and recursion programs, and while proving each of them.
+    #[stability::unstable]
+    pub fn with_cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self {
+            cancellation_token: Some(cancellation_token),
+            ..self
+        }
+    }
+
+    /// Return [ProverOpts] with the progress callback set to the given function.
+    ///
+    /// The callback receives a [ProgressEvent] as each segment is proven, each recursion program
+    /// is done, Groth16 proving starts, and the final receipt is produced. Recursion programs may
+    /// be proven concurrently, so the callback may be called from multiple threads.
+    #[stability::unstable]
+    pub fn with_progress<F>(self, progress: F) -> Self
+    where
+        F: Fn(&ProgressEvent) + Send + Sync + 'static,
+    {
+        Self {
+            progress: Some(Arc::new(progress)),
+            ..self
+        }
+    }
+
+    /// Returns true if the cancellation_token is set and has been cancelled.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Fail with a [Cancelled] error if the cancellation_token has been cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Report a progress event to the progress callback, if any.
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress(&event);
+        }
+    }
+
+    /// Run a proving step, checking the cancellation_token before and after it, and within the
+    /// FRI protocol while it runs.
+    #[cfg(feature = "prove")]
+    pub(crate) fn cancellable<T>(&self, step: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.check_cancelled()?;
+        let result = match &self.cancellation_token {
+            Some(token) => risc0_zkp::prove::cancel::with_cancel_flag(token.flag(), step),
+            None => step(),
+        };
+        // A cancelled proof is incomplete, so any result is discarded.
+        self.check_cancelled()?;
+        result
+    }
+
     #[cfg(feature = "prove")]
     pub(crate) fn hash_suite(
         &self,
@@ -363,14 +524,25 @@ impl ProverOpts {
 /// * LocalProver if the `prove` feature flag is enabled.
 /// * [ExternalProver] otherwise.
 pub fn default_prover() -> Rc<dyn Prover> {
+    Rc::<dyn Prover + Send + Sync>::from(select_prover())
+}
+
+/// Return a default [Prover] that can be shared across threads and async tasks.
+///
+/// The [Prover] is selected in the same way as by [default_prover].
+pub fn default_prover_handle() -> ProverHandle {
+    Arc::<dyn Prover + Send + Sync>::from(select_prover()).into()
+}
+
+fn select_prover() -> Box<dyn Prover + Send + Sync> {
     let explicit = std::env::var("RISC0_PROVER").unwrap_or_default();
     if !explicit.is_empty() {
         return match explicit.to_lowercase().as_str() {
             #[cfg(feature = "bonsai")]
-            "bonsai" => Rc::new(BonsaiProver::new("bonsai")),
-            "ipc" => Rc::new(ExternalProver::new("ipc", get_r0vm_path().unwrap())),
+            "bonsai" => Box::new(BonsaiProver::new("bonsai")),
+            "ipc" => Box::new(ExternalProver::new("ipc", get_r0vm_path().unwrap())),
             #[cfg(feature = "prove")]
-            "local" => Rc::new(self::local::LocalProver::new("local")),
+            "local" => Box::new(self::local::LocalProver::new("local")),
             _ => unimplemented!("Unsupported prover: {explicit}"),
         };
     }
@@ -381,16 +553,16 @@ pub fn default_prover() -> Rc<dyn Prover> {
             && std::env::var("BONSAI_API_URL").is_ok()
             && std::env::var("BONSAI_API_KEY").is_ok()
         {
-            return Rc::new(BonsaiProver::new("bonsai"));
+            return Box::new(BonsaiProver::new("bonsai"));
         }
     }
 
     if cfg!(feature = "prove") {
         #[cfg(feature = "prove")]
-        return Rc::new(self::local::LocalProver::new("local"));
+        return Box::new(self::local::LocalProver::new("local"));
     }
 
-    Rc::new(ExternalProver::new("ipc", get_r0vm_path().unwrap()))
+    Box::new(ExternalProver::new("ipc", get_r0vm_path().unwrap()))
 }
 
 /// Return a default [Executor] based on environment variables and feature
@@ -409,22 +581,33 @@ pub fn default_prover() -> Rc<dyn Prover> {
 /// * LocalProver if the `prove` feature flag is enabled.
 /// * [ExternalProver] otherwise.
 pub fn default_executor() -> Rc<dyn Executor> {
+    Rc::<dyn Executor + Send + Sync>::from(select_executor())
+}
+
+/// Return a default [Executor] that can be shared across threads and async tasks.
+///
+/// The [Executor] is selected in the same way as by [default_executor].
+pub fn default_executor_handle() -> ExecutorHandle {
+    Arc::<dyn Executor + Send + Sync>::from(select_executor()).into()
+}
+
+fn select_executor() -> Box<dyn Executor + Send + Sync> {
     let explicit = std::env::var("RISC0_EXECUTOR").unwrap_or_default();
     if !explicit.is_empty() {
         return match explicit.to_lowercase().as_str() {
-            "ipc" => Rc::new(ExternalProver::new("ipc", get_r0vm_path().unwrap())),
+            "ipc" => Box::new(ExternalProver::new("ipc", get_r0vm_path().unwrap())),
             #[cfg(feature = "prove")]
-            "local" => Rc::new(self::local::LocalProver::new("local")),
+            "local" => Box::new(self::local::LocalProver::new("local")),
             _ => unimplemented!("Unsupported executor: {explicit}"),
         };
     }
 
     if cfg!(feature = "prove") {
         #[cfg(feature = "prove")]
-        return Rc::new(self::local::LocalProver::new("local"));
+        return Box::new(self::local::LocalProver::new("local"));
     }
 
-    Rc::new(ExternalProver::new("ipc", get_r0vm_path().unwrap()))
+    Box::new(ExternalProver::new("ipc", get_r0vm_path().unwrap()))
 }
 
 fn try_r0vm_path(version: String) -> Option<PathBuf> {
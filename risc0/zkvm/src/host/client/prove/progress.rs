@@ -0,0 +1,103 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cancellation and progress reporting for long-running proofs.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A token to cancel proving from another thread or async task.
+///
+/// Pass a clone of the token to
+/// [ProverOpts::with_cancellation_token][super::ProverOpts::with_cancellation_token] and call
+/// [CancellationToken::cancel] to stop proving. The prover checks the token between segments and
+/// recursion programs, and while proving each of them, and then fails with a [Cancelled] error.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Construct a [CancellationToken] that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel all proofs using this token.
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "prove")]
+    pub(crate) fn flag(&self) -> Arc<AtomicBool> {
+        self.flag.clone()
+    }
+}
+
+/// The error returned when proving is stopped with a [CancellationToken].
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "proving was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// An event reported while proving.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// The segment with the given index was proven.
+    SegmentProved {
+        /// The index of the segment.
+        index: u32,
+    },
+
+    /// The receipt for the segment with the given index was lifted.
+    LiftDone {
+        /// The index of the segment.
+        index: u32,
+    },
+
+    /// Two succinct receipts were joined.
+    JoinDone,
+
+    /// An assumption was resolved.
+    ResolveDone,
+
+    /// Proving of the Groth16 receipt started.
+    Groth16Started,
+
+    /// The final receipt was produced.
+    ReceiptProduced {
+        /// The size of the receipt, serialized with bincode, in bytes.
+        bytes: u64,
+    },
+}
+
+/// A callback that receives [ProgressEvent]s, possibly from multiple threads at once.
+pub type ProgressCallback = Arc<dyn Fn(&ProgressEvent) + Send + Sync>;
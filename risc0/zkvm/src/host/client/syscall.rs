@@ -0,0 +1,128 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Public interface for host-side handlers of custom system calls.
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use anyhow::{bail, Result};
+
+use super::slice_io::SliceIoTable;
+
+/// Prefix shared by the names of all system calls built into the zkVM.
+const RESERVED_PREFIX: &str = "risc0_zkvm_platform::syscall::nr::";
+
+/// A host-side handler for a custom system call.
+///
+/// On the guest side, declare the name of the system call with
+/// [declare_syscall][crate::declare_syscall] and invoke it with
+/// `env::syscall` or `env::send_recv_slice`. Register the handler on the host
+/// with [ExecutorEnvBuilder::syscall][crate::ExecutorEnvBuilder::syscall],
+/// using the same name.
+///
+/// Custom system calls are only supported by the local executor.
+pub trait Syscall {
+    /// Invokes the system call.
+    ///
+    /// `to_guest` is the buffer the guest provided for the reply; it is empty
+    /// if the guest did not request any data. The returned pair is written to
+    /// the guest registers `a0` and `a1`.
+    fn syscall(
+        &mut self,
+        syscall: &str,
+        ctx: &mut dyn SyscallContext,
+        to_guest: &mut [u32],
+    ) -> Result<(u32, u32)>;
+}
+
+/// Read access to guest memory and machine state for custom system calls.
+pub trait SyscallContext {
+    /// Returns the current program counter.
+    fn get_pc(&self) -> u32;
+
+    /// Returns the current cycle being executed.
+    fn get_cycle(&self) -> u64;
+
+    /// Loads the value of the given register, e.g. REG_A0.
+    fn load_register(&mut self, idx: usize) -> u32;
+
+    /// Loads an individual byte from memory.
+    fn load_u8(&mut self, addr: u32) -> Result<u8>;
+
+    /// Loads an individual word from memory.
+    fn load_u32(&mut self, addr: u32) -> Result<u32>;
+
+    /// Loads bytes from the given region of memory. A region may span multiple pages.
+    ///
+    /// Returns an error if the region extends past the end of the address space.
+    fn load_region(&mut self, addr: u32, size: u32) -> Result<Vec<u8>> {
+        let mut region = Vec::new();
+        for i in 0..size {
+            let Some(byte_addr) = addr.checked_add(i) else {
+                bail!("region of {size} bytes at 0x{addr:08x} overflows the address space");
+            };
+            region.push(self.load_u8(byte_addr)?);
+        }
+        Ok(region)
+    }
+}
+
+pub type SyscallRef<'a> = Rc<RefCell<dyn Syscall + 'a>>;
+
+#[derive(Clone, Default)]
+pub struct CustomSyscallTable<'a> {
+    pub(crate) inner: BTreeMap<String, SyscallRef<'a>>,
+    duplicates: Vec<String>,
+}
+
+impl<'a> CustomSyscallTable<'a> {
+    pub fn with_handler(&mut self, name: &str, handler: impl Syscall + 'a) -> &mut Self {
+        if self
+            .inner
+            .insert(name.to_string(), Rc::new(RefCell::new(handler)))
+            .is_some()
+        {
+            self.duplicates.push(name.to_string());
+        }
+        self
+    }
+
+    /// Ensures that every custom handler has a unique name, and does not replace a built-in
+    /// system call or a [slice_io][crate::ExecutorEnvBuilder::slice_io] handler.
+    pub(crate) fn check(&self, slice_io: &SliceIoTable) -> Result<()> {
+        if let Some(name) = self.duplicates.first() {
+            bail!("custom syscall {name:?} is registered more than once");
+        }
+        for name in self.inner.keys() {
+            if name.starts_with(RESERVED_PREFIX) {
+                bail!("custom syscall {name:?} conflicts with a built-in syscall");
+            }
+            if slice_io.inner.contains_key(name) {
+                bail!("custom syscall {name:?} conflicts with a slice_io handler");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Syscall + ?Sized> Syscall for &mut S {
+    fn syscall(
+        &mut self,
+        syscall: &str,
+        ctx: &mut dyn SyscallContext,
+        to_guest: &mut [u32],
+    ) -> Result<(u32, u32)> {
+        (**self).syscall(syscall, ctx, to_guest)
+    }
+}
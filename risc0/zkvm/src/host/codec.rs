@@ -0,0 +1,200 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoding and decoding of [Receipt]s in the formats used to store and transport them.
+
+use anyhow::{anyhow, bail, Result};
+use prost::Message;
+
+use super::protos as pb;
+use crate::{sha::Digestible, InnerReceipt, Receipt};
+
+/// A format in which a [Receipt] can be encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReceiptFormat {
+    /// The bincode encoding of the [Receipt], as written by `r0vm` and most tools.
+    Bincode,
+
+    /// The JSON encoding of the [Receipt], with the same structure as its serde representation.
+    Json,
+
+    /// The protobuf encoding of the [Receipt], as used by the `r0vm` API.
+    Protobuf,
+
+    /// ABI-encoded calldata for the Solidity verifier, containing the seal and journal digest.
+    ///
+    /// Only Groth16 receipts can be encoded as calldata, and calldata cannot be decoded back into
+    /// a [Receipt], since it omits the claim. See [encode_calldata] for details.
+    Calldata,
+}
+
+/// Encode the given [Receipt] in the given [ReceiptFormat].
+pub fn encode_receipt(receipt: &Receipt, format: ReceiptFormat) -> Result<Vec<u8>> {
+    Ok(match format {
+        ReceiptFormat::Bincode => bincode::serialize(receipt)?,
+        ReceiptFormat::Json => serde_json::to_vec(receipt)?,
+        ReceiptFormat::Protobuf => pb::core::Receipt::from(receipt.clone()).encode_to_vec(),
+        ReceiptFormat::Calldata => encode_calldata(receipt)?,
+    })
+}
+
+/// Decode a [Receipt] encoded in the given [ReceiptFormat].
+pub fn decode_receipt(bytes: &[u8], format: ReceiptFormat) -> Result<Receipt> {
+    Ok(match format {
+        ReceiptFormat::Bincode => bincode::deserialize(bytes)?,
+        ReceiptFormat::Json => serde_json::from_slice(bytes)?,
+        ReceiptFormat::Protobuf => pb::core::Receipt::decode(bytes)?.try_into()?,
+        ReceiptFormat::Calldata => bail!("calldata cannot be decoded into a receipt"),
+    })
+}
+
+/// Encode the seal of a Groth16 [Receipt], prefixed with its selector.
+///
+/// The selector is the first four bytes of the verifier parameters digest, and is used by the
+/// Solidity verifier router to pick the verifier for the receipt.
+pub fn encode_seal(receipt: &Receipt) -> Result<Vec<u8>> {
+    let InnerReceipt::Groth16(groth16) = &receipt.inner else {
+        bail!("only Groth16 receipts can be encoded for the Solidity verifier");
+    };
+    let selector = &groth16.verifier_parameters.as_bytes()[..4];
+    Ok([selector, groth16.seal.as_slice()].concat())
+}
+
+/// Encode a Groth16 [Receipt] as calldata for the Solidity verifier.
+///
+/// The calldata is the ABI encoding of `(bytes seal, bytes32 journalDigest)`, where the seal is
+/// produced by [encode_seal].
+pub fn encode_calldata(receipt: &Receipt) -> Result<Vec<u8>> {
+    const WORD: usize = 32;
+
+    let seal = encode_seal(receipt)?;
+    let padded_len = seal.len().div_ceil(WORD) * WORD;
+    let mut calldata = Vec::with_capacity(3 * WORD + padded_len);
+    calldata.extend_from_slice(&abi_uint(2 * WORD)?);
+    calldata.extend_from_slice(receipt.journal.digest().as_bytes());
+    calldata.extend_from_slice(&abi_uint(seal.len())?);
+    calldata.extend_from_slice(&seal);
+    calldata.resize(3 * WORD + padded_len, 0);
+    Ok(calldata)
+}
+
+/// Encode the given value as an ABI `uint256`.
+fn abi_uint(value: usize) -> Result<[u8; 32]> {
+    let value = u64::try_from(value).map_err(|_| anyhow!("value does not fit in a u64"))?;
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use risc0_zkp::core::digest::Digest;
+
+    use super::{decode_receipt, encode_calldata, encode_receipt, ReceiptFormat};
+    use crate::{
+        receipt::merkle::MerkleProof, sha::Digestible, CompositeReceipt, FakeReceipt,
+        Groth16Receipt, InnerAssumptionReceipt, InnerReceipt, MaybePruned, Receipt, ReceiptClaim,
+        SegmentReceipt, SuccinctReceipt,
+    };
+
+    const JOURNAL: &[u8] = b"journal";
+
+    fn claim() -> ReceiptClaim {
+        ReceiptClaim::ok(
+            Digest::from([1u32; 8]),
+            MaybePruned::Pruned(JOURNAL.digest()),
+        )
+    }
+
+    fn succinct_receipt() -> SuccinctReceipt<ReceiptClaim> {
+        SuccinctReceipt {
+            seal: vec![1, 2, 3, 4],
+            control_id: Digest::from([2u32; 8]),
+            claim: claim().into(),
+            hashfn: "poseidon2".into(),
+            verifier_parameters: Digest::from([3u32; 8]),
+            control_inclusion_proof: MerkleProof {
+                index: 5,
+                digests: vec![Digest::from([4u32; 8])],
+            },
+        }
+    }
+
+    fn receipts() -> Vec<Receipt> {
+        let segment = SegmentReceipt {
+            seal: vec![5, 6, 7, 8],
+            index: 0,
+            hashfn: "poseidon2".into(),
+            verifier_parameters: Digest::from([5u32; 8]),
+            claim: claim(),
+        };
+        let assumption = InnerAssumptionReceipt::Fake(FakeReceipt {
+            claim: MaybePruned::Pruned(Digest::from([6u32; 8])),
+        });
+        [
+            InnerReceipt::Composite(CompositeReceipt {
+                segments: vec![segment],
+                assumption_receipts: vec![assumption],
+                verifier_parameters: Digest::from([7u32; 8]),
+            }),
+            InnerReceipt::Succinct(succinct_receipt()),
+            InnerReceipt::Groth16(Groth16Receipt::new(
+                vec![9; 256],
+                claim().into(),
+                Digest::from([8u32; 8]),
+            )),
+            InnerReceipt::Fake(FakeReceipt {
+                claim: claim().into(),
+            }),
+        ]
+        .into_iter()
+        .map(|inner| Receipt::new(inner, JOURNAL.to_vec()))
+        .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for receipt in receipts() {
+            for format in [
+                ReceiptFormat::Bincode,
+                ReceiptFormat::Json,
+                ReceiptFormat::Protobuf,
+            ] {
+                let encoded = encode_receipt(&receipt, format).unwrap();
+                let decoded = decode_receipt(&encoded, format).unwrap();
+                assert_eq!(receipt, decoded, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn calldata() {
+        let receipts = receipts();
+        let groth16 = &receipts[2];
+        let calldata = encode_calldata(groth16).unwrap();
+
+        // Offset of the seal, journal digest, seal length, then the seal padded to 32 bytes.
+        assert_eq!(calldata.len(), 3 * 32 + 288);
+        assert_eq!(calldata[31], 0x40);
+        assert_eq!(&calldata[32..64], JOURNAL.digest().as_bytes());
+        assert_eq!(u16::from_be_bytes([calldata[94], calldata[95]]), 260);
+        assert_eq!(&calldata[96..100], &Digest::from([8u32; 8]).as_bytes()[..4]);
+        assert_eq!(&calldata[100..356], &[9; 256]);
+        assert!(calldata[356..].iter().all(|&b| b == 0));
+
+        assert!(encode_calldata(&receipts[0]).is_err());
+        assert!(decode_receipt(&calldata, ReceiptFormat::Calldata).is_err());
+    }
+}
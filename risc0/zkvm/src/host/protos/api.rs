@@ -350,6 +350,14 @@ pub struct ExecutorEnv {
     pub segment_path: ::prost::alloc::string::String,
     #[prost(bool, tag = "13")]
     pub coprocessor: bool,
+    #[prost(string, tag = "14")]
+    pub gdb_addr: ::prost::alloc::string::String,
+    #[prost(bool, tag = "15")]
+    pub cycle_report: bool,
+    #[prost(string, tag = "16")]
+    pub record_io: ::prost::alloc::string::String,
+    #[prost(string, tag = "17")]
+    pub replay_io: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -383,6 +391,12 @@ pub struct ProverOpts {
     pub control_ids: ::prost::alloc::vec::Vec<super::base::Digest>,
     #[prost(uint64, tag = "5")]
     pub max_segment_po2: u64,
+    #[prost(uint64, tag = "6")]
+    pub recursion_workers: u64,
+    #[prost(string, tag = "7")]
+    pub checkpoint_dir: ::prost::alloc::string::String,
+    #[prost(bool, tag = "8")]
+    pub report_progress: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -395,6 +409,50 @@ pub struct SessionInfo {
     pub exit_code: ::core::option::Option<super::base::ExitCode>,
     #[prost(message, optional, tag = "4")]
     pub receipt_claim: ::core::option::Option<Asset>,
+    #[prost(message, optional, tag = "5")]
+    pub cycle_report: ::core::option::Option<CycleReport>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CycleReport {
+    #[prost(message, repeated, tag = "1")]
+    pub functions: ::prost::alloc::vec::Vec<FunctionCycles>,
+    #[prost(message, repeated, tag = "2")]
+    pub syscalls: ::prost::alloc::vec::Vec<SyscallCycles>,
+    #[prost(message, repeated, tag = "3")]
+    pub segments: ::prost::alloc::vec::Vec<SegmentCycles>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FunctionCycles {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub self_cycles: u64,
+    #[prost(uint64, tag = "3")]
+    pub inclusive_cycles: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyscallCycles {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub calls: u64,
+    #[prost(uint64, tag = "3")]
+    pub cycles: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentCycles {
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(uint32, tag = "2")]
+    pub po2: u32,
+    #[prost(uint64, tag = "3")]
+    pub user_cycles: u64,
+    #[prost(uint64, tag = "4")]
+    pub paging_cycles: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -484,7 +542,7 @@ pub mod server_reply {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ClientCallback {
-    #[prost(oneof = "client_callback::Kind", tags = "1, 2, 3, 4")]
+    #[prost(oneof = "client_callback::Kind", tags = "1, 2, 3, 4, 5")]
     pub kind: ::core::option::Option<client_callback::Kind>,
 }
 /// Nested message and enum types in `ClientCallback`.
@@ -500,6 +558,8 @@ pub mod client_callback {
         SessionDone(super::OnSessionDone),
         #[prost(message, tag = "4")]
         ProveDone(super::OnProveDone),
+        #[prost(message, tag = "5")]
+        Progress(super::OnProgress),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -660,6 +720,43 @@ pub struct OnProveDone {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OnProgress {
+    #[prost(message, optional, tag = "1")]
+    pub event: ::core::option::Option<ProgressEvent>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OnProgressReply {
+    #[prost(bool, tag = "1")]
+    pub cancel: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProgressEvent {
+    #[prost(oneof = "progress_event::Kind", tags = "1, 2, 3, 4, 5, 6")]
+    pub kind: ::core::option::Option<progress_event::Kind>,
+}
+/// Nested message and enum types in `ProgressEvent`.
+pub mod progress_event {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        #[prost(uint32, tag = "1")]
+        SegmentProved(u32),
+        #[prost(uint32, tag = "2")]
+        LiftDone(u32),
+        #[prost(message, tag = "3")]
+        JoinDone(()),
+        #[prost(message, tag = "4")]
+        ResolveDone(()),
+        #[prost(message, tag = "5")]
+        Groth16Started(()),
+        #[prost(uint64, tag = "6")]
+        ReceiptProduced(u64),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GenericReply {
     #[prost(oneof = "generic_reply::Kind", tags = "1, 2")]
     pub kind: ::core::option::Option<generic_reply::Kind>,
@@ -111,13 +111,7 @@ pub fn join(
     out_stream.extend(receipt.output.iter());
 
     // Construct the expected claim that should have result from the join.
-    let ab_claim = ReceiptClaim {
-        pre: a.claim.as_value()?.pre.clone(),
-        post: b.claim.as_value()?.post.clone(),
-        exit_code: b.claim.as_value()?.exit_code,
-        input: a.claim.as_value()?.input.clone(),
-        output: b.claim.as_value()?.output.clone(),
-    };
+    let ab_claim = joined_claim(a, b)?;
 
     let claim_decoded = ReceiptClaim::decode(&mut out_stream)?;
     tracing::debug!("Proving join finished: decoded claim = {claim_decoded:#?}");
@@ -158,6 +152,48 @@ where
 
     // Construct the resolved claim by copying the conditional receipt claim and resolving
     // the head assumption. If this fails, then so would the resolve program.
+    let resolved_claim = resolved_claim(conditional)?;
+
+    let opts = ProverOpts::succinct();
+    let mut prover = Prover::new_resolve(conditional, assumption, opts.clone())?;
+    let receipt = prover.prover.run()?;
+    let mut out_stream = VecDeque::<u32>::new();
+    out_stream.extend(receipt.output.iter());
+
+    let claim_decoded = ReceiptClaim::decode(&mut out_stream)?;
+    tracing::debug!("Proving resolve finished: decoded claim = {claim_decoded:#?}");
+
+    // Include an inclusion proof for control_id to allow verification against a root.
+    let control_inclusion_proof = MerkleGroup::new(opts.control_ids.clone())?
+        .get_proof(&prover.control_id, opts.hash_suite()?.hashfn.as_ref())?;
+    Ok(SuccinctReceipt {
+        seal: receipt.seal,
+        hashfn: opts.hashfn,
+        control_id: prover.control_id,
+        control_inclusion_proof,
+        claim: claim_decoded.merge(&resolved_claim)?.into(),
+        verifier_parameters: SuccinctReceiptVerifierParameters::default().digest(),
+    })
+}
+
+/// Construct the claim proven by joining the receipts `a` and `b`, which cover consecutive spans
+/// of execution.
+pub(crate) fn joined_claim(
+    a: &SuccinctReceipt<ReceiptClaim>,
+    b: &SuccinctReceipt<ReceiptClaim>,
+) -> Result<ReceiptClaim> {
+    Ok(ReceiptClaim {
+        pre: a.claim.as_value()?.pre.clone(),
+        post: b.claim.as_value()?.post.clone(),
+        exit_code: b.claim.as_value()?.exit_code,
+        input: a.claim.as_value()?.input.clone(),
+        output: b.claim.as_value()?.output.clone(),
+    })
+}
+
+/// Construct the claim proven by resolving the head assumption of the `conditional` receipt,
+/// which is the conditional claim with that assumption removed.
+pub(crate) fn resolved_claim(conditional: &SuccinctReceipt<ReceiptClaim>) -> Result<ReceiptClaim> {
     let mut resolved_claim = conditional
         .claim
         .as_value()
@@ -184,26 +220,7 @@ where
             "cannot resolve assumption from receipt with no assumptions"
         ))?;
 
-    let opts = ProverOpts::succinct();
-    let mut prover = Prover::new_resolve(conditional, assumption, opts.clone())?;
-    let receipt = prover.prover.run()?;
-    let mut out_stream = VecDeque::<u32>::new();
-    out_stream.extend(receipt.output.iter());
-
-    let claim_decoded = ReceiptClaim::decode(&mut out_stream)?;
-    tracing::debug!("Proving resolve finished: decoded claim = {claim_decoded:#?}");
-
-    // Include an inclusion proof for control_id to allow verification against a root.
-    let control_inclusion_proof = MerkleGroup::new(opts.control_ids.clone())?
-        .get_proof(&prover.control_id, opts.hash_suite()?.hashfn.as_ref())?;
-    Ok(SuccinctReceipt {
-        seal: receipt.seal,
-        hashfn: opts.hashfn,
-        control_id: prover.control_id,
-        control_inclusion_proof,
-        claim: claim_decoded.merge(&resolved_claim)?.into(),
-        verifier_parameters: SuccinctReceiptVerifierParameters::default().digest(),
-    })
+    Ok(resolved_claim)
 }
 
 /// Prove the verification of a recursion receipt using the Poseidon254 hash function for FRI.
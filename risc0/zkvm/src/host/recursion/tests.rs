@@ -225,6 +225,35 @@ fn test_recursion_lift_join_identity_e2e() {
     rollup_receipt.verify(MULTI_TEST_ID).unwrap();
 }
 
+#[test]
+fn test_recursion_lift_join_tree() {
+    let segment_limit_po2 = 16; // 64k cycles
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::BusyLoop {
+            cycles: 3 << segment_limit_po2,
+        })
+        .unwrap()
+        .segment_limit_po2(segment_limit_po2)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    tracing::info!("Session has {} segments", session.segments.len());
+
+    let opts = ProverOpts::composite().with_recursion_workers(2);
+    let prover = get_prover_server(&opts).unwrap();
+    let ctx = VerifierContext::default();
+    let receipt = prover.prove_session(&ctx, &session).unwrap().receipt;
+    let composite = receipt.inner.composite().unwrap();
+
+    // The tree-shaped join must prove the same claim as the composite receipt.
+    let succinct = prover.composite_to_succinct(composite).unwrap();
+    succinct.verify_integrity_with_context(&ctx).unwrap();
+    assert_eq!(succinct.claim.digest(), composite.claim().unwrap().digest());
+}
+
 #[test]
 fn test_recursion_identity_sha256() {
     let default_prover = get_prover_server(&ProverOpts::succinct()).unwrap();
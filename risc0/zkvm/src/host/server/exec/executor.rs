@@ -12,9 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cell::RefCell, io::Write, rc::Rc, sync::Arc, time::Instant};
+use std::{cell::RefCell, io::Write, path::Path, rc::Rc, sync::Arc, time::Instant};
 
-use anyhow::{Context as _, Result};
+use anyhow::{ensure, Context as _, Result};
 use risc0_binfmt::{MemoryImage, Program};
 use risc0_circuit_rv32im::prove::emu::{
     addr::ByteAddr,
@@ -25,15 +25,26 @@ use risc0_circuit_rv32im::prove::emu::{
 };
 use risc0_core::scope;
 use risc0_zkp::core::digest::Digest;
-use risc0_zkvm_platform::{fileno, memory::GUEST_MAX_MEM, PAGE_SIZE};
+use risc0_zkvm_platform::{
+    fileno,
+    memory::GUEST_MAX_MEM,
+    syscall::{
+        nr::SYS_WRITE,
+        reg_abi::{REG_A3, REG_A4, REG_A5, REG_A6, REG_A7},
+    },
+    PAGE_SIZE,
+};
 use tempfile::tempdir;
 
 use crate::{
-    host::client::env::SegmentPath, Assumptions, ExecutorEnv, FileSegmentRef, Output, Segment,
-    SegmentRef, Session,
+    host::{client::env::SegmentPath, server::session::SessionSnapshot},
+    Assumptions, ExecutorEnv, ExitCode, FileSegmentRef, Output, Segment, SegmentCycles, SegmentRef,
+    Session, SessionEvents,
 };
 
 use super::{
+    gdb::GdbStub,
+    io_log::{executed_on_replay, IoLog, IoLogEntry, Request},
     profiler::Profiler,
     syscall::{SyscallContext, SyscallTable},
 };
@@ -46,6 +57,8 @@ pub struct ExecutorImpl<'a> {
     image: MemoryImage,
     pub(crate) syscall_table: SyscallTable<'a>,
     profiler: Option<Rc<RefCell<Profiler>>>,
+    debugger: Option<Rc<RefCell<GdbStub>>>,
+    io_log: Option<RefCell<IoLog>>,
 }
 
 impl<'a> ExecutorImpl<'a> {
@@ -57,7 +70,7 @@ impl<'a> ExecutorImpl<'a> {
     /// the guest program is executed to determine how its proof should be
     /// divided into subparts.
     pub fn new(env: ExecutorEnv<'a>, image: MemoryImage) -> Result<Self> {
-        Self::with_details(env, image, None)
+        Self::with_details(env, image, None, None)
     }
 
     /// Construct a new [ExecutorImpl] from the ELF binary of the guest program
@@ -80,7 +93,7 @@ impl<'a> ExecutorImpl<'a> {
         let program = Program::load_elf(elf, GUEST_MAX_MEM as u32)?;
         let image = MemoryImage::new(&program, PAGE_SIZE as u32)?;
 
-        let profiler = if env.pprof_out.is_some() {
+        let profiler = if env.pprof_out.is_some() || env.cycle_report {
             let profiler = Rc::new(RefCell::new(Profiler::new(elf, None)?));
             env.trace.push(profiler.clone());
             Some(profiler)
@@ -88,20 +101,62 @@ impl<'a> ExecutorImpl<'a> {
             None
         };
 
-        Self::with_details(env, image, profiler)
+        Self::with_details(env, image, Some(elf), profiler)
+    }
+
+    /// Construct a new [ExecutorImpl] that resumes execution of a paused [Session] from the
+    /// [SessionSnapshot] saved at the given path.
+    ///
+    /// The assumptions resolved before the session paused are added to the [ExecutorEnv], so that
+    /// they remain available to the guest. Running the resulting executor produces the same
+    /// [Session] as calling [ExecutorImpl::run] again on the executor that paused. In both cases
+    /// the journal of the resumed [Session] only holds the data committed after the pause, since
+    /// the guest starts a new journal when it resumes; the journal committed before the pause is
+    /// kept in [SessionSnapshot::journal].
+    pub fn resume_from_snapshot(mut env: ExecutorEnv<'a>, path: impl AsRef<Path>) -> Result<Self> {
+        let snapshot = SessionSnapshot::load(path)?;
+        ensure!(
+            matches!(snapshot.exit_code, ExitCode::Paused(_)),
+            "snapshot is not of a paused session: exit code is {:?}",
+            snapshot.exit_code
+        );
+
+        if env.input_digest.is_none() && snapshot.input != Digest::ZERO {
+            env.input_digest = Some(snapshot.input);
+        }
+        env.assumptions
+            .borrow_mut()
+            .0
+            .extend(snapshot.assumptions.into_iter().map(|(_, receipt)| receipt));
+
+        Self::with_details(env, snapshot.image, None, None)
     }
 
     fn with_details(
-        env: ExecutorEnv<'a>,
+        mut env: ExecutorEnv<'a>,
         image: MemoryImage,
+        elf: Option<&[u8]>,
         profiler: Option<Rc<RefCell<Profiler>>>,
     ) -> Result<Self> {
         let syscall_table = SyscallTable::from_env(&env);
+        let debugger = match (env.gdb_listener.take(), &env.gdb_addr) {
+            (Some(listener), _) => Some(GdbStub::accept(listener, elf)?),
+            (None, Some(addr)) => Some(GdbStub::listen(addr, elf)?),
+            (None, None) => None,
+        }
+        .map(|stub| Rc::new(RefCell::new(stub)));
+        let io_log = match (&env.record_io, &env.replay_io) {
+            (Some(path), _) => Some(RefCell::new(IoLog::record(path)?)),
+            (None, Some(path)) => Some(RefCell::new(IoLog::replay(path)?)),
+            (None, None) => None,
+        };
         Ok(Self {
             env,
             image,
             syscall_table,
             profiler,
+            debugger,
+            io_log,
         })
     }
 
@@ -116,6 +171,12 @@ impl<'a> ExecutorImpl<'a> {
         self.run_with_callback(|segment| Ok(Box::new(FileSegmentRef::new(&segment, &path)?)))
     }
 
+    /// Take the [SessionEvents] hooks registered on the [ExecutorEnv], for a prover that fires
+    /// them while execution is still running.
+    pub(crate) fn take_session_hooks(&mut self) -> Vec<Box<dyn SessionEvents>> {
+        std::mem::take(&mut self.env.session_hooks)
+    }
+
     /// Run the executor until [crate::ExitCode::Halted] or
     /// [crate::ExitCode::Paused] is reached, producing a [Session] as a result.
     pub fn run_with_callback<F>(&mut self, mut callback: F) -> Result<Session>
@@ -136,12 +197,16 @@ impl<'a> ExecutorImpl<'a> {
             .unwrap_or(DEFAULT_SEGMENT_LIMIT_PO2 as u32) as usize;
 
         let mut refs = Vec::new();
+        let mut segment_cycles = Vec::new();
         let mut exec = Executor::new(
             self.image.clone(),
             self,
             self.env.input_digest,
             self.env.trace.clone(),
         );
+        if let Some(debugger) = self.debugger.clone() {
+            exec = exec.with_debugger(debugger);
+        }
 
         let start_time = Instant::now();
         let result = exec.run(segment_limit_po2, self.env.session_limit, |inner| {
@@ -172,6 +237,15 @@ impl<'a> ExecutorImpl<'a> {
                 .flatten()
                 .transpose()?;
 
+            if self.profiler.is_some() {
+                segment_cycles.push(SegmentCycles::new(
+                    inner.index as u32,
+                    inner.po2 as u32,
+                    inner.insn_cycles as u64,
+                    inner.paging_cycles as u64,
+                ));
+            }
+
             let segment = Segment {
                 index: inner.index as u32,
                 inner,
@@ -200,14 +274,20 @@ impl<'a> ExecutorImpl<'a> {
         let assumptions = self.syscall_table.assumptions_used.take();
         let pending_zkrs = self.syscall_table.pending_zkrs.take();
 
+        let mut cycle_report = None;
         if let Some(profiler) = self.profiler.take() {
-            let report = profiler.borrow_mut().finalize_to_vec();
-            std::fs::write(self.env.pprof_out.as_ref().unwrap(), report)?;
+            let mut profiler = profiler.borrow_mut();
+            if let Some(pprof_out) = self.env.pprof_out.as_ref() {
+                std::fs::write(pprof_out, profiler.finalize_to_vec())?;
+            }
+            if self.env.cycle_report {
+                cycle_report = Some(profiler.cycle_report(segment_cycles));
+            }
         }
 
         self.image = result.post_image.clone();
 
-        let session = Session::new(
+        let mut session = Session::new(
             refs,
             self.env.input_digest.unwrap_or_default(),
             session_journal,
@@ -222,6 +302,8 @@ impl<'a> ExecutorImpl<'a> {
             result.post_state,
             pending_zkrs,
         );
+        session.cycle_report = cycle_report;
+        session.hooks = std::mem::take(&mut self.env.session_hooks);
 
         tracing::info!("execution time: {elapsed:?}");
         session.log();
@@ -233,6 +315,8 @@ impl<'a> ExecutorImpl<'a> {
 struct ContextAdapter<'a, 'b> {
     ctx: &'b mut dyn NewSyscallContext,
     syscall_table: SyscallTable<'a>,
+    // The guest memory loaded by the syscall, when I/O is being recorded.
+    loaded: Option<Request>,
 }
 
 impl<'a, 'b> SyscallContext<'a> for ContextAdapter<'a, 'b> {
@@ -253,11 +337,19 @@ impl<'a, 'b> SyscallContext<'a> for ContextAdapter<'a, 'b> {
     }
 
     fn load_u8(&mut self, addr: ByteAddr) -> Result<u8> {
-        self.ctx.peek_u8(addr)
+        let byte = self.ctx.peek_u8(addr)?;
+        if let Some(loaded) = self.loaded.as_mut() {
+            loaded.push(addr.0, &[byte]);
+        }
+        Ok(byte)
     }
 
     fn load_u32(&mut self, addr: ByteAddr) -> Result<u32> {
-        self.ctx.peek_u32(addr)
+        let word = self.ctx.peek_u32(addr)?;
+        if let Some(loaded) = self.loaded.as_mut() {
+            loaded.push(addr.0, &word.to_le_bytes());
+        }
+        Ok(word)
     }
 
     fn syscall_table(&self) -> &SyscallTable<'a> {
@@ -265,6 +357,59 @@ impl<'a, 'b> SyscallContext<'a> for ContextAdapter<'a, 'b> {
     }
 }
 
+impl<'a> ExecutorImpl<'a> {
+    fn dispatch_syscall(
+        &self,
+        syscall: &str,
+        ctx: &mut ContextAdapter<'a, '_>,
+        into_guest: &mut [u32],
+    ) -> Result<(u32, u32)> {
+        self.syscall_table
+            .get_syscall(syscall)
+            .context(format!("Unknown syscall: {syscall:?}"))?
+            .borrow_mut()
+            .syscall(syscall, ctx, into_guest)
+    }
+
+    fn replay_syscall(
+        &self,
+        io_log: &RefCell<IoLog>,
+        syscall: &str,
+        ctx: &mut ContextAdapter<'a, '_>,
+        into_guest: &mut [u32],
+    ) -> Result<(u32, u32)> {
+        let args = syscall_args(ctx);
+        let entry = io_log
+            .borrow_mut()
+            .next(syscall, &args, |addr| ctx.ctx.peek_u8(ByteAddr(addr)))?;
+        if executed_on_replay(syscall) {
+            // Writes to file descriptors of the original host have nowhere to go.
+            let dropped = syscall == SYS_WRITE.as_str()
+                && self
+                    .syscall_table
+                    .posix_io
+                    .borrow()
+                    .get_writer(args[0])
+                    .is_err();
+            if !dropped {
+                return self.dispatch_syscall(syscall, ctx, into_guest);
+            }
+        }
+        ensure!(
+            entry.to_guest.len() == into_guest.len(),
+            "replay diverged: {syscall} recorded {} words for the guest, but {} were requested",
+            entry.to_guest.len(),
+            into_guest.len()
+        );
+        into_guest.copy_from_slice(&entry.to_guest);
+        Ok(entry.ret)
+    }
+}
+
+fn syscall_args(ctx: &mut dyn SyscallContext) -> [u32; 5] {
+    [REG_A3, REG_A4, REG_A5, REG_A6, REG_A7].map(|idx| ctx.load_register(idx))
+}
+
 impl<'a> NewSyscall for ExecutorImpl<'a> {
     fn syscall(
         &self,
@@ -272,15 +417,38 @@ impl<'a> NewSyscall for ExecutorImpl<'a> {
         ctx: &mut dyn NewSyscallContext,
         into_guest: &mut [u32],
     ) -> Result<(u32, u32)> {
+        if let Some(profiler) = self.profiler.as_ref() {
+            profiler.borrow_mut().on_syscall(syscall);
+        }
+        let recording = self
+            .io_log
+            .as_ref()
+            .is_some_and(|io_log| matches!(*io_log.borrow(), IoLog::Record(_)));
         let mut ctx = ContextAdapter {
             ctx,
             syscall_table: self.syscall_table.clone(),
+            loaded: recording.then(Request::default),
         };
-        self.syscall_table
-            .get_syscall(syscall)
-            .context(format!("Unknown syscall: {syscall:?}"))?
-            .borrow_mut()
-            .syscall(syscall, &mut ctx, into_guest)
+        match self.io_log.as_ref() {
+            Some(io_log) if !recording => {
+                self.replay_syscall(io_log, syscall, &mut ctx, into_guest)
+            }
+            Some(io_log) => {
+                let args = syscall_args(&mut ctx);
+                let ret = self.dispatch_syscall(syscall, &mut ctx, into_guest)?;
+                let request = ctx.loaded.take().unwrap_or_default();
+                io_log.borrow_mut().append(&IoLogEntry {
+                    syscall: syscall.to_string(),
+                    args,
+                    request: request.bytes,
+                    request_ranges: request.ranges,
+                    to_guest: into_guest.to_vec(),
+                    ret,
+                })?;
+                Ok(ret)
+            }
+            None => self.dispatch_syscall(syscall, &mut ctx, into_guest),
+        }
     }
 }
 
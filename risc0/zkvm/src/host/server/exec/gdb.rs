@@ -0,0 +1,406 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for debugging the guest with GDB.
+//!
+//! This implements the subset of the GDB remote serial protocol needed to
+//! set breakpoints, single-step, continue, and read registers and memory. The
+//! executor hands control to GDB before each instruction is executed, so the
+//! guest runs exactly as it would without a debugger attached.
+//!
+//! Memory and register writes are not supported, since they would cause the
+//! execution to diverge from the guest program being proven.
+
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use addr2line::{object::File, ObjectContext};
+use anyhow::{anyhow, bail, Context as _, Result};
+use risc0_binfmt::ExitCode;
+use risc0_circuit_rv32im::prove::emu::{
+    addr::ByteAddr,
+    exec::{Debugger, SyscallContext},
+};
+use risc0_zkvm_platform::syscall::reg_abi::REG_MAX;
+
+use super::profiler::lookup_pc;
+
+/// Byte sent by GDB to interrupt a running guest.
+const INTERRUPT: u8 = 0x03;
+
+/// Number of instructions executed between checks for an interrupt from GDB.
+const INTERRUPT_POLL_STEPS: u64 = 1 << 16;
+
+/// Register number used by GDB for the program counter.
+const PC_REGNUM: usize = 32;
+
+/// Maximum number of bytes returned for a single memory read.
+const MAX_READ_BYTES: usize = 0x1000;
+
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+
+/// What the guest should do when the debugger is next given control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Wait for commands from GDB.
+    Stopped,
+    /// Execute a single instruction, then stop.
+    Step,
+    /// Execute until a breakpoint is hit or GDB interrupts the guest.
+    Continue,
+    /// GDB has detached; execute until the guest exits.
+    Detached,
+}
+
+/// A GDB remote serial protocol server driving the executor.
+pub(crate) struct GdbStub {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    no_ack: bool,
+    mode: Mode,
+    breakpoints: BTreeSet<u32>,
+    stop_reply: String,
+    steps: u64,
+    symbols: Option<ObjectContext>,
+}
+
+impl GdbStub {
+    /// Listen on the given address and wait for GDB to connect.
+    ///
+    /// When the guest ELF is provided, its debug info is used to report where the guest stopped.
+    pub(crate) fn listen(addr: &str, elf: Option<&[u8]>) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("failed to listen on {addr}"))?;
+        Self::accept(listener, elf)
+    }
+
+    /// Wait for GDB to connect on the given listener.
+    pub(crate) fn accept(listener: TcpListener, elf: Option<&[u8]>) -> Result<Self> {
+        let symbols = elf
+            .map(|elf| -> Result<_> { Ok(ObjectContext::new(&File::parse(elf)?)?) })
+            .transpose()?;
+
+        tracing::info!("waiting for GDB to connect on {}", listener.local_addr()?);
+        let (stream, peer) = listener.accept()?;
+        stream.set_nodelay(true)?;
+        tracing::info!("GDB connected from {peer}");
+
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            no_ack: false,
+            mode: Mode::Stopped,
+            breakpoints: BTreeSet::new(),
+            stop_reply: format!("S{SIGTRAP:02x}"),
+            steps: 0,
+            symbols,
+        })
+    }
+
+    /// Report that the guest stopped, then serve commands until GDB resumes execution.
+    fn stop(&mut self, ctx: &mut dyn SyscallContext, reply: String) -> Result<()> {
+        let pc = ctx.get_pc();
+        tracing::info!("stopped at 0x{pc:08x}{}", self.describe(pc));
+        self.mode = Mode::Stopped;
+        self.stop_reply = reply.clone();
+        self.send_packet(&reply)?;
+        self.serve(ctx)
+    }
+
+    /// Serve commands from GDB until it resumes execution.
+    fn serve(&mut self, ctx: &mut dyn SyscallContext) -> Result<()> {
+        while self.mode == Mode::Stopped {
+            let packet = self.read_packet()?;
+            let packet = String::from_utf8(packet).context("malformed GDB packet")?;
+            tracing::trace!("gdb> {packet}");
+            let reply = self.handle(ctx, &packet)?;
+            if let Some(reply) = reply {
+                self.send_packet(&reply)?;
+            }
+            if packet == "QStartNoAckMode" {
+                self.no_ack = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a single command, returning the reply to send to GDB, if any.
+    fn handle(&mut self, ctx: &mut dyn SyscallContext, packet: &str) -> Result<Option<String>> {
+        let reply = match packet {
+            "?" => self.stop_reply.clone(),
+            "QStartNoAckMode" => "OK".into(),
+            "qAttached" => "1".into(),
+            "qC" => "QC1".into(),
+            "qfThreadInfo" => "m1".into(),
+            "qsThreadInfo" => "l".into(),
+            "g" => {
+                let mut regs = String::new();
+                for idx in 0..=PC_REGNUM {
+                    regs.push_str(&read_register(ctx, idx)?);
+                }
+                regs
+            }
+            "c" => {
+                self.mode = Mode::Continue;
+                return Ok(None);
+            }
+            "s" => {
+                self.mode = Mode::Step;
+                return Ok(None);
+            }
+            "D" => {
+                tracing::info!("GDB detached");
+                self.mode = Mode::Detached;
+                "OK".into()
+            }
+            "k" => bail!("guest killed by GDB"),
+            _ if packet.starts_with("qSupported") => {
+                "PacketSize=1000;swbreak+;hwbreak+;QStartNoAckMode+".into()
+            }
+            _ if packet.starts_with('H') => "OK".into(),
+            _ if packet.starts_with("qRcmd,") => self.monitor(ctx, &packet[6..])?,
+            _ if packet.starts_with('p') => match usize::from_str_radix(&packet[1..], 16) {
+                Ok(idx) if idx <= PC_REGNUM => read_register(ctx, idx)?,
+                _ => "E01".into(),
+            },
+            _ if packet.starts_with('m') => match parse_addr_len(&packet[1..]) {
+                Some((addr, len)) => read_memory(ctx, addr, len),
+                None => "E01".into(),
+            },
+            _ if packet.starts_with("Z0,") || packet.starts_with("Z1,") => {
+                match parse_addr_len(&packet[3..]) {
+                    Some((addr, _)) => {
+                        self.breakpoints.insert(addr);
+                        "OK".into()
+                    }
+                    None => "E01".into(),
+                }
+            }
+            _ if packet.starts_with("z0,") || packet.starts_with("z1,") => {
+                match parse_addr_len(&packet[3..]) {
+                    Some((addr, _)) => {
+                        self.breakpoints.remove(&addr);
+                        "OK".into()
+                    }
+                    None => "E01".into(),
+                }
+            }
+            // An empty reply tells GDB the command is not supported.
+            _ => String::new(),
+        };
+        Ok(Some(reply))
+    }
+
+    /// Handle a `monitor` command, returning its hex-encoded output.
+    fn monitor(&mut self, ctx: &mut dyn SyscallContext, cmd: &str) -> Result<String> {
+        let cmd = String::from_utf8(hex::decode(cmd)?)?;
+        let output = match cmd.trim() {
+            "cycles" => format!("{}\n", ctx.get_cycle()),
+            "where" => {
+                let pc = ctx.get_pc();
+                format!("0x{pc:08x}{}\n", self.describe(pc))
+            }
+            _ => "supported monitor commands: cycles, where\n".into(),
+        };
+        Ok(hex::encode(output))
+    }
+
+    /// Describe the source location of the given program counter.
+    fn describe(&self, pc: u32) -> String {
+        let Some(symbols) = self.symbols.as_ref() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        for frame in lookup_pc(pc, symbols) {
+            write!(
+                out,
+                " in {} at {}:{}",
+                frame.name, frame.filename, frame.lineno
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Check whether GDB has sent an interrupt, without blocking.
+    fn interrupted(&mut self) -> Result<bool> {
+        self.reader.get_ref().set_nonblocking(true)?;
+        let next = match self.reader.fill_buf() {
+            Ok(buf) => buf
+                .first()
+                .copied()
+                .map(Some)
+                .ok_or_else(|| anyhow!("GDB disconnected")),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(err.into()),
+        };
+        self.reader.get_ref().set_nonblocking(false)?;
+
+        if next? == Some(INTERRUPT) {
+            self.reader.consume(1);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        let mut byte = [0u8];
+        self.reader
+            .read_exact(&mut byte)
+            .context("GDB disconnected")?;
+        Ok(byte[0])
+    }
+
+    /// Read the payload of the next packet, acknowledging it if required.
+    fn read_packet(&mut self) -> Result<Vec<u8>> {
+        loop {
+            // Skip acknowledgements and interrupts received while already stopped.
+            while self.read_byte()? != b'$' {}
+
+            let mut data = Vec::new();
+            loop {
+                match self.read_byte()? {
+                    b'#' => break,
+                    byte => data.push(byte),
+                }
+            }
+            let mut checksum = [0u8; 2];
+            self.reader.read_exact(&mut checksum)?;
+            let expected = u8::from_str_radix(std::str::from_utf8(&checksum)?, 16)?;
+
+            if self.no_ack {
+                return Ok(data);
+            }
+            if expected == packet_checksum(&data) {
+                self.writer.write_all(b"+")?;
+                return Ok(data);
+            }
+            self.writer.write_all(b"-")?;
+        }
+    }
+
+    /// Send a packet, retransmitting it until GDB acknowledges it.
+    fn send_packet(&mut self, data: &str) -> Result<()> {
+        tracing::trace!("gdb< {data}");
+        let packet = format!("${data}#{:02x}", packet_checksum(data.as_bytes()));
+        loop {
+            self.writer.write_all(packet.as_bytes())?;
+            self.writer.flush()?;
+            if self.no_ack {
+                return Ok(());
+            }
+            loop {
+                match self.read_byte()? {
+                    b'+' => return Ok(()),
+                    b'-' => break,
+                    _ => continue,
+                }
+            }
+        }
+    }
+}
+
+impl Debugger for GdbStub {
+    fn on_step(&mut self, ctx: &mut dyn SyscallContext) -> Result<()> {
+        match self.mode {
+            Mode::Detached => Ok(()),
+            Mode::Stopped => self.serve(ctx),
+            Mode::Step => self.stop(ctx, format!("S{SIGTRAP:02x}")),
+            Mode::Continue => {
+                if self.breakpoints.contains(&ctx.get_pc()) {
+                    return self.stop(ctx, format!("T{SIGTRAP:02x}swbreak:;"));
+                }
+                self.steps += 1;
+                if self.steps % INTERRUPT_POLL_STEPS == 0 && self.interrupted()? {
+                    return self.stop(ctx, format!("S{SIGINT:02x}"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn on_exit(&mut self, ctx: &mut dyn SyscallContext, exit_code: ExitCode) -> Result<()> {
+        match (self.mode, exit_code) {
+            (Mode::Detached, _) => Ok(()),
+            // A paused guest can be resumed, so stay attached.
+            (_, ExitCode::Paused(_)) => self.stop(ctx, format!("S{SIGTRAP:02x}")),
+            (_, ExitCode::Halted(code)) => {
+                self.mode = Mode::Detached;
+                self.send_packet(&format!("W{:02x}", code as u8))
+            }
+            _ => {
+                self.mode = Mode::Detached;
+                self.send_packet(&format!("X{SIGTRAP:02x}"))
+            }
+        }
+    }
+}
+
+fn packet_checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Parse an `addr,length` pair of hex numbers, ignoring any trailing `;` options.
+fn parse_addr_len(args: &str) -> Option<(u32, usize)> {
+    let args = args.split(';').next()?;
+    let (addr, len) = args.split_once(',')?;
+    Some((
+        u32::from_str_radix(addr, 16).ok()?,
+        usize::from_str_radix(len, 16).ok()?,
+    ))
+}
+
+/// Read a register in GDB's encoding: a little-endian, hex-encoded word.
+fn read_register(ctx: &mut dyn SyscallContext, idx: usize) -> Result<String> {
+    let value = match idx {
+        0 => 0,
+        PC_REGNUM => ctx.get_pc(),
+        idx if idx < REG_MAX => ctx.peek_register(idx)?,
+        _ => bail!("invalid register: {idx}"),
+    };
+    Ok(hex::encode(value.to_le_bytes()))
+}
+
+/// Read hex-encoded guest memory, stopping at the first inaccessible address.
+fn read_memory(ctx: &mut dyn SyscallContext, addr: u32, len: usize) -> String {
+    let mut bytes = Vec::new();
+    for offset in 0..len.min(MAX_READ_BYTES) {
+        match ctx.peek_u8(ByteAddr(addr.wrapping_add(offset as u32))) {
+            Ok(byte) => bytes.push(byte),
+            Err(_) => break,
+        }
+    }
+    if bytes.is_empty() && len > 0 {
+        return "E14".into();
+    }
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{packet_checksum, parse_addr_len};
+
+    #[test]
+    fn packet_parsing() {
+        assert_eq!(packet_checksum(b"qSupported"), 0x37);
+        assert_eq!(parse_addr_len("200000,4"), Some((0x200000, 4)));
+        assert_eq!(parse_addr_len("200000,4;X1,0"), Some((0x200000, 4)));
+        assert_eq!(parse_addr_len("200000"), None);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording and replaying of the host side of system calls.
+//!
+//! A recorded execution can be replayed without any of the host-side handlers that served it,
+//! e.g. stdin, `read_fd`, `slice_io` or custom syscall handlers.
+
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use risc0_zkvm_platform::syscall::nr::{
+    SYS_FORK, SYS_KECCAK, SYS_LOG, SYS_PANIC, SYS_PIPE, SYS_PROVE_KECCAK, SYS_PROVE_ZKR,
+    SYS_VERIFY_INTEGRITY, SYS_WRITE,
+};
+use serde::{Deserialize, Serialize};
+
+/// A single system call made by the guest, along with the reply of the host.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct IoLogEntry {
+    /// The name of the system call.
+    pub syscall: String,
+
+    /// The argument registers `a3` through `a7`.
+    pub args: [u32; 5],
+
+    /// The bytes of guest memory read by the host while handling the system call.
+    pub request: Vec<u8>,
+
+    /// The ranges of guest memory that `request` was read from, as `(address, length)` pairs.
+    pub request_ranges: Vec<(u32, u32)>,
+
+    /// The words written into guest memory.
+    pub to_guest: Vec<u32>,
+
+    /// The values returned in registers `a0` and `a1`.
+    pub ret: (u32, u32),
+}
+
+/// The guest memory read by the host while handling a system call.
+#[derive(Default)]
+pub(crate) struct Request {
+    pub bytes: Vec<u8>,
+    pub ranges: Vec<(u32, u32)>,
+}
+
+impl Request {
+    /// Add bytes read from guest memory at the given address.
+    pub fn push(&mut self, addr: u32, bytes: &[u8]) {
+        match self.ranges.last_mut() {
+            Some((start, len)) if start.wrapping_add(*len) == addr => *len += bytes.len() as u32,
+            _ => self.ranges.push((addr, bytes.len() as u32)),
+        }
+        self.bytes.extend_from_slice(bytes);
+    }
+}
+
+/// A log of system calls that is either being recorded or replayed.
+pub(crate) enum IoLog {
+    Record(BufWriter<File>),
+    Replay {
+        path: PathBuf,
+        entries: VecDeque<IoLogEntry>,
+        index: usize,
+    },
+}
+
+impl IoLog {
+    /// Record system calls to a new file at the given path.
+    pub fn record(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create I/O log {}", path.display()))?;
+        Ok(Self::Record(BufWriter::new(file)))
+    }
+
+    /// Replay the system calls recorded in the file at the given path.
+    pub fn replay(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open I/O log {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+        let mut entries = VecDeque::new();
+        while !reader.fill_buf()?.is_empty() {
+            let entry = bincode::deserialize_from(&mut reader)
+                .with_context(|| format!("malformed I/O log {}", path.display()))?;
+            entries.push_back(entry);
+        }
+        Ok(Self::Replay {
+            path: path.to_path_buf(),
+            entries,
+            index: 0,
+        })
+    }
+
+    /// Append an entry to the log being recorded.
+    pub fn append(&mut self, entry: &IoLogEntry) -> Result<()> {
+        let Self::Record(writer) = self else {
+            bail!("I/O log is not being recorded");
+        };
+        bincode::serialize_into(&mut *writer, entry)?;
+        // Flush eagerly, so the log is complete even if the execution fails.
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Take the next recorded entry, checking that it matches the system call the guest made.
+    ///
+    /// The recorded request is compared with the guest memory it was read from, which is loaded
+    /// with `load_u8`.
+    pub fn next(
+        &mut self,
+        syscall: &str,
+        args: &[u32; 5],
+        mut load_u8: impl FnMut(u32) -> Result<u8>,
+    ) -> Result<IoLogEntry> {
+        let Self::Replay {
+            path,
+            entries,
+            index,
+        } = self
+        else {
+            bail!("I/O log is not being replayed");
+        };
+        let entry = entries.pop_front().with_context(|| {
+            format!(
+                "replay diverged: guest made {syscall} as syscall #{index}, but {} ends there",
+                path.display()
+            )
+        })?;
+        ensure!(
+            entry.syscall == syscall && &entry.args == args,
+            "replay diverged at syscall #{index}: guest made {syscall} with {args:x?}, \
+             but {} recorded {} with {:x?}",
+            path.display(),
+            entry.syscall,
+            entry.args
+        );
+        let request = entry
+            .request_ranges
+            .iter()
+            .flat_map(|&(addr, len)| (0..len).map(move |offset| addr.wrapping_add(offset)))
+            .map(&mut load_u8)
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            request == entry.request,
+            "replay diverged at syscall #{index}: guest made {syscall} with a request that \
+             differs from the one recorded in {}",
+            path.display()
+        );
+        *index += 1;
+        Ok(entry)
+    }
+}
+
+/// Returns true if the system call is executed again when replaying, rather than being served
+/// from the log.
+///
+/// The executor depends on the side effects of these syscalls, e.g. the journal or the
+/// assumptions used by the guest. Their results only depend on the guest, so they need none of
+/// the host-side handlers that are absent during replay.
+pub(crate) fn executed_on_replay(syscall: &str) -> bool {
+    [
+        SYS_FORK,
+        SYS_KECCAK,
+        SYS_LOG,
+        SYS_PANIC,
+        SYS_PIPE,
+        SYS_PROVE_KECCAK,
+        SYS_PROVE_ZKR,
+        SYS_VERIFY_INTEGRITY,
+        SYS_WRITE,
+    ]
+    .iter()
+    .any(|name| name.as_str() == syscall)
+}
@@ -19,6 +19,8 @@
 //! contains an execution trace of the specified program.
 
 pub(crate) mod executor;
+mod gdb;
+mod io_log;
 pub(crate) mod profiler;
 mod proto;
 pub(crate) mod syscall;
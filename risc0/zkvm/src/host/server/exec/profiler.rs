@@ -25,7 +25,7 @@
 
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Write,
     hash::{Hash, Hasher},
     rc::Rc,
@@ -40,12 +40,18 @@ use addr2line::{
 use anyhow::{anyhow, Result};
 use elf::{abi::STT_FUNC, endian::LittleEndian, ElfBytes};
 use prost::Message;
-use risc0_zkvm_platform::memory::TEXT_START;
+use risc0_zkvm_platform::{
+    memory::TEXT_START,
+    syscall::{ecall, reg_abi::REG_T0},
+};
 use rrs_lib::instruction_formats::{IType, JType, OPCODE_JAL, OPCODE_JALR};
 use rustc_demangle::demangle;
 
 use super::proto;
-use crate::{TraceCallback, TraceEvent};
+use crate::{CycleReport, FunctionCycles, SegmentCycles, SyscallCycles, TraceCallback, TraceEvent};
+
+/// Encoding of the `ecall` instruction.
+const ECALL_INSN: u32 = 0x0000_0073;
 
 /// Operations effecting the function call stack.
 #[derive(Debug)]
@@ -160,6 +166,18 @@ pub struct Profiler {
     ctx: ObjectContext,
 
     profile: ProfileBuilder,
+
+    // Last value written to the t0 register, which selects the kind of ecall
+    t0: u32,
+
+    // Name of the last software syscall dispatched by the executor
+    syscall_name: Option<String>,
+
+    // Name of the ecall at self.pc, if self.insn is an ecall
+    ecall: Option<String>,
+
+    // Number of calls and cycles spent in each kind of ecall
+    syscalls: BTreeMap<String, (u64, u64)>,
 }
 
 /// Represents a frame.
@@ -183,7 +201,7 @@ fn decode_frame(fr: addr2line::Frame<EndianRcSlice<RunTimeEndian>>) -> Option<Fr
     })
 }
 
-fn lookup_pc(pc: u32, ctx: &ObjectContext) -> Vec<Frame> {
+pub(crate) fn lookup_pc(pc: u32, ctx: &ObjectContext) -> Vec<Frame> {
     let frames = match ctx.find_frames(pc as u64) {
         LookupResult::Output(result) => result.unwrap(),
         LookupResult::Load {
@@ -223,6 +241,10 @@ impl Profiler {
             call_stack_path: Vec::new(),
             ctx,
             profile: ProfileBuilder::new(),
+            t0: 0,
+            syscall_name: None,
+            ecall: None,
+            syscalls: BTreeMap::new(),
         };
 
         // Save the main binary name
@@ -338,6 +360,91 @@ impl Profiler {
         }
     }
 
+    /// Walk the profile tree rooted at node_ref, attributing the cycles of each call stack to the
+    /// functions on it. All call stacks encountered build on top of the base_stack.
+    fn walk_functions(
+        &self,
+        node_ref: &Rc<RefCell<CallNode>>,
+        base_stack: &[String],
+        functions: &mut BTreeMap<String, (u64, u64)>,
+    ) {
+        let node = node_ref.borrow();
+        for (&pc, &count) in &node.counts {
+            let mut new_stack = base_stack.to_vec();
+            let frames = self.lookup_pc(pc.into());
+            if frames.is_empty() {
+                new_stack.push(format!("0x{pc:08x}"));
+            } else {
+                new_stack.extend(frames.into_iter().map(|fr| fr.name));
+            }
+
+            // Self cycles belong to the innermost frame, inclusive cycles to every distinct
+            // function on the stack.
+            let count = count as u64;
+            if let Some(name) = new_stack.last() {
+                functions.entry(name.clone()).or_default().0 += count;
+            }
+            for name in new_stack.iter().collect::<BTreeSet<_>>() {
+                functions.entry(name.clone()).or_default().1 += count;
+            }
+
+            if let Some(next_node_ref) = node.calls.get(&pc) {
+                self.walk_functions(next_node_ref, &new_stack, functions);
+            }
+        }
+    }
+
+    /// Record the name of a software syscall dispatched by the executor, so that the cycles of
+    /// the ecall that made it can be attributed to it.
+    pub(crate) fn on_syscall(&mut self, syscall: &str) {
+        let name = syscall.rsplit("::").next().unwrap_or(syscall);
+        self.syscall_name = Some(name.to_string());
+    }
+
+    /// Summarize the cycles attributed to each function and syscall, along with the cycles spent
+    /// in each of the given segments.
+    pub(crate) fn cycle_report(&self, segments: Vec<SegmentCycles>) -> CycleReport {
+        let mut functions = BTreeMap::new();
+        self.walk_functions(&self.root, &[], &mut functions);
+        let mut functions: Vec<_> = functions
+            .into_iter()
+            .map(|(name, (self_cycles, inclusive_cycles))| {
+                FunctionCycles::new(name, self_cycles, inclusive_cycles)
+            })
+            .collect();
+        functions.sort_by(|a, b| b.self_cycles.cmp(&a.self_cycles));
+
+        let mut syscalls: Vec<_> = self
+            .syscalls
+            .iter()
+            .map(|(name, &(calls, cycles))| SyscallCycles::new(name.clone(), calls, cycles))
+            .collect();
+        syscalls.sort_by(|a, b| b.cycles.cmp(&a.cycles));
+
+        CycleReport {
+            functions,
+            syscalls,
+            segments,
+        }
+    }
+
+    /// Returns the name of the ecall being made, based on the current machine state.
+    fn ecall_name(&mut self) -> String {
+        match self.t0 {
+            ecall::HALT => "halt".into(),
+            ecall::INPUT => "input".into(),
+            ecall::SOFTWARE => self
+                .syscall_name
+                .take()
+                .unwrap_or_else(|| "software".into()),
+            ecall::SHA => "sha".into(),
+            ecall::BIGINT => "bigint".into(),
+            ecall::USER => "user".into(),
+            ecall::BIGINT2 => "bigint2".into(),
+            other => format!("ecall({other})"),
+        }
+    }
+
     /// Count and save the profiling samples, write the results to `output_path`.
     #[cfg(test)]
     pub(crate) fn finalize(mut self) -> ProfileBuilder {
@@ -366,6 +473,15 @@ impl TraceCallback for Profiler {
                 let orig_pc = self.pc;
                 let orig_insn = self.insn;
 
+                if let Some(name) = self.ecall.take() {
+                    let entry = self.syscalls.entry(name).or_default();
+                    entry.0 += 1;
+                    entry.1 += cycles;
+                }
+                if insn == ECALL_INSN {
+                    self.ecall = Some(self.ecall_name());
+                }
+
                 if !self.call_stack_path.is_empty() {
                     let current_node = self
                         .current_node
@@ -438,7 +554,11 @@ impl TraceCallback for Profiler {
                 self.insn = insn;
                 self.cycle = cycle;
             }
-            TraceEvent::RegisterSet { .. } => (),
+            TraceEvent::RegisterSet { idx, value } => {
+                if idx == REG_T0 {
+                    self.t0 = value;
+                }
+            }
             TraceEvent::MemorySet { .. } => (),
         }
         Ok(())
@@ -0,0 +1,79 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Result;
+use risc0_circuit_rv32im::prove::emu::addr::ByteAddr;
+
+use crate::host::client::syscall::{
+    Syscall as CustomSyscall, SyscallContext as CustomSyscallContext, SyscallRef,
+};
+
+use super::{Syscall, SyscallContext};
+
+/// A wrapper around a user-provided syscall handler that exposes it as a
+/// [Syscall] handler.
+pub(crate) struct SysCustom<'a> {
+    handler: SyscallRef<'a>,
+}
+
+impl<'a> SysCustom<'a> {
+    pub(crate) fn new(handler: SyscallRef<'a>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a> Syscall for SysCustom<'a> {
+    fn syscall(
+        &mut self,
+        syscall: &str,
+        ctx: &mut dyn SyscallContext,
+        to_guest: &mut [u32],
+    ) -> Result<(u32, u32)> {
+        let mut ctx = ContextAdapter { ctx };
+        self.handler
+            .borrow_mut()
+            .syscall(syscall, &mut ctx, to_guest)
+    }
+}
+
+/// Restricts the crate-internal [SyscallContext] to the public interface.
+struct ContextAdapter<'a, 'b> {
+    ctx: &'b mut dyn SyscallContext<'a>,
+}
+
+impl<'a, 'b> CustomSyscallContext for ContextAdapter<'a, 'b> {
+    fn get_pc(&self) -> u32 {
+        self.ctx.get_pc()
+    }
+
+    fn get_cycle(&self) -> u64 {
+        self.ctx.get_cycle()
+    }
+
+    fn load_register(&mut self, idx: usize) -> u32 {
+        self.ctx.load_register(idx)
+    }
+
+    fn load_u8(&mut self, addr: u32) -> Result<u8> {
+        self.ctx.load_u8(ByteAddr(addr))
+    }
+
+    fn load_u32(&mut self, addr: u32) -> Result<u32> {
+        self.ctx.load_u32(ByteAddr(addr))
+    }
+
+    fn load_region(&mut self, addr: u32, size: u32) -> Result<Vec<u8>> {
+        self.ctx.load_region(ByteAddr(addr), size)
+    }
+}
@@ -15,6 +15,7 @@
 //! Handlers for two-way private I/O between host and guest.
 
 mod args;
+mod custom;
 mod cycle_count;
 mod fork;
 mod getenv;
@@ -55,10 +56,10 @@ use crate::{
 };
 
 use self::{
-    args::SysArgs, cycle_count::SysCycleCount, fork::SysFork, getenv::SysGetenv, keccak::SysKeccak,
-    log::SysLog, panic::SysPanic, pipe::SysPipe, posix_io::SysRead, posix_io::SysWrite,
-    prove_keccak::SysProveKeccak, prove_zkr::SysProveZkr, random::SysRandom, slice_io::SysSliceIo,
-    verify::SysVerify,
+    args::SysArgs, custom::SysCustom, cycle_count::SysCycleCount, fork::SysFork, getenv::SysGetenv,
+    keccak::SysKeccak, log::SysLog, panic::SysPanic, pipe::SysPipe, posix_io::SysRead,
+    posix_io::SysWrite, prove_keccak::SysProveKeccak, prove_zkr::SysProveZkr, random::SysRandom,
+    slice_io::SysSliceIo, verify::SysVerify,
 };
 
 /// A host-side implementation of a system call.
@@ -162,6 +163,11 @@ impl<'a> SyscallTable<'a> {
             this.inner
                 .insert(syscall.clone(), Rc::new(RefCell::new(handler)));
         }
+        for (syscall, handler) in env.syscalls.borrow().inner.iter() {
+            let handler = SysCustom::new(handler.clone());
+            this.inner
+                .insert(syscall.clone(), Rc::new(RefCell::new(handler)));
+        }
 
         this
     }
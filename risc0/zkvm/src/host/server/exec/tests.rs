@@ -14,7 +14,8 @@
 
 use std::{
     collections::{BTreeMap, HashSet},
-    io::Cursor,
+    io::{Cursor, Read, Write},
+    net::{TcpListener, TcpStream},
     str::from_utf8,
     sync::Mutex,
 };
@@ -211,6 +212,129 @@ fn host_syscall_words() {
     assert_eq!(session.exit_code, ExitCode::Halted(0));
 }
 
+#[test]
+fn host_custom_syscall() {
+    use risc0_zkvm_platform::syscall::reg_abi::{REG_A3, REG_A4};
+
+    #[derive(Default)]
+    struct SwapWords {
+        from_guest: Vec<u8>,
+        cycles: Vec<u64>,
+    }
+
+    impl crate::Syscall for SwapWords {
+        fn syscall(
+            &mut self,
+            _syscall: &str,
+            ctx: &mut dyn crate::SyscallContext,
+            to_guest: &mut [u32],
+        ) -> Result<(u32, u32)> {
+            self.cycles.push(ctx.get_cycle());
+            if to_guest.is_empty() {
+                // First call: read the request directly from guest memory.
+                let buf_ptr = ctx.load_register(REG_A3);
+                let buf_len = ctx.load_register(REG_A4);
+                self.from_guest = ctx.load_region(buf_ptr, buf_len)?;
+                return Ok((self.from_guest.len() as u32, 0));
+            }
+            // Second call: reply with the request, reinterpreted as words.
+            to_guest.copy_from_slice(bytemuck::cast_slice(&self.from_guest));
+            Ok((0, 0))
+        }
+    }
+
+    let mut handler = SwapWords::default();
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::SyscallWords)
+        .unwrap()
+        .syscall(SYS_MULTI_TEST_WORDS, &mut handler)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    assert_eq!(handler.from_guest, 0x0102030405060708u64.to_le_bytes());
+    assert_eq!(handler.cycles.len(), 2);
+    assert!(handler.cycles[0] < handler.cycles[1]);
+}
+
+#[test]
+fn host_custom_syscall_reserved_name() {
+    struct Nop;
+
+    impl crate::Syscall for Nop {
+        fn syscall(
+            &mut self,
+            _syscall: &str,
+            _ctx: &mut dyn crate::SyscallContext,
+            _to_guest: &mut [u32],
+        ) -> Result<(u32, u32)> {
+            Ok((0, 0))
+        }
+    }
+
+    let err = ExecutorEnv::builder()
+        .syscall(SYS_RANDOM, Nop)
+        .build()
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .contains("conflicts with a built-in syscall"));
+
+    let err = ExecutorEnv::builder()
+        .io_callback(SYS_MULTI_TEST_WORDS, Ok)
+        .syscall(SYS_MULTI_TEST_WORDS, Nop)
+        .build()
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .contains("conflicts with a slice_io handler"));
+
+    let err = ExecutorEnv::builder()
+        .syscall(SYS_MULTI_TEST_WORDS, Nop)
+        .syscall(SYS_MULTI_TEST_WORDS, Nop)
+        .build()
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("is registered more than once"));
+}
+
+#[test]
+fn host_custom_syscall_region_overflow() {
+    struct Zeros;
+
+    impl crate::SyscallContext for Zeros {
+        fn get_pc(&self) -> u32 {
+            0
+        }
+
+        fn get_cycle(&self) -> u64 {
+            0
+        }
+
+        fn load_register(&mut self, _idx: usize) -> u32 {
+            0
+        }
+
+        fn load_u8(&mut self, _addr: u32) -> Result<u8> {
+            Ok(0)
+        }
+
+        fn load_u32(&mut self, _addr: u32) -> Result<u32> {
+            Ok(0)
+        }
+    }
+
+    let ctx: &mut dyn crate::SyscallContext = &mut Zeros;
+    assert_eq!(ctx.load_region(u32::MAX - 3, 4).unwrap(), vec![0; 4]);
+    let err = ctx.load_region(u32::MAX - 3, 5).unwrap_err();
+    assert!(err.to_string().contains("overflows the address space"));
+}
+
 // Make sure panics in the callback get propagated correctly.
 #[test]
 #[should_panic(expected = "I am panicking from here!")]
@@ -271,6 +395,108 @@ fn bigint_accel() {
     }
 }
 
+#[test]
+fn groth16_verify() {
+    use risc0_groth16::{ProofJson, PublicInputsJson, Seal, VerifyingKeyJson};
+
+    macro_rules! test_data {
+        ($s:expr) => {
+            include_str!(concat!("../../../../../groth16/tests/data/", $s))
+        };
+    }
+
+    let verifying_key =
+        serde_json::from_str::<VerifyingKeyJson>(test_data!("verification_key.json"))
+            .unwrap()
+            .verifying_key()
+            .unwrap();
+    let seal: Seal = serde_json::from_str::<ProofJson>(test_data!("proof.json"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let spec = |values: Vec<String>| MultiTestSpec::Groth16Verify {
+        seal: seal.to_vec(),
+        public_inputs_words: to_vec(&PublicInputsJson { values }.to_scalar().unwrap()).unwrap(),
+        verifying_key_words: to_vec(&verifying_key).unwrap(),
+    };
+
+    run_test(spec(
+        serde_json::from_str(test_data!("public.json")).unwrap(),
+    ));
+
+    // The proof must be rejected for any other public inputs.
+    let env = ExecutorEnv::builder()
+        .write(&spec(vec!["34".into()]))
+        .unwrap()
+        .build()
+        .unwrap();
+    assert!(ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .is_err());
+}
+
+#[test]
+fn groth16_receipt_verify() {
+    use risc0_groth16::{ProofJson, Seal, VerifyingKeyJson};
+    use risc0_zkp::core::digest::digest;
+
+    use crate::{
+        Groth16Receipt, Groth16ReceiptVerifierParameters, MaybePruned, Unknown, VerifierContext,
+    };
+
+    // The fixtures and digests are generated by `testdata/groth16/generate.py`.
+    macro_rules! test_data {
+        ($s:expr) => {
+            include_str!(concat!("../testdata/groth16/", $s))
+        };
+    }
+
+    let params = Groth16ReceiptVerifierParameters {
+        control_root: digest!("2f31c50881e9453bba22f98e4bc8b06804dcbe11799308e758a4f157126b0e28"),
+        bn254_control_id: digest!(
+            "299c06b7f24b30fc003ff914f248acb35400f5575871cef830861ca606ad2000"
+        ),
+        verifying_key: serde_json::from_str::<VerifyingKeyJson>(test_data!(
+            "verification_key.json"
+        ))
+        .unwrap()
+        .verifying_key()
+        .unwrap(),
+    };
+    let seal: Seal = serde_json::from_str::<ProofJson>(test_data!("proof.json"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let receipt = |claim: Digest| {
+        Groth16Receipt::<Unknown>::new(seal.to_vec(), MaybePruned::Pruned(claim), params.digest())
+    };
+    let run = |receipt: &Groth16Receipt<Unknown>| {
+        let env = ExecutorEnv::builder()
+            .write(&MultiTestSpec::Groth16ReceiptVerify {
+                receipt_words: to_vec(receipt).unwrap(),
+                verifier_parameters_words: to_vec(&params).unwrap(),
+            })
+            .unwrap()
+            .build()
+            .unwrap();
+        ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap().run()
+    };
+
+    let ctx = VerifierContext::empty().with_groth16_verifier_parameters(params.clone());
+    let valid = receipt(digest!(
+        "dd1b3c312cf7d816130354452e9629ce39355b0c534129dd26a08cd9a4502ede"
+    ));
+    valid.verify_integrity_with_context(&ctx).unwrap();
+    let session = run(&valid).unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+
+    // The receipt must be rejected for any other claim.
+    let invalid = receipt(Digest::ZERO);
+    assert!(invalid.verify_integrity_with_context(&ctx).is_err());
+    assert!(run(&invalid).is_err());
+}
+
 #[test]
 fn env_stdio() {
     const MSG: &str = "Hello world!  This is a test of standard input and output.";
@@ -913,6 +1139,117 @@ fn slice_io() {
     run(b"0000");
 }
 
+#[test]
+fn record_replay_io() {
+    let dir = tempfile::tempdir().unwrap();
+    let run = |spec: MultiTestSpec, name: &str| {
+        let path = dir.path().join(name);
+        let env = ExecutorEnv::builder()
+            .write(&spec)
+            .unwrap()
+            .io_callback(SYS_MULTI_TEST, |buf| {
+                Ok(Bytes::from([b"x".as_slice(), &buf].concat()))
+            })
+            .record_io(&path)
+            .build()
+            .unwrap();
+        let recorded = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+
+        // Neither the input nor the syscall handler is present when replaying.
+        let env = ExecutorEnv::builder().replay_io(&path).build().unwrap();
+        let replayed = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(replayed.exit_code, ExitCode::Halted(0));
+        assert_eq!(replayed.journal, recorded.journal);
+        assert_eq!(replayed.post_state.digest(), recorded.post_state.digest());
+    };
+
+    run(MultiTestSpec::DoRandom, "random.log");
+    run(MultiTestSpec::Syscall { count: 5 }, "syscall.log");
+}
+
+#[test]
+fn replay_io_diverged() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("io.log");
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoRandom)
+        .unwrap()
+        .record_io(&path)
+        .build()
+        .unwrap();
+    ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    let env = ExecutorEnv::builder().replay_io(&path).build().unwrap();
+    let err = ExecutorImpl::from_elf(env, HELLO_COMMIT_ELF)
+        .unwrap()
+        .run()
+        .err()
+        .unwrap();
+    let err = format!("{err:#}");
+    assert!(err.contains("replay diverged"), "{err}");
+}
+
+#[test]
+fn replay_io_request_diverged() {
+    use std::io::Write as _;
+
+    use super::io_log::IoLogEntry;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("io.log");
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::Syscall { count: 2 })
+        .unwrap()
+        .io_callback(SYS_MULTI_TEST, |buf| {
+            Ok(Bytes::from([b"x".as_slice(), &buf].concat()))
+        })
+        .record_io(&path)
+        .build()
+        .unwrap();
+    ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    // Change the recorded request of the second call, which sent the reply to the first one.
+    let contents = std::fs::read(&path).unwrap();
+    let mut reader = contents.as_slice();
+    let mut entries = vec![];
+    while !reader.is_empty() {
+        entries.push(bincode::deserialize_from::<_, IoLogEntry>(&mut reader).unwrap());
+    }
+    let entry = entries
+        .iter_mut()
+        .filter(|entry| entry.syscall == SYS_MULTI_TEST.as_str())
+        .nth(1)
+        .unwrap();
+    assert_eq!(entry.request, b"x");
+    entry.request = b"y".to_vec();
+    let mut file = std::fs::File::create(&path).unwrap();
+    for entry in entries.iter() {
+        bincode::serialize_into(&mut file, entry).unwrap();
+    }
+    file.flush().unwrap();
+
+    let env = ExecutorEnv::builder().replay_io(&path).build().unwrap();
+    let err = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .err()
+        .unwrap();
+    let err = format!("{err:#}");
+    assert!(err.contains("differs from the one recorded"), "{err}");
+}
+
 // Check that a compliant host will return an error on panic.
 #[test]
 fn panic() {
@@ -1025,6 +1362,105 @@ fn profiler() {
     assert!(check(&fr, addr), "{fr:#?} {addr}");
 }
 
+#[test]
+fn cycle_report() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::Profiler)
+        .unwrap()
+        .enable_cycle_report()
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    let report = session.cycle_report.unwrap();
+
+    let func = |name: &str| {
+        report
+            .functions
+            .iter()
+            .find(|func| func.name.ends_with(name))
+            .unwrap_or_else(|| panic!("{name} missing from {report:#?}"))
+    };
+    let func1 = func("profile_test_func1");
+    let func2 = func("profile_test_func2");
+    assert!(func2.inclusive_cycles > 0);
+    assert!(func1.inclusive_cycles >= func2.inclusive_cycles);
+    assert!(report
+        .functions
+        .windows(2)
+        .all(|pair| pair[0].self_cycles >= pair[1].self_cycles));
+
+    // The guest reads its input with a software syscall.
+    assert!(report
+        .syscalls
+        .iter()
+        .any(|s| s.name == "SYS_READ" && s.calls > 0));
+
+    assert_eq!(report.segments.len(), session.segments.len());
+    assert_eq!(report.user_cycles(), session.user_cycles);
+    assert_eq!(report.paging_cycles(), session.paging_cycles);
+    assert!(report.to_text(5).contains("profile_test_func2"));
+}
+
+#[test]
+fn gdb_server() {
+    fn request(stream: &mut TcpStream, packet: &str) -> String {
+        let checksum = packet.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+        write!(stream, "${packet}#{checksum:02x}").unwrap();
+
+        let mut reply = Vec::new();
+        let mut byte = [0u8];
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            match byte[0] {
+                b'+' | b'$' => continue,
+                b'#' => break,
+                byte => reply.push(byte),
+            }
+        }
+        let mut checksum = [0u8; 2];
+        stream.read_exact(&mut checksum).unwrap();
+        stream.write_all(b"+").unwrap();
+        String::from_utf8(reply).unwrap()
+    }
+
+    // The listener stays bound until the executor accepts the connection, so the client can
+    // connect right away.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let entry = Program::load_elf(MULTI_TEST_ELF, u32::MAX).unwrap().entry;
+
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        assert_eq!(request(&mut stream, "?"), "S05");
+        assert_eq!(
+            request(&mut stream, "p20"),
+            hex::encode(entry.to_le_bytes())
+        );
+        assert_eq!(request(&mut stream, "s"), "S05");
+        assert_ne!(
+            request(&mut stream, "p20"),
+            hex::encode(entry.to_le_bytes())
+        );
+        assert_eq!(request(&mut stream, "c"), "W00");
+    });
+
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .enable_gdb_server_on(listener)
+        .build()
+        .unwrap();
+    let session = ExecutorImpl::from_elf(env, MULTI_TEST_ELF)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    client.join().unwrap();
+}
+
 #[test]
 fn oom() {
     let env = ExecutorEnv::builder()
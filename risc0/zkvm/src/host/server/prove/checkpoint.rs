@@ -0,0 +1,167 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-disk checkpointing of intermediate receipts, allowing interrupted proofs to be resumed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A directory in which intermediate receipts are stored under a key that identifies the work
+/// that produced them.
+pub(crate) struct Checkpoint {
+    dir: PathBuf,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.bincode"))
+    }
+
+    /// Load the value stored under the given key, if it exists and can be decoded.
+    fn load<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let path = self.path(key);
+        let contents = fs::read(&path).ok()?;
+        match bincode::deserialize(&contents) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                tracing::warn!("ignoring malformed checkpoint {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Store a value under the given key.
+    ///
+    /// The value is first written to a temporary file, so an interrupted write never leaves a
+    /// truncated checkpoint behind.
+    fn save<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let path = self.path(key);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bincode::serialize(value)?)
+            .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Return the value stored under `key`, if there is one and it passes `verify`.
+pub(crate) fn load_verified<T, V>(
+    checkpoint: Option<&Checkpoint>,
+    key: &str,
+    verify: V,
+) -> Option<T>
+where
+    T: DeserializeOwned,
+    V: FnOnce(&T) -> Result<()>,
+{
+    let value = checkpoint?.load(key)?;
+    match verify(&value) {
+        Ok(()) => {
+            tracing::debug!("loaded checkpoint {key}");
+            Some(value)
+        }
+        Err(err) => {
+            tracing::warn!("checkpoint {key} failed verification: {err}");
+            None
+        }
+    }
+}
+
+/// Store the value under `key`, if checkpointing is enabled.
+pub(crate) fn store<T: Serialize>(
+    checkpoint: Option<&Checkpoint>,
+    key: &str,
+    value: &T,
+) -> Result<()> {
+    match checkpoint {
+        Some(checkpoint) => checkpoint.save(key, value),
+        None => Ok(()),
+    }
+}
+
+/// Return the value stored under `key` if it passes `verify`, otherwise run `prove` and store
+/// its result.
+///
+/// When no [Checkpoint] is given, this simply runs `prove`.
+pub(crate) fn load_or_prove<T, V, P>(
+    checkpoint: Option<&Checkpoint>,
+    key: &str,
+    verify: V,
+    prove: P,
+) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    V: FnOnce(&T) -> Result<()>,
+    P: FnOnce() -> Result<T>,
+{
+    if let Some(value) = load_verified(checkpoint, key, verify) {
+        return Ok(value);
+    }
+
+    let value = prove()?;
+    store(checkpoint, key, &value)?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use anyhow::bail;
+
+    use super::{load_or_prove, Checkpoint};
+
+    #[test]
+    fn load_or_prove_skips_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint = Checkpoint::new(dir.path());
+        let calls = Cell::new(0);
+        let prove = || {
+            calls.set(calls.get() + 1);
+            Ok(42u32)
+        };
+
+        let value = load_or_prove(Some(&checkpoint), "a", |_| Ok(()), prove).unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(calls.get(), 1);
+
+        // A verified checkpoint is reused.
+        let value = load_or_prove(Some(&checkpoint), "a", |_| Ok(()), prove).unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(calls.get(), 1);
+
+        // A checkpoint that fails verification is proven again.
+        let value = load_or_prove(Some(&checkpoint), "a", |_| bail!("rejected"), prove).unwrap();
+        assert_eq!(value, 42);
+        assert_eq!(calls.get(), 2);
+
+        // Without a checkpoint, everything is proven.
+        load_or_prove(None, "a", |_: &u32| Ok(()), prove).unwrap();
+        assert_eq!(calls.get(), 3);
+    }
+}
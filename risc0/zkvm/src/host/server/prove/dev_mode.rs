@@ -86,20 +86,26 @@ impl ProverServer for DevModeProver {
         unimplemented!("This is unsupported for dev mode.")
     }
 
-    fn lift(&self, _receipt: &SegmentReceipt) -> Result<SuccinctReceipt<ReceiptClaim>> {
+    fn lift_with_ctx(
+        &self,
+        _ctx: &VerifierContext,
+        _receipt: &SegmentReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
         unimplemented!("This is unsupported for dev mode.")
     }
 
-    fn join(
+    fn join_with_ctx(
         &self,
+        _ctx: &VerifierContext,
         _a: &SuccinctReceipt<ReceiptClaim>,
         _b: &SuccinctReceipt<ReceiptClaim>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>> {
         unimplemented!("This is unsupported for dev mode.")
     }
 
-    fn resolve(
+    fn resolve_with_ctx(
         &self,
+        _ctx: &VerifierContext,
         _conditional: &SuccinctReceipt<ReceiptClaim>,
         _assumption: &SuccinctReceipt<Unknown>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>> {
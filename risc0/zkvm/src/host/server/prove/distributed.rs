@@ -0,0 +1,461 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Distribute proving of a [Session] across worker processes.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, ToSocketAddrs},
+    path::Path,
+    process::{Child, Command},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bytes::Bytes;
+
+use super::{
+    join_tree::lift_join_tree, prover_impl::finish_session, resolve_assumptions, ProverServer,
+};
+use crate::{
+    host::{
+        api::{client::Client, ListenerConnector},
+        client::{env::ProveZkrRequest, prove::progress::ProgressEvent},
+        prove_info::ProveInfo,
+    },
+    receipt::{CompositeReceipt, SegmentReceipt, SuccinctReceipt},
+    receipt_claim::Unknown,
+    Asset, AssetRequest, ProverOpts, ReceiptClaim, Segment, Session, VerifierContext,
+};
+
+/// A [ProverServer] that hands the work of proving a [Session] out to worker processes.
+///
+/// The coordinator executes nothing itself. It listens for workers, which are `r0vm` processes
+/// started with `r0vm --port <port> --host <host> --worker` that connect to it, and hands each
+/// connection a single request, e.g. to prove one segment or to run one recursion program. Once
+/// a worker is done with a request, it connects again to receive the next one. Segments are
+/// proven and then lifted and joined as a balanced tree, so workers on the same or on other hosts
+/// all contribute to producing a single succinct receipt.
+///
+/// A request that fails, e.g. because its worker exits, is handed to the next worker that
+/// connects, up to the maximum number of attempts. An attempt also fails when no worker connects
+/// within the accept timeout, or, unless remote workers are expected, when all of the local
+/// workers have exited.
+pub struct Coordinator {
+    listener: TcpListener,
+    opts: ProverOpts,
+    jobs: usize,
+    max_attempts: usize,
+    job_timeout: Option<Duration>,
+    accept_timeout: Option<Duration>,
+    local_workers: Arc<Mutex<Vec<Child>>>,
+    remote_workers: bool,
+}
+
+/// How long a request waits for a worker to connect by default.
+const DEFAULT_ACCEPT_TIMEOUT: Duration = Duration::from_secs(600);
+
+impl Coordinator {
+    /// Listen for workers on the given address, and prove with the given [ProverOpts].
+    ///
+    /// Receipts are compressed to the [ReceiptKind][crate::ReceiptKind] specified by `opts`. For
+    /// Groth16 receipts, the final STARK-to-SNARK step runs on the coordinator.
+    pub fn bind<A: ToSocketAddrs>(addr: A, opts: ProverOpts) -> Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            opts,
+            jobs: 1,
+            max_attempts: 3,
+            job_timeout: None,
+            accept_timeout: Some(DEFAULT_ACCEPT_TIMEOUT),
+            local_workers: Arc::new(Mutex::new(Vec::new())),
+            remote_workers: false,
+        })
+    }
+
+    /// Returns the address the coordinator listens on for workers.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Return a [Coordinator] that hands out up to `jobs` requests at once.
+    ///
+    /// This should match the number of workers; any more requests wait for a worker to connect.
+    pub fn with_jobs(self, jobs: usize) -> Self {
+        Self {
+            jobs: jobs.max(1),
+            ..self
+        }
+    }
+
+    /// Return a [Coordinator] that tries each request up to `max_attempts` times before failing.
+    pub fn with_max_attempts(self, max_attempts: usize) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// Return a [Coordinator] that fails a request when its worker does not reply within the
+    /// given time, so it can be retried on another worker.
+    pub fn with_job_timeout(self, job_timeout: Duration) -> Self {
+        Self {
+            job_timeout: Some(job_timeout),
+            ..self
+        }
+    }
+
+    /// Return a [Coordinator] that fails an attempt at a request when no worker connects within
+    /// the given time, or that waits indefinitely if it is `None`.
+    ///
+    /// Defaults to 10 minutes.
+    pub fn with_accept_timeout(self, accept_timeout: Option<Duration>) -> Self {
+        Self {
+            accept_timeout,
+            ..self
+        }
+    }
+
+    /// Return a [Coordinator] that expects workers on other hosts to connect as well as the ones
+    /// it starts locally.
+    ///
+    /// By default, a request fails as soon as all of the local workers have exited. With remote
+    /// workers expected, it keeps waiting for a worker to connect until the accept timeout.
+    pub fn with_remote_workers(self, remote_workers: bool) -> Self {
+        Self {
+            remote_workers,
+            ..self
+        }
+    }
+
+    /// Start `count` worker processes on this host, running the `r0vm` at `server_path`.
+    ///
+    /// The workers connect to the address the coordinator is bound to, or to the loopback
+    /// address if it is bound to all interfaces. They are killed when the [Coordinator] is
+    /// dropped.
+    pub fn spawn_local_workers<P: AsRef<Path>>(
+        &mut self,
+        server_path: P,
+        count: usize,
+    ) -> Result<()> {
+        let addr = self.local_addr()?;
+        let host = match addr.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => Ipv4Addr::LOCALHOST.to_string(),
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) if ip.is_unspecified() => format!("[{}]", Ipv6Addr::LOCALHOST),
+            IpAddr::V6(ip) => format!("[{ip}]"),
+        };
+        let mut local_workers = self.local_workers.lock().unwrap();
+        for _ in 0..count {
+            let child = Command::new(server_path.as_ref())
+                .arg("--port")
+                .arg(addr.port().to_string())
+                .arg("--host")
+                .arg(&host)
+                .arg("--worker")
+                .spawn()
+                .with_context(|| {
+                    format!(
+                        "failed to start worker: {}",
+                        server_path.as_ref().to_string_lossy()
+                    )
+                })?;
+            local_workers.push(child);
+        }
+        Ok(())
+    }
+
+    /// Hand a request to the next worker that connects, retrying on failure.
+    fn dispatch<T>(&self, name: &str, request: impl Fn(&Client) -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            self.opts.check_cancelled()?;
+            let mut connector =
+                ListenerConnector::new(self.listener.try_clone()?, self.job_timeout)
+                    .with_accept_timeout(self.accept_timeout);
+            if !self.remote_workers && !self.local_workers.lock().unwrap().is_empty() {
+                let local_workers = self.local_workers.clone();
+                connector = connector.with_servers_alive(Arc::new(move || {
+                    local_workers
+                        .lock()
+                        .unwrap()
+                        .iter_mut()
+                        .any(|child| matches!(child.try_wait(), Ok(None)))
+                }));
+            }
+            let client = Client::with_connector(Box::new(connector));
+            match request(&client) {
+                Ok(result) => return Ok(result),
+                Err(err) if attempt < self.max_attempts => {
+                    tracing::warn!("{name} failed on attempt {attempt}, retrying: {err:#}");
+                    attempt += 1;
+                }
+                Err(err) => {
+                    return Err(err.context(format!("{name} failed after {attempt} attempts")))
+                }
+            }
+        }
+    }
+
+    /// Prove the serialized segments received from `segments` on up to `jobs` workers at once.
+    fn prove_segments(
+        &self,
+        segments: mpsc::Receiver<(u32, Bytes)>,
+    ) -> Result<Vec<SegmentReceipt>> {
+        let segments = Mutex::new(segments);
+        let receipts = Mutex::new(Vec::new());
+        let failed = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.jobs)
+                .map(|_| {
+                    scope.spawn(|| -> Result<()> {
+                        while !failed.load(Ordering::Relaxed) {
+                            let Ok((index, segment)) = segments.lock().unwrap().recv() else {
+                                break;
+                            };
+                            let result = self
+                                .dispatch("prove_segment", |client| {
+                                    client.prove_segment(
+                                        &self.opts,
+                                        Asset::Inline(segment.clone()),
+                                        AssetRequest::Inline,
+                                    )
+                                })
+                                .and_then(|receipt| {
+                                    ensure!(
+                                        receipt.index == index,
+                                        "worker proved segment {} instead of {index}",
+                                        receipt.index
+                                    );
+                                    Ok(receipt)
+                                });
+                            match result {
+                                Ok(receipt) => {
+                                    self.opts.report(ProgressEvent::SegmentProved { index });
+                                    receipts.lock().unwrap().push(receipt);
+                                }
+                                Err(err) => {
+                                    // Stop handing out segments to any of the jobs.
+                                    failed.store(true, Ordering::Relaxed);
+                                    return Err(err);
+                                }
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .try_for_each(|worker| worker.join().map_err(|_| anyhow!("worker panicked"))?)
+        })?;
+
+        let mut receipts = receipts.into_inner().unwrap();
+        receipts.sort_by_key(|receipt| receipt.index);
+        Ok(receipts)
+    }
+}
+
+impl Drop for Coordinator {
+    fn drop(&mut self) {
+        for child in self.local_workers.lock().unwrap().iter_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl ProverServer for Coordinator {
+    fn prove_session(&self, ctx: &VerifierContext, session: &Session) -> Result<ProveInfo> {
+        tracing::debug!(
+            "prove_session: distributing {} segments",
+            session.segments.len()
+        );
+
+        // Segments are resolved on this thread and handed to the jobs as they free up, so only
+        // a few of them are held in memory at any time.
+        let (segment_tx, segment_rx) = mpsc::sync_channel(self.jobs);
+        let segments = thread::scope(|scope| {
+            let jobs = scope.spawn(|| self.prove_segments(segment_rx));
+            for segment_ref in session.segments.iter() {
+                let segment = segment_ref.resolve()?;
+                for hook in &session.hooks {
+                    hook.on_pre_prove_segment(&segment);
+                }
+                let bytes = bincode::serialize(&segment)?;
+                if segment_tx.send((segment.index, bytes.into())).is_err() {
+                    // The jobs stopped early, and will report why below.
+                    break;
+                }
+            }
+            drop(segment_tx);
+            jobs.join().map_err(|_| anyhow!("segment jobs panicked"))?
+        })?;
+        ensure!(
+            segments.len() == session.segments.len(),
+            "expected {} segment receipts, got {}",
+            session.segments.len(),
+            segments.len()
+        );
+
+        for receipt in segments.iter() {
+            receipt.verify_integrity_with_context(ctx)?;
+        }
+        if !session.hooks.is_empty() {
+            // Segments are not kept in memory while they are proven, so resolve them again.
+            for segment_ref in session.segments.iter() {
+                let segment = segment_ref.resolve()?;
+                for hook in &session.hooks {
+                    hook.on_post_prove_segment(&segment);
+                }
+            }
+        }
+
+        finish_session(
+            self,
+            &self.opts,
+            None,
+            ctx,
+            session,
+            segments,
+            |request| {
+                self.dispatch("prove_zkr", |client| {
+                    client.prove_zkr(
+                        ProveZkrRequest {
+                            claim_digest: request.claim_digest,
+                            control_id: request.control_id,
+                            input: request.input.clone(),
+                        },
+                        AssetRequest::Inline,
+                    )
+                })
+            },
+            |receipt| self.composite_to_succinct(receipt),
+        )
+    }
+
+    fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
+        let bytes: Bytes = bincode::serialize(segment)?.into();
+        let receipt = self.dispatch("prove_segment", |client| {
+            client.prove_segment(
+                &self.opts,
+                Asset::Inline(bytes.clone()),
+                AssetRequest::Inline,
+            )
+        })?;
+        receipt.verify_integrity_with_context(ctx)?;
+        self.opts.report(ProgressEvent::SegmentProved {
+            index: segment.index,
+        });
+        Ok(receipt)
+    }
+
+    fn lift_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        receipt: &SegmentReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        let bytes: Bytes = bincode::serialize(receipt)?.into();
+        let lifted = self.dispatch("lift", |client| {
+            client.lift(
+                &self.opts,
+                Asset::Inline(bytes.clone()),
+                AssetRequest::Inline,
+            )
+        })?;
+        lifted.verify_integrity_with_context(ctx)?;
+        self.opts.report(ProgressEvent::LiftDone {
+            index: receipt.index,
+        });
+        Ok(lifted)
+    }
+
+    fn join_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        a: &SuccinctReceipt<ReceiptClaim>,
+        b: &SuccinctReceipt<ReceiptClaim>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        let a: Bytes = bincode::serialize(a)?.into();
+        let b: Bytes = bincode::serialize(b)?.into();
+        let joined = self.dispatch("join", |client| {
+            client.join(
+                &self.opts,
+                Asset::Inline(a.clone()),
+                Asset::Inline(b.clone()),
+                AssetRequest::Inline,
+            )
+        })?;
+        joined.verify_integrity_with_context(ctx)?;
+        self.opts.report(ProgressEvent::JoinDone);
+        Ok(joined)
+    }
+
+    fn resolve_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        conditional: &SuccinctReceipt<ReceiptClaim>,
+        assumption: &SuccinctReceipt<Unknown>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        let conditional: Bytes = bincode::serialize(conditional)?.into();
+        let assumption: Bytes = bincode::serialize(assumption)?.into();
+        let resolved = self.dispatch("resolve", |client| {
+            client.resolve(
+                &self.opts,
+                Asset::Inline(conditional.clone()),
+                Asset::Inline(assumption.clone()),
+                AssetRequest::Inline,
+            )
+        })?;
+        resolved.verify_integrity_with_context(ctx)?;
+        self.opts.report(ProgressEvent::ResolveDone);
+        Ok(resolved)
+    }
+
+    fn identity_p254(
+        &self,
+        a: &SuccinctReceipt<ReceiptClaim>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        let bytes: Bytes = bincode::serialize(a)?.into();
+        self.dispatch("identity_p254", |client| {
+            client.identity_p254(
+                &self.opts,
+                Asset::Inline(bytes.clone()),
+                AssetRequest::Inline,
+            )
+        })
+    }
+
+    fn composite_to_succinct(
+        &self,
+        receipt: &CompositeReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        let continuation_receipt = lift_join_tree(
+            &receipt.segments,
+            self.jobs,
+            |receipt| self.lift(receipt),
+            |a, b| self.join(a, b),
+        )?;
+        resolve_assumptions(
+            continuation_receipt,
+            &receipt.assumption_receipts,
+            |conditional, assumption| self.resolve(conditional, assumption),
+            |receipt| self.composite_to_succinct(receipt),
+        )
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Balanced binary-tree scheduling of the lift and join recursion programs.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use anyhow::{anyhow, ensure, Result};
+
+use crate::{receipt::SegmentReceipt, ReceiptClaim, SuccinctReceipt};
+
+/// Lift all segment receipts and join them into a single [SuccinctReceipt].
+///
+/// Segments are lifted concurrently, after which adjacent pairs of receipts are joined level by
+/// level, forming a balanced binary tree. Up to `workers` lift or join programs are proven at the
+/// same time. The claim of the resulting receipt is identical to the one produced by joining the
+/// segments one at a time from left to right.
+pub(crate) fn lift_join_tree<L, J>(
+    segments: &[SegmentReceipt],
+    workers: usize,
+    lift: L,
+    join: J,
+) -> Result<SuccinctReceipt<ReceiptClaim>>
+where
+    L: Fn(&SegmentReceipt) -> Result<SuccinctReceipt<ReceiptClaim>> + Sync,
+    J: Fn(
+            &SuccinctReceipt<ReceiptClaim>,
+            &SuccinctReceipt<ReceiptClaim>,
+        ) -> Result<SuccinctReceipt<ReceiptClaim>>
+        + Sync,
+{
+    let mut level = par_map(segments, workers, &lift)?;
+    while level.len() > 1 {
+        tracing::debug!("joining {} receipts", level.len());
+
+        // An odd receipt at the end of a level is carried over to the next level as is.
+        let carry = if level.len() % 2 == 1 {
+            level.pop()
+        } else {
+            None
+        };
+        let pairs: Vec<_> = level.chunks_exact(2).collect();
+        level = par_map(&pairs, workers, |pair| join(&pair[0], &pair[1]))?;
+        level.extend(carry);
+    }
+
+    level.pop().ok_or(anyhow!(
+        "malformed composite receipt has no continuation segment receipts"
+    ))
+}
+
+/// Apply `f` to each item using up to `workers` threads, preserving the order of the items.
+///
+/// When `workers` is at most one, all items are processed on the calling thread.
+fn par_map<T, U, F>(items: &[T], workers: usize, f: F) -> Result<Vec<U>>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> Result<U> + Sync,
+{
+    let workers = workers.min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<U>>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= items.len() {
+                    break;
+                }
+                let result = f(&items[idx]);
+                let failed = result.is_err();
+                results.lock().unwrap()[idx] = Some(result);
+                if failed {
+                    // Stop handing out new work to any of the workers.
+                    next.store(items.len(), Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    let mut out = Vec::with_capacity(items.len());
+    let mut skipped = false;
+    for result in results.into_inner().unwrap() {
+        match result {
+            Some(result) => out.push(result?),
+            None => skipped = true,
+        }
+    }
+    ensure!(
+        !skipped,
+        "recursion workers stopped before all work was done"
+    );
+    Ok(out)
+}
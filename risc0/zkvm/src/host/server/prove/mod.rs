@@ -14,7 +14,10 @@
 
 //! Run the zkVM guest and prove its results.
 
+mod checkpoint;
 mod dev_mode;
+pub(crate) mod distributed;
+mod join_tree;
 mod prover_impl;
 #[cfg(test)]
 mod tests;
@@ -60,6 +63,30 @@ pub trait ProverServer {
         self.prove_session(ctx, &session)
     }
 
+    /// Prove the specified ELF binary, proving each [Segment] while execution is still running.
+    ///
+    /// Segments are handed to the prover as soon as the executor produces them, so execution and
+    /// proving overlap. At most `queue_depth` segments wait to be proven at any time; once the
+    /// queue is full, execution blocks until the prover catches up. This bounds peak memory by the
+    /// queue depth rather than by the length of the session.
+    ///
+    /// The [SessionEvents][crate::SessionEvents] hooks registered with
+    /// [ExecutorEnvBuilder::session_hook][crate::ExecutorEnvBuilder::session_hook] are fired for
+    /// each segment, like the hooks of a [Session] passed to [ProverServer::prove_session].
+    ///
+    /// The default implementation does not pipeline: it runs the whole session and then calls
+    /// [ProverServer::prove_session], ignoring `queue_depth`, so peak memory is not bounded by
+    /// the queue.
+    fn prove_streaming(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+        _queue_depth: usize,
+    ) -> Result<ProveInfo> {
+        self.prove_with_ctx(env, ctx, elf)
+    }
+
     /// Prove the specified [Session].
     fn prove_session(&self, ctx: &VerifierContext, session: &Session) -> Result<ProveInfo>;
 
@@ -67,13 +94,34 @@ pub trait ProverServer {
     fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt>;
 
     /// Lift a [SegmentReceipt] into a [SuccinctReceipt]
-    fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt<ReceiptClaim>>;
+    fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        self.lift_with_ctx(&VerifierContext::default(), receipt)
+    }
+
+    /// Lift a [SegmentReceipt] into a [SuccinctReceipt], verifying receipts against the given
+    /// [VerifierContext].
+    fn lift_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        receipt: &SegmentReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>>;
 
     /// Join two [SuccinctReceipt] into a [SuccinctReceipt]
     fn join(
         &self,
         a: &SuccinctReceipt<ReceiptClaim>,
         b: &SuccinctReceipt<ReceiptClaim>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        self.join_with_ctx(&VerifierContext::default(), a, b)
+    }
+
+    /// Join two [SuccinctReceipt] into a [SuccinctReceipt], verifying receipts against the given
+    /// [VerifierContext].
+    fn join_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        a: &SuccinctReceipt<ReceiptClaim>,
+        b: &SuccinctReceipt<ReceiptClaim>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>>;
 
     /// Resolve an assumption from a conditional [SuccinctReceipt] by providing a [SuccinctReceipt]
@@ -82,6 +130,17 @@ pub trait ProverServer {
         &self,
         conditional: &SuccinctReceipt<ReceiptClaim>,
         assumption: &SuccinctReceipt<Unknown>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        self.resolve_with_ctx(&VerifierContext::default(), conditional, assumption)
+    }
+
+    /// Resolve an assumption from a conditional [SuccinctReceipt], verifying receipts against
+    /// the given [VerifierContext].
+    fn resolve_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        conditional: &SuccinctReceipt<ReceiptClaim>,
+        assumption: &SuccinctReceipt<Unknown>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>>;
 
     /// Convert a [SuccinctReceipt] with a Poseidon hash function that uses a 254-bit field
@@ -98,6 +157,10 @@ pub trait ProverServer {
     /// [CompositeReceipt] into a single [SuccinctReceipt] that proves the same top-level claim. It
     /// accomplishes this by iterative application of the recursion programs including lift, join,
     /// and resolve.
+    ///
+    /// Assumptions must be proven by succinct or composite receipts. There is no recursion program
+    /// that verifies a Groth16 receipt, so an assumption only available as a Groth16 receipt
+    /// cannot be resolved.
     fn composite_to_succinct(
         &self,
         receipt: &CompositeReceipt,
@@ -122,20 +185,11 @@ pub trait ProverServer {
             ))?;
 
         // Compress assumptions and resolve them to get the final succinct receipt.
-        receipt.assumption_receipts.iter().try_fold(
+        resolve_assumptions(
             continuation_receipt,
-            |conditional: SuccinctReceipt<ReceiptClaim>, assumption: &InnerAssumptionReceipt| match assumption {
-                InnerAssumptionReceipt::Succinct(assumption) => self.resolve(&conditional, assumption),
-                InnerAssumptionReceipt::Composite(assumption) => {
-                    self.resolve(&conditional, &self.composite_to_succinct(assumption)?.into_unknown())
-                }
-                InnerAssumptionReceipt::Fake(_) => bail!(
-                    "compressing composite receipts with fake receipt assumptions is not supported"
-                ),
-                InnerAssumptionReceipt::Groth16(_) => bail!(
-                    "compressing composite receipts with Groth16 receipt assumptions is not supported"
-                )
-            },
+            &receipt.assumption_receipts,
+            |conditional, assumption| self.resolve(conditional, assumption),
+            |receipt| self.composite_to_succinct(receipt),
         )
     }
 
@@ -144,15 +198,7 @@ pub trait ProverServer {
         &self,
         receipt: &SuccinctReceipt<ReceiptClaim>,
     ) -> Result<Groth16Receipt<ReceiptClaim>> {
-        let ident_receipt = self.identity_p254(receipt).unwrap();
-        let seal_bytes = ident_receipt.get_seal_bytes();
-
-        let seal = stark_to_snark(&seal_bytes)?.to_vec();
-        Ok(Groth16Receipt {
-            seal,
-            claim: receipt.claim.clone(),
-            verifier_parameters: Groth16ReceiptVerifierParameters::default().digest(),
-        })
+        succinct_to_groth16(self, receipt)
     }
 
     /// Compress a receipt into one with a smaller representation.
@@ -206,6 +252,59 @@ pub trait ProverServer {
     }
 }
 
+/// Resolve each of the given assumptions against the conditional receipt, compressing composite
+/// assumption receipts as needed.
+fn resolve_assumptions<R, C>(
+    conditional: SuccinctReceipt<ReceiptClaim>,
+    assumption_receipts: &[InnerAssumptionReceipt],
+    resolve: R,
+    composite_to_succinct: C,
+) -> Result<SuccinctReceipt<ReceiptClaim>>
+where
+    R: Fn(
+        &SuccinctReceipt<ReceiptClaim>,
+        &SuccinctReceipt<Unknown>,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>>,
+    C: Fn(&CompositeReceipt) -> Result<SuccinctReceipt<ReceiptClaim>>,
+{
+    assumption_receipts.iter().try_fold(
+        conditional,
+        |conditional: SuccinctReceipt<ReceiptClaim>, assumption: &InnerAssumptionReceipt| {
+            match assumption {
+                InnerAssumptionReceipt::Succinct(assumption) => resolve(&conditional, assumption),
+                InnerAssumptionReceipt::Composite(assumption) => resolve(
+                    &conditional,
+                    &composite_to_succinct(assumption)?.into_unknown(),
+                ),
+                InnerAssumptionReceipt::Fake(_) => bail!(
+                    "compressing composite receipts with fake receipt assumptions is not supported"
+                ),
+                InnerAssumptionReceipt::Groth16(_) => bail!(
+                    "compressing composite receipts with Groth16 receipt assumptions is not supported: \
+                     provide the assumption as a succinct receipt instead"
+                ),
+            }
+        },
+    )
+}
+
+/// Compress a [SuccinctReceipt] into a [Groth16Receipt] by running the identity program with a
+/// Poseidon hash function over the BN254 field, followed by the STARK-to-SNARK prover.
+fn succinct_to_groth16<P: ProverServer + ?Sized>(
+    prover: &P,
+    receipt: &SuccinctReceipt<ReceiptClaim>,
+) -> Result<Groth16Receipt<ReceiptClaim>> {
+    let ident_receipt = prover.identity_p254(receipt)?;
+    let seal_bytes = ident_receipt.get_seal_bytes();
+
+    let seal = stark_to_snark(&seal_bytes)?.to_vec();
+    Ok(Groth16Receipt {
+        seal,
+        claim: receipt.claim.clone(),
+        verifier_parameters: Groth16ReceiptVerifierParameters::default().digest(),
+    })
+}
+
 /// A pair of [Hal] and [CircuitHal].
 #[derive(Clone)]
 pub struct HalPair<H, C>
@@ -12,17 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::mpsc, thread};
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
-use risc0_circuit_rv32im::prove::SegmentProver;
+use risc0_circuit_rv32im::prove::{segment_prover, SegmentProver};
 
-use super::ProverServer;
+use super::{
+    checkpoint::{load_or_prove, load_verified, store, Checkpoint},
+    join_tree::lift_join_tree,
+    resolve_assumptions, succinct_to_groth16, ProverServer,
+};
 use crate::{
     host::{
-        client::prove::ReceiptKind,
+        client::{
+            env::ProveZkrRequest,
+            prove::{progress::ProgressEvent, ReceiptKind},
+        },
         prove_info::ProveInfo,
-        recursion::{identity_p254, join, lift, resolve},
+        recursion::{
+            identity_p254, join, lift,
+            prove::{joined_claim, resolved_claim},
+            resolve,
+        },
     },
     prove_zkr,
     receipt::{
@@ -30,24 +41,169 @@ use crate::{
     },
     receipt_claim::{MaybePruned, Merge, Unknown},
     sha::Digestible,
-    Assumption, AssumptionReceipt, CompositeReceipt, InnerAssumptionReceipt, Output, ProverOpts,
-    Receipt, ReceiptClaim, Segment, Session, VerifierContext,
+    Assumption, AssumptionReceipt, CompositeReceipt, ExecutorEnv, ExecutorImpl, Groth16Receipt,
+    InnerAssumptionReceipt, NullSegmentRef, Output, ProverOpts, Receipt, ReceiptClaim, Segment,
+    Session, SessionEvents, SuccinctReceiptVerifierParameters, VerifierContext,
 };
 
+/// The parts of a [VerifierContext] used to verify checkpointed recursion receipts.
+///
+/// A [VerifierContext] holds reference-counted hash suites, so it cannot be shared with the
+/// threads that prove lift and join programs. Each of them rebuilds it from these parameters
+/// instead.
+struct RecursionVerifierParams {
+    hash_suites: Vec<String>,
+    succinct_verifier_parameters: Option<SuccinctReceiptVerifierParameters>,
+}
+
+impl RecursionVerifierParams {
+    fn new(ctx: &VerifierContext) -> Self {
+        Self {
+            hash_suites: ctx.suites.keys().cloned().collect(),
+            succinct_verifier_parameters: ctx.succinct_verifier_parameters.clone(),
+        }
+    }
+
+    /// Rebuild the [VerifierContext] on the current thread.
+    ///
+    /// Only the [VerifierContext::default_hash_suites] are carried over, so checkpoints that use
+    /// any other hash suite fail to verify and are proven again.
+    fn verifier_context(&self) -> VerifierContext {
+        let mut suites = VerifierContext::default_hash_suites();
+        suites.retain(|name, _| self.hash_suites.contains(name));
+        let mut ctx = VerifierContext::empty().with_suites(suites);
+        ctx.succinct_verifier_parameters = self.succinct_verifier_parameters.clone();
+        ctx
+    }
+}
+
+/// A segment seal produced by the streaming prover, along with the [Segment] it proves.
+struct ProvenSegment {
+    key: String,
+    segment: Segment,
+    seal: Vec<u32>,
+}
+
 /// An implementation of a Prover that runs locally.
 pub struct ProverImpl {
     opts: ProverOpts,
     segment_prover: Box<dyn SegmentProver>,
+    checkpoint: Option<Checkpoint>,
 }
 
 impl ProverImpl {
     /// Construct a [ProverImpl].
     pub fn new(opts: ProverOpts, segment_prover: Box<dyn SegmentProver>) -> Self {
+        let checkpoint = opts.checkpoint_dir.as_ref().map(Checkpoint::new);
         Self {
             opts,
             segment_prover,
+            checkpoint,
         }
     }
+
+    /// Construct and verify a [SegmentReceipt] from the seal produced for a segment.
+    fn segment_receipt(
+        &self,
+        ctx: &VerifierContext,
+        index: u32,
+        output: Option<Output>,
+        seal: Vec<u32>,
+    ) -> Result<SegmentReceipt> {
+        let mut claim = decode_receipt_claim_from_seal(&seal)?;
+        claim.output = output.into();
+
+        let verifier_parameters = ctx
+            .segment_verifier_parameters
+            .as_ref()
+            .ok_or(anyhow!(
+                "segment receipt verifier parameters missing from context"
+            ))?
+            .digest();
+        let receipt = SegmentReceipt {
+            seal,
+            index,
+            hashfn: self.opts.hashfn.clone(),
+            claim,
+            verifier_parameters,
+        };
+        receipt.verify_integrity_with_context(ctx)?;
+
+        Ok(receipt)
+    }
+
+    /// Construct the receipt for a segment proven by the streaming prover, and checkpoint it.
+    fn checkpoint_segment_receipt(
+        &self,
+        ctx: &VerifierContext,
+        hooks: &[Box<dyn SessionEvents>],
+        proven: ProvenSegment,
+    ) -> Result<SegmentReceipt> {
+        let segment = proven.segment;
+        let receipt =
+            self.segment_receipt(ctx, segment.index, segment.output.clone(), proven.seal)?;
+        store(self.checkpoint.as_ref(), &proven.key, &receipt)?;
+        for hook in hooks {
+            hook.on_post_prove_segment(&segment);
+        }
+        self.opts.report(ProgressEvent::SegmentProved {
+            index: receipt.index,
+        });
+        Ok(receipt)
+    }
+
+    /// Assemble the proven segments of a [Session] into a [Receipt], compressing it to the
+    /// requested [ReceiptKind].
+    fn finish_session(
+        &self,
+        ctx: &VerifierContext,
+        session: &Session,
+        segments: Vec<SegmentReceipt>,
+    ) -> Result<ProveInfo> {
+        finish_session(
+            self,
+            &self.opts,
+            self.checkpoint.as_ref(),
+            ctx,
+            session,
+            segments,
+            |request| {
+                self.opts
+                    .cancellable(|| prove_zkr(&request.control_id, &request.input))
+            },
+            |receipt| self.composite_to_succinct_with_ctx(ctx, receipt),
+        )
+    }
+
+    /// Compress a [CompositeReceipt] into a [SuccinctReceipt], verifying any checkpointed
+    /// recursion receipts against the given [VerifierContext].
+    fn composite_to_succinct_with_ctx(
+        &self,
+        ctx: &VerifierContext,
+        receipt: &CompositeReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        // Lift and join the segments as a balanced tree, proving independent recursion programs
+        // in parallel.
+        let opts = &self.opts;
+        let checkpoint = self.checkpoint.as_ref();
+        let params = &RecursionVerifierParams::new(ctx);
+        let continuation_receipt = lift_join_tree(
+            &receipt.segments,
+            opts.recursion_workers,
+            |receipt| lift_checkpointed(opts, checkpoint, &params.verifier_context(), receipt),
+            |a, b| join_checkpointed(opts, checkpoint, &params.verifier_context(), a, b),
+        )?;
+
+        // Compress assumptions and resolve them to get the final succinct receipt.
+        resolve_assumptions(
+            continuation_receipt,
+            &receipt.assumption_receipts,
+            |conditional, assumption| {
+                resolve_checkpointed(opts, checkpoint, ctx, conditional, assumption)
+            },
+            |receipt| self.composite_to_succinct_with_ctx(ctx, receipt),
+        )
+    }
 }
 
 impl ProverServer for ProverImpl {
@@ -70,110 +226,7 @@ impl ProverServer for ProverImpl {
             }
         }
 
-        let (assumptions, session_assumption_receipts): (Vec<_>, Vec<_>) =
-            session.assumptions.iter().cloned().unzip();
-
-        // Merge the output, including journal digest and assumptions, into the last segment.
-        segments
-            .last_mut()
-            .ok_or(anyhow!("session is empty"))?
-            .claim
-            .output
-            .merge_with(
-                &session
-                    .journal
-                    .as_ref()
-                    .map(|journal| Output {
-                        journal: MaybePruned::Pruned(journal.digest()),
-                        assumptions: assumptions.into(),
-                    })
-                    .into(),
-            )
-            .context("failed to merge output into final segment claim")?;
-
-        let verifier_parameters = ctx
-            .composite_verifier_parameters()
-            .ok_or(anyhow!(
-                "composite receipt verifier parameters missing from context"
-            ))?
-            .digest();
-
-        let mut zkr_receipts = HashMap::new();
-        for proof_request in session.pending_zkrs.iter() {
-            let receipt = prove_zkr(&proof_request.control_id, &proof_request.input)?;
-            let assumption = Assumption {
-                claim: receipt.claim.digest(),
-                control_root: receipt.control_root()?,
-            };
-            zkr_receipts.insert(assumption, receipt);
-        }
-
-        // TODO: add test case for when a single session refers to the same assumption multiple times
-        let inner_assumption_receipts: Vec<_> = session_assumption_receipts
-            .into_iter()
-            .map(|assumption_receipt| match assumption_receipt {
-                AssumptionReceipt::Proven(receipt) => Ok(receipt),
-                AssumptionReceipt::Unresolved(assumption) => {
-                    let receipt = zkr_receipts
-                        .get(&assumption)
-                        .ok_or(anyhow!("no receipt available for unresolved assumption"))?;
-                    Ok(InnerAssumptionReceipt::Succinct(receipt.clone()))
-                }
-            })
-            .collect::<Result<_>>()?;
-
-        let assumption_receipts: Vec<_> = inner_assumption_receipts
-            .iter()
-            .map(|inner| AssumptionReceipt::Proven(inner.clone()))
-            .collect();
-
-        let composite_receipt = CompositeReceipt {
-            segments,
-            assumption_receipts: inner_assumption_receipts,
-            verifier_parameters,
-        };
-
-        let session_claim = session.claim_with_assumptions(assumption_receipts.iter())?;
-
-        // Verify the receipt to catch if something is broken in the proving process.
-        composite_receipt.verify_integrity_with_context(ctx)?;
-        check_claims(
-            &session_claim,
-            "composite",
-            MaybePruned::Value(composite_receipt.claim()?),
-        )?;
-
-        // Compress the receipt to the requested level.
-        let receipt = match self.opts.receipt_kind {
-            ReceiptKind::Composite => Receipt::new(
-                InnerReceipt::Composite(composite_receipt),
-                session.journal.clone().unwrap_or_default().bytes,
-            ),
-            ReceiptKind::Succinct => {
-                let succinct_receipt = self.composite_to_succinct(&composite_receipt)?;
-                Receipt::new(
-                    InnerReceipt::Succinct(succinct_receipt),
-                    session.journal.clone().unwrap_or_default().bytes,
-                )
-            }
-            ReceiptKind::Groth16 => {
-                let succinct_receipt = self.composite_to_succinct(&composite_receipt)?;
-                let groth16_receipt = self.succinct_to_groth16(&succinct_receipt)?;
-                Receipt::new(
-                    InnerReceipt::Groth16(groth16_receipt),
-                    session.journal.clone().unwrap_or_default().bytes,
-                )
-            }
-        };
-
-        // Verify the receipt to catch if something is broken in the proving process.
-        receipt.verify_integrity_with_context(ctx)?;
-        check_claims(&session_claim, "receipt", receipt.claim()?)?;
-
-        Ok(ProveInfo {
-            receipt,
-            stats: session.stats(),
-        })
+        self.finish_session(ctx, session, segments)
     }
 
     fn prove_segment(&self, ctx: &VerifierContext, segment: &Segment) -> Result<SegmentReceipt> {
@@ -184,58 +237,427 @@ impl ProverServer for ProverImpl {
             self.opts.max_segment_po2
         );
 
-        let seal = self.segment_prover.prove_segment(&segment.inner)?;
-
-        let mut claim = decode_receipt_claim_from_seal(&seal)?;
-        claim.output = segment.output.clone().into();
+        self.opts.check_cancelled()?;
 
-        let verifier_parameters = ctx
-            .segment_verifier_parameters
-            .as_ref()
-            .ok_or(anyhow!(
-                "segment receipt verifier parameters missing from context"
-            ))?
-            .digest();
-        let receipt = SegmentReceipt {
-            seal,
+        let receipt = load_or_prove(
+            self.checkpoint.as_ref(),
+            &segment_key(&self.opts.hashfn, segment),
+            |receipt: &SegmentReceipt| {
+                verify_segment_checkpoint(ctx, &self.opts.hashfn, segment, receipt)
+            },
+            || {
+                let seal = self
+                    .opts
+                    .cancellable(|| self.segment_prover.prove_segment(&segment.inner))?;
+                self.segment_receipt(ctx, segment.index, segment.output.clone(), seal)
+            },
+        )?;
+        self.opts.report(ProgressEvent::SegmentProved {
             index: segment.index,
-            hashfn: self.opts.hashfn.clone(),
-            claim,
-            verifier_parameters,
-        };
-        receipt.verify_integrity_with_context(ctx)?;
-
+        });
         Ok(receipt)
     }
 
-    fn lift(&self, receipt: &SegmentReceipt) -> Result<SuccinctReceipt<ReceiptClaim>> {
-        lift(receipt)
+    fn prove_streaming(
+        &self,
+        env: ExecutorEnv<'_>,
+        ctx: &VerifierContext,
+        elf: &[u8],
+        queue_depth: usize,
+    ) -> Result<ProveInfo> {
+        ensure!(queue_depth > 0, "queue depth must be at least 1");
+
+        let mut exec = ExecutorImpl::from_elf(env, elf)?;
+        let hooks = exec.take_session_hooks();
+        let (segment_tx, segment_rx) = mpsc::sync_channel::<Segment>(queue_depth);
+        let (seal_tx, seal_rx) = mpsc::channel::<ProvenSegment>();
+        let hashfn = self.opts.hashfn.clone();
+        let opts = self.opts.clone();
+
+        thread::scope(|scope| {
+            // Prove segments on a separate thread while the executor keeps running.
+            let worker = scope.spawn(move || -> Result<()> {
+                let segment_prover = segment_prover(&hashfn)?;
+                for segment in segment_rx {
+                    let seal = opts.cancellable(|| segment_prover.prove_segment(&segment.inner))?;
+                    let proven = ProvenSegment {
+                        key: segment_key(&hashfn, &segment),
+                        segment,
+                        seal,
+                    };
+                    if seal_tx.send(proven).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+
+            let mut segments = Vec::new();
+            let session = {
+                let segment_tx = segment_tx;
+                exec.run_with_callback(|segment| {
+                    self.opts.check_cancelled()?;
+                    ensure!(
+                        segment.po2() <= self.opts.max_segment_po2,
+                        "segment po2 exceeds max on ProverOpts: {} > {}",
+                        segment.po2(),
+                        self.opts.max_segment_po2
+                    );
+                    for proven in seal_rx.try_iter() {
+                        segments.push(self.checkpoint_segment_receipt(ctx, &hooks, proven)?);
+                    }
+                    for hook in &hooks {
+                        hook.on_pre_prove_segment(&segment);
+                    }
+                    if let Some(receipt) = load_verified(
+                        self.checkpoint.as_ref(),
+                        &segment_key(&self.opts.hashfn, &segment),
+                        |receipt: &SegmentReceipt| {
+                            verify_segment_checkpoint(ctx, &self.opts.hashfn, &segment, receipt)
+                        },
+                    ) {
+                        for hook in &hooks {
+                            hook.on_post_prove_segment(&segment);
+                        }
+                        self.opts.report(ProgressEvent::SegmentProved {
+                            index: receipt.index,
+                        });
+                        segments.push(receipt);
+                        return Ok(Box::new(NullSegmentRef));
+                    }
+                    // Blocks while the queue is full, which bounds the number of segments held
+                    // in memory.
+                    segment_tx
+                        .send(segment)
+                        .map_err(|_| anyhow!("segment prover stopped unexpectedly"))?;
+                    Ok(Box::new(NullSegmentRef))
+                })
+            };
+
+            // Errors from the segment prover take precedence, since they cause execution to fail.
+            match worker.join() {
+                Ok(result) => result?,
+                Err(err) => std::panic::resume_unwind(err),
+            }
+            let mut session = session?;
+
+            for proven in seal_rx.iter() {
+                segments.push(self.checkpoint_segment_receipt(ctx, &hooks, proven)?);
+            }
+            // Checkpointed segments are not proven, so receipts may arrive out of order.
+            segments.sort_by_key(|receipt| receipt.index);
+            ensure!(
+                segments.len() == session.segments.len(),
+                "expected {} segment receipts, got {}",
+                session.segments.len(),
+                segments.len()
+            );
+
+            session.hooks = hooks;
+            self.finish_session(ctx, &session, segments)
+        })
     }
 
-    fn join(
+    fn lift_with_ctx(
         &self,
+        ctx: &VerifierContext,
+        receipt: &SegmentReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        lift_checkpointed(&self.opts, self.checkpoint.as_ref(), ctx, receipt)
+    }
+
+    fn join_with_ctx(
+        &self,
+        ctx: &VerifierContext,
         a: &SuccinctReceipt<ReceiptClaim>,
         b: &SuccinctReceipt<ReceiptClaim>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>> {
-        join(a, b)
+        join_checkpointed(&self.opts, self.checkpoint.as_ref(), ctx, a, b)
     }
 
-    fn resolve(
+    fn resolve_with_ctx(
         &self,
+        ctx: &VerifierContext,
         conditional: &SuccinctReceipt<ReceiptClaim>,
         assumption: &SuccinctReceipt<Unknown>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>> {
-        resolve(conditional, assumption)
+        resolve_checkpointed(
+            &self.opts,
+            self.checkpoint.as_ref(),
+            ctx,
+            conditional,
+            assumption,
+        )
     }
 
     fn identity_p254(
         &self,
         a: &SuccinctReceipt<ReceiptClaim>,
     ) -> Result<SuccinctReceipt<ReceiptClaim>> {
-        identity_p254(a)
+        self.opts.cancellable(|| identity_p254(a))
+    }
+
+    fn composite_to_succinct(
+        &self,
+        receipt: &CompositeReceipt,
+    ) -> Result<SuccinctReceipt<ReceiptClaim>> {
+        self.composite_to_succinct_with_ctx(&VerifierContext::default(), receipt)
+    }
+
+    fn succinct_to_groth16(
+        &self,
+        receipt: &SuccinctReceipt<ReceiptClaim>,
+    ) -> Result<Groth16Receipt<ReceiptClaim>> {
+        self.opts.check_cancelled()?;
+        self.opts.report(ProgressEvent::Groth16Started);
+        self.opts.cancellable(|| succinct_to_groth16(self, receipt))
     }
 }
 
+/// Assemble the proven segments of a [Session] into a [Receipt], compressing it to the
+/// [ReceiptKind] requested by `opts` with the given prover.
+///
+/// The receipts for recursion programs requested by the guest are proven with `prove_zkr`, and
+/// composite receipts are compressed with `composite_to_succinct`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn finish_session<P, Z, C>(
+    prover: &P,
+    opts: &ProverOpts,
+    checkpoint: Option<&Checkpoint>,
+    ctx: &VerifierContext,
+    session: &Session,
+    mut segments: Vec<SegmentReceipt>,
+    prove_zkr: Z,
+    composite_to_succinct: C,
+) -> Result<ProveInfo>
+where
+    P: ProverServer + ?Sized,
+    Z: Fn(&ProveZkrRequest) -> Result<SuccinctReceipt<Unknown>>,
+    C: Fn(&CompositeReceipt) -> Result<SuccinctReceipt<ReceiptClaim>>,
+{
+    let (assumptions, session_assumption_receipts): (Vec<_>, Vec<_>) =
+        session.assumptions.iter().cloned().unzip();
+
+    // Merge the output, including journal digest and assumptions, into the last segment.
+    segments
+        .last_mut()
+        .ok_or(anyhow!("session is empty"))?
+        .claim
+        .output
+        .merge_with(
+            &session
+                .journal
+                .as_ref()
+                .map(|journal| Output {
+                    journal: MaybePruned::Pruned(journal.digest()),
+                    assumptions: assumptions.into(),
+                })
+                .into(),
+        )
+        .context("failed to merge output into final segment claim")?;
+
+    let verifier_parameters = ctx
+        .composite_verifier_parameters()
+        .ok_or(anyhow!(
+            "composite receipt verifier parameters missing from context"
+        ))?
+        .digest();
+
+    let mut zkr_receipts = HashMap::new();
+    for proof_request in session.pending_zkrs.iter() {
+        let receipt = load_or_prove(
+            checkpoint,
+            &format!("zkr-{}", proof_request.claim_digest),
+            |receipt: &SuccinctReceipt<Unknown>| {
+                ensure!(
+                    receipt.claim.digest() == proof_request.claim_digest,
+                    "zkr claim does not match"
+                );
+                Ok(receipt.verify_integrity_with_context(ctx)?)
+            },
+            || prove_zkr(proof_request),
+        )?;
+        let assumption = Assumption {
+            claim: receipt.claim.digest(),
+            control_root: receipt.control_root()?,
+        };
+        zkr_receipts.insert(assumption, receipt);
+    }
+
+    // TODO: add test case for when a single session refers to the same assumption multiple times
+    let inner_assumption_receipts: Vec<_> = session_assumption_receipts
+        .into_iter()
+        .map(|assumption_receipt| match assumption_receipt {
+            AssumptionReceipt::Proven(receipt) => Ok(receipt),
+            AssumptionReceipt::Unresolved(assumption) => {
+                let receipt = zkr_receipts
+                    .get(&assumption)
+                    .ok_or(anyhow!("no receipt available for unresolved assumption"))?;
+                Ok(InnerAssumptionReceipt::Succinct(receipt.clone()))
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    let assumption_receipts: Vec<_> = inner_assumption_receipts
+        .iter()
+        .map(|inner| AssumptionReceipt::Proven(inner.clone()))
+        .collect();
+
+    let composite_receipt = CompositeReceipt {
+        segments,
+        assumption_receipts: inner_assumption_receipts,
+        verifier_parameters,
+    };
+
+    let session_claim = session.claim_with_assumptions(assumption_receipts.iter())?;
+
+    // Verify the receipt to catch if something is broken in the proving process.
+    composite_receipt.verify_integrity_with_context(ctx)?;
+    check_claims(
+        &session_claim,
+        "composite",
+        MaybePruned::Value(composite_receipt.claim()?),
+    )?;
+
+    // Compress the receipt to the requested level.
+    let receipt = match opts.receipt_kind {
+        ReceiptKind::Composite => Receipt::new(
+            InnerReceipt::Composite(composite_receipt),
+            session.journal.clone().unwrap_or_default().bytes,
+        ),
+        ReceiptKind::Succinct => {
+            let succinct_receipt = composite_to_succinct(&composite_receipt)?;
+            Receipt::new(
+                InnerReceipt::Succinct(succinct_receipt),
+                session.journal.clone().unwrap_or_default().bytes,
+            )
+        }
+        ReceiptKind::Groth16 => {
+            let succinct_receipt = composite_to_succinct(&composite_receipt)?;
+            let groth16_receipt = prover.succinct_to_groth16(&succinct_receipt)?;
+            Receipt::new(
+                InnerReceipt::Groth16(groth16_receipt),
+                session.journal.clone().unwrap_or_default().bytes,
+            )
+        }
+    };
+
+    // Verify the receipt to catch if something is broken in the proving process.
+    receipt.verify_integrity_with_context(ctx)?;
+    check_claims(&session_claim, "receipt", receipt.claim()?)?;
+    opts.report(ProgressEvent::ReceiptProduced {
+        bytes: bincode::serialized_size(&receipt)?,
+    });
+
+    Ok(ProveInfo {
+        receipt,
+        stats: session.stats(),
+    })
+}
+
+/// Key under which the receipt for a segment is checkpointed.
+///
+/// Segments are identified by their index and pre-state, which are deterministic for a given ELF
+/// binary and input.
+fn segment_key(hashfn: &str, segment: &Segment) -> String {
+    format!(
+        "segment-{hashfn}-{}-{}",
+        segment.index,
+        segment.inner.pre_state.digest()
+    )
+}
+
+fn verify_segment_checkpoint(
+    ctx: &VerifierContext,
+    hashfn: &str,
+    segment: &Segment,
+    receipt: &SegmentReceipt,
+) -> Result<()> {
+    ensure!(receipt.hashfn == hashfn, "hash function does not match");
+    ensure!(
+        receipt.index == segment.index,
+        "segment index does not match"
+    );
+    ensure!(
+        receipt.claim.pre.digest() == segment.inner.pre_state.digest()
+            && receipt.claim.post.digest() == segment.inner.post_state.digest()
+            && receipt.claim.exit_code == segment.inner.exit_code,
+        "segment claim does not match"
+    );
+    Ok(receipt.verify_integrity_with_context(ctx)?)
+}
+
+/// Verify a checkpointed recursion receipt, which must prove the `expected` claim.
+fn verify_succinct_checkpoint(
+    ctx: &VerifierContext,
+    expected: &ReceiptClaim,
+    receipt: &SuccinctReceipt<ReceiptClaim>,
+) -> Result<()> {
+    ensure!(
+        receipt.claim.digest() == expected.digest(),
+        "recursion claim does not match"
+    );
+    Ok(receipt.verify_integrity_with_context(ctx)?)
+}
+
+fn lift_checkpointed(
+    opts: &ProverOpts,
+    checkpoint: Option<&Checkpoint>,
+    ctx: &VerifierContext,
+    receipt: &SegmentReceipt,
+) -> Result<SuccinctReceipt<ReceiptClaim>> {
+    let lifted = load_or_prove(
+        checkpoint,
+        &format!("lift-{}", receipt.claim.digest()),
+        |lifted| verify_succinct_checkpoint(ctx, &receipt.claim, lifted),
+        || opts.cancellable(|| lift(receipt)),
+    )?;
+    opts.report(ProgressEvent::LiftDone {
+        index: receipt.index,
+    });
+    Ok(lifted)
+}
+
+fn join_checkpointed(
+    opts: &ProverOpts,
+    checkpoint: Option<&Checkpoint>,
+    ctx: &VerifierContext,
+    a: &SuccinctReceipt<ReceiptClaim>,
+    b: &SuccinctReceipt<ReceiptClaim>,
+) -> Result<SuccinctReceipt<ReceiptClaim>> {
+    let expected = joined_claim(a, b)?;
+    let joined = load_or_prove(
+        checkpoint,
+        &format!("join-{}-{}", a.claim.digest(), b.claim.digest()),
+        |joined| verify_succinct_checkpoint(ctx, &expected, joined),
+        || opts.cancellable(|| join(a, b)),
+    )?;
+    opts.report(ProgressEvent::JoinDone);
+    Ok(joined)
+}
+
+fn resolve_checkpointed(
+    opts: &ProverOpts,
+    checkpoint: Option<&Checkpoint>,
+    ctx: &VerifierContext,
+    conditional: &SuccinctReceipt<ReceiptClaim>,
+    assumption: &SuccinctReceipt<Unknown>,
+) -> Result<SuccinctReceipt<ReceiptClaim>> {
+    let expected = resolved_claim(conditional)?;
+    let resolved = load_or_prove(
+        checkpoint,
+        &format!(
+            "resolve-{}-{}",
+            conditional.claim.digest(),
+            assumption.claim.digest()
+        ),
+        |resolved| verify_succinct_checkpoint(ctx, &expected, resolved),
+        || opts.cancellable(|| resolve(conditional, assumption)),
+    )?;
+    opts.report(ProgressEvent::ResolveDone);
+    Ok(resolved)
+}
+
 fn check_claims(
     session_claim: &ReceiptClaim,
     other_name: &str,
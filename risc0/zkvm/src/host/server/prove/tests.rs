@@ -24,7 +24,9 @@ use super::get_prover_server;
 use crate::{
     host::server::testutils,
     serde::{from_slice, to_vec},
-    ExecutorEnv, ExecutorImpl, ExitCode, ProveInfo, ProverOpts, Receipt, Session, VerifierContext,
+    sha::Digestible,
+    ExecutorEnv, ExecutorImpl, ExitCode, MaybePruned, ProveInfo, ProverOpts, Receipt,
+    SegmentReceipt, Session, SessionSnapshot, SuccinctBatchVerifier, VerifierContext,
 };
 
 fn prove_session_fast(session: &Session) -> Receipt {
@@ -63,6 +65,33 @@ fn prove_nothing_succinct() {
         .unwrap(); // ensure that we got a succinct receipt.
 }
 
+#[test]
+fn batch_verify_succinct() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let opts = ProverOpts::succinct();
+    let receipt = get_prover_server(&opts)
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap()
+        .receipt;
+    let succinct = receipt.inner.succinct().unwrap();
+
+    let mut receipts = vec![succinct.clone(); 3];
+    receipts[1].claim = MaybePruned::Pruned(Digest::ZERO);
+
+    let verifier = SuccinctBatchVerifier::new(&VerifierContext::default()).unwrap();
+    verifier.verify_integrity(&receipts[..1]).unwrap();
+    let err = verifier.verify_integrity(&receipts).unwrap_err();
+    assert_eq!(
+        err.failures,
+        vec![(1, VerificationError::JournalDigestMismatch)]
+    );
+}
+
 #[test]
 fn hashfn_poseidon2() {
     prove_nothing("poseidon2").unwrap();
@@ -285,6 +314,121 @@ fn session_events() {
     assert!(on_post_prove_segment_flag.take());
 }
 
+#[test]
+fn prove_streaming() {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{LocalProver, Prover, Segment, SessionEvents};
+
+    #[derive(Default)]
+    struct Counter {
+        pre: Cell<usize>,
+        post: Cell<usize>,
+    }
+
+    struct CountingHook(Rc<Counter>);
+
+    impl SessionEvents for CountingHook {
+        fn on_pre_prove_segment(&self, _: &Segment) {
+            self.0.pre.set(self.0.pre.get() + 1);
+        }
+
+        fn on_post_prove_segment(&self, _: &Segment) {
+            self.0.post.set(self.0.post.get() + 1);
+        }
+    }
+
+    let segment_limit_po2 = 15; // 32k cycles
+    let counter = Rc::new(Counter::default());
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::BusyLoop {
+            cycles: 3 << segment_limit_po2,
+        })
+        .unwrap()
+        .segment_limit_po2(segment_limit_po2)
+        .session_hook(CountingHook(counter.clone()))
+        .build()
+        .unwrap();
+    let prove_info = LocalProver::new("local")
+        .prove_streaming(
+            env,
+            &VerifierContext::default(),
+            MULTI_TEST_ELF,
+            &ProverOpts::fast(),
+            1,
+        )
+        .unwrap();
+    assert!(prove_info.stats.segments > 1);
+    assert_eq!(counter.pre.get(), prove_info.stats.segments);
+    assert_eq!(counter.post.get(), prove_info.stats.segments);
+
+    let segments = &prove_info.receipt.inner.composite().unwrap().segments;
+    assert_eq!(segments.len(), prove_info.stats.segments);
+    for (idx, receipt) in segments.iter().enumerate() {
+        assert_eq!(receipt.index, idx as u32);
+    }
+    prove_info.receipt.verify(MULTI_TEST_ID).unwrap();
+}
+
+#[test]
+fn checkpoint_resume() {
+    let dir = tempfile::tempdir().unwrap();
+    let prove = || {
+        let env = ExecutorEnv::builder()
+            .write(&MultiTestSpec::BusyLoop { cycles: 1 << 16 })
+            .unwrap()
+            .segment_limit_po2(15)
+            .build()
+            .unwrap();
+        let opts = ProverOpts::fast().with_checkpoint_dir(dir.path());
+        get_prover_server(&opts)
+            .unwrap()
+            .prove(env, MULTI_TEST_ELF)
+            .unwrap()
+    };
+
+    let first = prove();
+    let checkpoints = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(checkpoints, first.stats.segments);
+
+    // Proving again reuses the checkpointed segment receipts.
+    let second = prove();
+    second.receipt.verify(MULTI_TEST_ID).unwrap();
+    assert_eq!(
+        first.receipt.inner.composite().unwrap().segments[0].seal,
+        second.receipt.inner.composite().unwrap().segments[0].seal
+    );
+}
+
+#[test]
+fn checkpoint_rejects_mismatched_claim() {
+    let dir = tempfile::tempdir().unwrap();
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::BusyLoop { cycles: 1 << 16 })
+        .unwrap()
+        .segment_limit_po2(15)
+        .build()
+        .unwrap();
+    let opts = ProverOpts::fast().with_checkpoint_dir(dir.path());
+    let prover = get_prover_server(&opts).unwrap();
+    let prove_info = prover.prove(env, MULTI_TEST_ELF).unwrap();
+    let segments = &prove_info.receipt.inner.composite().unwrap().segments;
+    assert!(segments.len() > 1);
+
+    // Store the lift of the first segment under the key of the second one.
+    let first = prover.lift(&segments[0]).unwrap();
+    let lift_path = |receipt: &SegmentReceipt| {
+        dir.path()
+            .join(format!("lift-{}.bincode", receipt.claim.digest()))
+    };
+    std::fs::copy(lift_path(&segments[0]), lift_path(&segments[1])).unwrap();
+
+    // The checkpoint passes verification, but proves the wrong claim, so it is proven again.
+    let second = prover.lift(&segments[1]).unwrap();
+    assert_ne!(first.claim.digest(), second.claim.digest());
+    assert_eq!(second.claim.digest(), segments[1].claim.digest());
+}
+
 // These tests come from:
 // https://github.com/riscv-software-src/riscv-tests
 // They were built using the toolchain from:
@@ -404,6 +548,43 @@ fn pause_resume() {
     prove_session_fast(&session);
 }
 
+#[test]
+fn pause_resume_from_snapshot() {
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::PauseResume(0))
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut exec = ExecutorImpl::from_elf(env, MULTI_TEST_ELF).unwrap();
+
+    // Run until sys_pause and save a snapshot.
+    let session = exec.run().unwrap();
+    assert_eq!(session.exit_code, ExitCode::Paused(0));
+    assert_eq!(session.journal.as_ref().unwrap().bytes, b"before pause");
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.snapshot");
+    session.save_snapshot(&path).unwrap();
+    let snapshot = SessionSnapshot::load(&path).unwrap();
+    assert_eq!(snapshot.journal, session.journal);
+
+    // Resume in-process for reference.
+    let expected = exec.run().unwrap();
+    assert_eq!(expected.exit_code, ExitCode::Halted(0));
+
+    // Resume from the snapshot with a fresh executor.
+    let env = ExecutorEnv::builder().build().unwrap();
+    let mut exec = ExecutorImpl::resume_from_snapshot(env, &path).unwrap();
+    let session = exec.run().unwrap();
+    assert_eq!(session.exit_code, ExitCode::Halted(0));
+    assert_eq!(session.journal.as_ref().unwrap().bytes, b"after pause");
+    assert_eq!(session.journal, expected.journal);
+    assert_eq!(
+        session.claim().unwrap().digest(),
+        expected.claim().unwrap().digest()
+    );
+    prove_session_fast(&session);
+}
+
 #[test]
 fn pause_exit_nonzero() {
     let user_exit_code = 1;
@@ -1100,3 +1281,97 @@ mod soundness {
         assert_eq!(security, 97.945);
     }
 }
+
+#[test(tokio::test(flavor = "multi_thread"))]
+#[cfg(feature = "async")]
+async fn prove_async_shared_handle() {
+    use crate::{host::client::prove::local::LocalProver, ProverHandle};
+
+    let prover = ProverHandle::new(LocalProver::new("local"));
+    let tasks: Vec<_> = ["", "abc"]
+        .into_iter()
+        .map(|msg| {
+            let prover = prover.clone();
+            tokio::spawn(async move {
+                let env = move || {
+                    ExecutorEnv::builder()
+                        .write(&MultiTestSpec::ShaDigest { data: msg.into() })?
+                        .build()
+                };
+                prover
+                    .prove_with_ctx_async(
+                        env,
+                        VerifierContext::default,
+                        MULTI_TEST_ELF,
+                        ProverOpts::fast(),
+                    )
+                    .await
+            })
+        })
+        .collect();
+
+    let mut journals = Vec::new();
+    for task in tasks {
+        let receipt = task.await.unwrap().unwrap().receipt;
+        receipt.verify(MULTI_TEST_ID).unwrap();
+        journals.push(hex::encode(
+            Digest::try_from(receipt.journal.bytes).unwrap(),
+        ));
+    }
+    assert_eq!(
+        journals,
+        [
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ]
+    );
+}
+
+#[test]
+fn prove_reports_progress() {
+    use std::sync::{Arc, Mutex};
+
+    use crate::ProgressEvent;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let opts = ProverOpts::succinct().with_progress({
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event.clone())
+    });
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    get_prover_server(&opts)
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], ProgressEvent::SegmentProved { index: 0 });
+    assert!(events.contains(&ProgressEvent::LiftDone { index: 0 }));
+    assert!(matches!(
+        events.last(),
+        Some(ProgressEvent::ReceiptProduced { .. })
+    ));
+}
+
+#[test]
+fn prove_cancelled() {
+    use crate::{CancellationToken, Cancelled};
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let opts = ProverOpts::fast().with_cancellation_token(token);
+    let env = ExecutorEnv::builder()
+        .write(&MultiTestSpec::DoNothing)
+        .unwrap()
+        .build()
+        .unwrap();
+    let err = get_prover_server(&opts)
+        .unwrap()
+        .prove(env, MULTI_TEST_ELF)
+        .unwrap_err();
+    assert!(err.downcast_ref::<Cancelled>().is_some());
+}
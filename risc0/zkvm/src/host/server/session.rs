@@ -15,9 +15,13 @@
 //! This module defines [Session] and [Segment] which provides a way to share
 //! execution traces between the execution phase and the proving phase.
 
-use std::{collections::BTreeSet, fs, path::PathBuf};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Context, Result};
 use risc0_binfmt::{MemoryImage, SystemState};
 use risc0_circuit_rv32im::prove::segment::Segment as CircuitSegment;
 use serde::{Deserialize, Serialize};
@@ -28,8 +32,8 @@ use crate::{
         prove_info::SessionStats,
     },
     sha::Digest,
-    Assumption, AssumptionReceipt, Assumptions, ExitCode, Journal, MaybePruned, Output,
-    ReceiptClaim,
+    Assumption, AssumptionReceipt, Assumptions, CycleReport, ExitCode, Journal, MaybePruned,
+    Output, ReceiptClaim,
 };
 
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
@@ -90,6 +94,10 @@ pub struct Session {
     /// The system state of the final [MemoryImage] at the end of execution.
     pub post_state: SystemState,
 
+    /// The [CycleReport] collected during execution, if requested with
+    /// [ExecutorEnvBuilder::enable_cycle_report][crate::ExecutorEnvBuilder::enable_cycle_report].
+    pub cycle_report: Option<CycleReport>,
+
     /// A list of pending ZKR proof requests.
     // TODO: make this scalable so we don't OOM
     pub(crate) pending_zkrs: Vec<ProveZkrRequest>,
@@ -131,6 +139,51 @@ pub trait SegmentRef: Send {
     fn resolve(&self) -> Result<Segment>;
 }
 
+/// The state of a paused [Session], from which execution can be resumed later, possibly in a
+/// different process.
+///
+/// Use [Session::save_snapshot] to write a snapshot to disk, and
+/// [ExecutorImpl::resume_from_snapshot][crate::ExecutorImpl::resume_from_snapshot] to continue
+/// execution from it.
+#[derive(Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SessionSnapshot {
+    /// The [MemoryImage] at the point where execution paused.
+    pub image: MemoryImage,
+
+    /// The [ExitCode] of the paused session.
+    pub exit_code: ExitCode,
+
+    /// The input digest of the session.
+    pub input: Digest,
+
+    /// The data publicly committed by the guest program before it paused.
+    ///
+    /// This is the journal of the paused [Session]. The guest starts a new journal when it
+    /// resumes, so it is not part of the journal of the resumed [Session].
+    pub journal: Option<Journal>,
+
+    /// The assumptions made by the guest before it paused, along with the receipts resolving them.
+    pub assumptions: Vec<(Assumption, AssumptionReceipt)>,
+}
+
+impl SessionSnapshot {
+    /// Load a [SessionSnapshot] from the given path.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents =
+            fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(bincode::deserialize(&contents)?)
+    }
+
+    /// Save this [SessionSnapshot] to the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, bincode::serialize(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
 /// The Events of [Session]
 pub trait SessionEvents {
     /// Fired before the proving of a segment starts.
@@ -174,10 +227,34 @@ impl Session {
             total_cycles,
             pre_state,
             post_state,
+            cycle_report: None,
             pending_zkrs,
         }
     }
 
+    /// Capture the state of this paused [Session] as a [SessionSnapshot].
+    pub fn snapshot(&self) -> Result<SessionSnapshot> {
+        ensure!(
+            matches!(self.exit_code, ExitCode::Paused(_)),
+            "only paused sessions can be resumed: exit code is {:?}",
+            self.exit_code
+        );
+        Ok(SessionSnapshot {
+            image: self.post_image.clone(),
+            exit_code: self.exit_code,
+            input: self.input,
+            journal: self.journal.clone(),
+            assumptions: self.assumptions.clone(),
+        })
+    }
+
+    /// Save the state of this paused [Session] to the given path, so that execution can be
+    /// resumed later with
+    /// [ExecutorImpl::resume_from_snapshot][crate::ExecutorImpl::resume_from_snapshot].
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.snapshot()?.save(path)
+    }
+
     /// Add a hook to be called during the proving phase.
     pub fn add_hook<E: SessionEvents + 'static>(&mut self, hook: E) {
         self.hooks.push(Box::new(hook));
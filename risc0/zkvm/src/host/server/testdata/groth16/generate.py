@@ -0,0 +1,179 @@
+#!/usr/bin/env python3
+"""Generates the Groth16 receipt fixtures used by the `groth16_receipt_verify` test.
+
+* verification_key.json: a verifying key in the snarkjs format, for five public inputs, from fixed
+  (and therefore insecure) toxic waste.
+* proof.json: a proof, in the snarkjs format, for the public inputs of a Groth16 receipt with the
+  claim digest, control root and BN254 control ID below.
+
+Since the toxic waste is known, the proof is computed from the verification equation directly,
+without a circuit.
+"""
+
+import hashlib
+import json
+import sys
+from pathlib import Path
+
+Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+R = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+
+# Toxic waste. snarkjs always uses a gamma of one.
+ALPHA, BETA, DELTA = 3, 5, 7
+# The discrete logarithms of the IC points.
+IC = [11, 13, 17, 19, 23, 29]
+# The discrete logarithms of the proof's A and B.
+A, B = 31, 37
+
+
+def digest(name):
+    return hashlib.sha256(name.encode()).digest()
+
+
+CLAIM_DIGEST = digest("claim")
+CONTROL_ROOT = digest("control_root")
+# The most significant byte is cleared, so the control ID is less than R.
+BN254_CONTROL_ID = digest("bn254_control_id")[:31] + b"\x00"
+
+
+def public_inputs():
+    """The public inputs of a Groth16 receipt, as computed by `verify_integrity_with_context`."""
+
+    def split(d):
+        return [int.from_bytes(d[:16], "little"), int.from_bytes(d[16:], "little")]
+
+    return split(CONTROL_ROOT) + split(CLAIM_DIGEST) + [int.from_bytes(BN254_CONTROL_ID, "little")]
+
+
+def inv(x, p):
+    return pow(x, -1, p)
+
+
+class Fq:
+    zero = 0
+
+    @staticmethod
+    def add(a, b):
+        return (a + b) % Q
+
+    @staticmethod
+    def sub(a, b):
+        return (a - b) % Q
+
+    @staticmethod
+    def mul(a, b):
+        return a * b % Q
+
+    @staticmethod
+    def inv(a):
+        return inv(a, Q)
+
+
+class Fq2:
+    zero = (0, 0)
+
+    @staticmethod
+    def add(a, b):
+        return ((a[0] + b[0]) % Q, (a[1] + b[1]) % Q)
+
+    @staticmethod
+    def sub(a, b):
+        return ((a[0] - b[0]) % Q, (a[1] - b[1]) % Q)
+
+    @staticmethod
+    def mul(a, b):
+        return ((a[0] * b[0] - a[1] * b[1]) % Q, (a[0] * b[1] + a[1] * b[0]) % Q)
+
+    @staticmethod
+    def inv(a):
+        d = inv(a[0] * a[0] + a[1] * a[1], Q)
+        return (a[0] * d % Q, -a[1] * d % Q)
+
+
+def ec_add(F, p1, p2):
+    if p1 is None:
+        return p2
+    if p2 is None:
+        return p1
+    (x1, y1), (x2, y2) = p1, p2
+    if x1 == x2:
+        if F.add(y1, y2) == F.zero:
+            return None
+        three_x2 = F.mul(F.add(F.add(x1, x1), x1), x1)
+        lam = F.mul(three_x2, F.inv(F.add(y1, y1)))
+    else:
+        lam = F.mul(F.sub(y2, y1), F.inv(F.sub(x2, x1)))
+    x3 = F.sub(F.sub(F.mul(lam, lam), x1), x2)
+    return (x3, F.sub(F.mul(lam, F.sub(x1, x3)), y1))
+
+
+def ec_mul(F, k, p):
+    acc = None
+    k %= R
+    while k:
+        if k & 1:
+            acc = ec_add(F, acc, p)
+        p = ec_add(F, p, p)
+        k >>= 1
+    return acc
+
+
+G1 = (1, 2)
+G2 = (
+    (
+        10857046999023057135944570762232829481370756359578518086990519993285655852781,
+        11559732032986387107991004021392285783925812861821192530917403151452391805634,
+    ),
+    (
+        8495653923123431417604973247489272438418190587263600148770280649306958101930,
+        4082367875863433681332203403145435568316851327593401208105741076214120093531,
+    ),
+)
+
+
+def g1(k):
+    x, y = ec_mul(Fq, k, G1)
+    return [str(x), str(y), "1"]
+
+
+def g2(k):
+    (x0, x1), (y0, y1) = ec_mul(Fq2, k, G2)
+    return [[str(x0), str(x1)], [str(y0), str(y1)], ["1", "0"]]
+
+
+def verification_key():
+    return {
+        "protocol": "groth16",
+        "curve": "bn128",
+        "nPublic": len(IC) - 1,
+        "vk_alpha_1": g1(ALPHA),
+        "vk_beta_2": g2(BETA),
+        "vk_gamma_2": g2(1),
+        "vk_delta_2": g2(DELTA),
+        "vk_alphabeta_12": [],
+        "IC": [g1(k) for k in IC],
+    }
+
+
+def proof():
+    # e(A, B) = e(α, β) e(vk_x, γ) e(C, δ) holds if A B = α β + vk_x + C δ over the exponents.
+    inputs = public_inputs()
+    assert all(x < R for x in inputs)
+    vk_x = (IC[0] + sum(x * k for x, k in zip(inputs, IC[1:]))) % R
+    c = (A * B - ALPHA * BETA - vk_x) * inv(DELTA, R) % R
+    return {
+        "pi_a": g1(A),
+        "pi_b": g2(B),
+        "pi_c": g1(c),
+        "protocol": "groth16",
+        "curve": "bn128",
+    }
+
+
+if __name__ == "__main__":
+    out = Path(sys.argv[1] if len(sys.argv) > 1 else Path(__file__).parent)
+    (out / "verification_key.json").write_text(json.dumps(verification_key(), indent=1) + "\n")
+    (out / "proof.json").write_text(json.dumps(proof(), indent=1) + "\n")
+    print("claim digest:", CLAIM_DIGEST.hex())
+    print("control root:", CONTROL_ROOT.hex())
+    print("bn254 control id:", BN254_CONTROL_ID.hex())
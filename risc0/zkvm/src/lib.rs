@@ -54,6 +54,7 @@
 //! | client           | all except rv32im | std        | Enables the client API.                                                                                                                                      |
 //! | cuda             |                   | prove, std | Enables CUDA GPU acceleration for the prover. Requires CUDA toolkit to be installed.                                                                         |
 //! | disable-dev-mode | all except rv32im |            | Disables dev mode so that proving and verifying may not be faked. Used to prevent a misplaced `RISC0_DEV_MODE` from breaking security in production systems. |
+//! | groth16-native   | all except rv32im | prove, std | Produces Groth16 receipts with a prover implemented in Rust, instead of Docker. See the `prove-native` feature of risc0-groth16.                             |
 //! | metal            | macos             | prove, std | Deprecated - Metal GPU acceleration for the prover is now enabled by default on Apple Silicon.                                                               |
 //! | prove            | all except rv32im | std        | Enables the prover, incompatible within the zkvm guest.                                                                                                      |
 //! | std              | all               |            | Support for the Rust stdlib.                                                                                                                                 |
@@ -80,6 +81,8 @@ mod receipt_claim;
 pub mod serde;
 pub mod sha;
 
+#[cfg(all(not(target_os = "zkvm"), feature = "client"))]
+pub use host::codec;
 #[cfg(all(not(target_os = "zkvm"), feature = "prove"))]
 pub use host::recursion;
 
@@ -106,17 +109,15 @@ pub use {
         },
         server::{
             exec::executor::ExecutorImpl,
-            prove::{get_prover_server, HalPair, ProverServer},
+            prove::{distributed::Coordinator, get_prover_server, HalPair, ProverServer},
             session::{
                 FileSegmentRef, NullSegmentRef, Segment, SegmentRef, Session, SessionEvents,
-                SimpleSegmentRef,
+                SessionSnapshot, SimpleSegmentRef,
             },
         },
     },
     risc0_circuit_rv32im::prove::engine::loader::Loader,
-    risc0_groth16::{
-        docker::stark_to_snark, to_json as seal_to_json, ProofJson as Groth16ProofJson,
-    },
+    risc0_groth16::{stark_to_snark, to_json as seal_to_json, ProofJson as Groth16ProofJson},
 };
 
 #[cfg(feature = "bonsai")]
@@ -131,11 +132,16 @@ pub use {
             SessionInfo,
         },
         client::{
+            cycle_report::{CycleReport, FunctionCycles, SegmentCycles, SyscallCycles},
             env::{ExecutorEnv, ExecutorEnvBuilder},
             prove::{
-                default_executor, default_prover, external::ExternalProver, Executor, Prover,
-                ProverOpts, ReceiptKind,
+                default_executor, default_executor_handle, default_prover, default_prover_handle,
+                external::ExternalProver,
+                handle::{ExecutorHandle, ProverHandle},
+                progress::{CancellationToken, Cancelled, ProgressCallback, ProgressEvent},
+                Executor, Prover, ProverOpts, ReceiptKind,
             },
+            syscall::{Syscall, SyscallContext},
         },
     },
     risc0_circuit_rv32im::trace::{TraceCallback, TraceEvent},
@@ -164,9 +170,11 @@ pub use receipt::{
     AssumptionReceipt, CompositeReceipt, CompositeReceiptVerifierParameters, FakeReceipt,
     InnerAssumptionReceipt, InnerReceipt, Journal, Receipt, ReceiptMetadata, SegmentReceipt,
     SegmentReceiptVerifierParameters, SuccinctReceipt, SuccinctReceiptVerifierParameters,
-    VerifierContext, DEFAULT_MAX_PO2,
+    VerifierContext, VerifierContextConfig, DEFAULT_MAX_PO2,
 };
 //#[cfg(any(not(target_os = "zkvm"), feature = "std"))]
+#[cfg(all(not(target_os = "zkvm"), feature = "client"))]
+pub use receipt::batch::{BatchVerificationError, SuccinctBatchVerifier};
 pub use receipt::{Groth16Receipt, Groth16ReceiptVerifierParameters};
 
 use semver::Version;
@@ -14,6 +14,8 @@
 
 //! Manages the output and cryptographic data for a proven computation.
 
+#[cfg(all(not(target_os = "zkvm"), feature = "client"))]
+pub(crate) mod batch;
 pub(crate) mod composite;
 pub(crate) mod groth16;
 pub(crate) mod merkle;
@@ -23,15 +25,16 @@ pub(crate) mod succinct;
 use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 use core::fmt::Debug;
 
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
+use hex::FromHex;
 use risc0_core::field::baby_bear::BabyBear;
 use risc0_zkp::{
     core::{
         digest::Digest,
         hash::{
-            blake2b::Blake2bCpuHashSuite, poseidon2::Poseidon2HashSuite, sha::Sha256HashSuite,
-            HashSuite,
+            blake2b::Blake2bCpuHashSuite, hash_suite_from_name, poseidon2::Poseidon2HashSuite,
+            sha::Sha256HashSuite, HashSuite,
         },
     },
     verify::VerificationError,
@@ -673,6 +676,37 @@ impl From<InnerReceipt> for InnerAssumptionReceipt {
 /// a po2 higher than 21 shows a degradation of 1 bit of security per po2, to 94 bits at po2 24.
 pub const DEFAULT_MAX_PO2: usize = 21;
 
+/// Serializable configuration of a [VerifierContext].
+///
+/// Every field is optional, and an empty configuration produces the default [VerifierContext].
+/// This allows verifying receipts produced with a non-default maximum po2, or pinning an older
+/// control root or Groth16 verifying key, e.g. from a JSON file passed to the CLIs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct VerifierContextConfig {
+    /// Names of the hash suites accepted for STARK seals (e.g. "poseidon2" or "sha-256").
+    ///
+    /// Defaults to all of [VerifierContext::default_hash_suites].
+    pub hash_suites: Option<Vec<String>>,
+
+    /// Maximum segment size, as a power of two (po2), that will be accepted.
+    ///
+    /// Defaults to [DEFAULT_MAX_PO2].
+    pub max_po2: Option<usize>,
+
+    /// Control root, encoded as hex, with which succinct and Groth16 receipts are expected to
+    /// verify.
+    ///
+    /// Defaults to the control root calculated from `max_po2`.
+    pub control_root: Option<String>,
+
+    /// Groth16 verifying key, in the JSON format produced by snarkjs.
+    ///
+    /// Defaults to the verifying key of the current release.
+    pub groth16_verifying_key: Option<risc0_groth16::VerifyingKeyJson>,
+}
+
 /// Context available to the verification process.
 #[non_exhaustive]
 pub struct VerifierContext {
@@ -735,6 +769,55 @@ impl VerifierContext {
         Self::from_max_po2(risc0_zkp::MAX_CYCLES_PO2)
     }
 
+    /// Construct a verifier context from the given [VerifierContextConfig].
+    ///
+    /// Returns an error if the configuration names an unknown hash suite, a maximum po2 that is
+    /// not supported, or contains a malformed control root or verifying key.
+    #[stability::unstable]
+    pub fn from_config(config: &VerifierContextConfig) -> Result<Self> {
+        let mut ctx = match config.max_po2 {
+            Some(po2_max) => {
+                ensure!(
+                    po2_max <= risc0_zkp::MAX_CYCLES_PO2,
+                    "max po2 {po2_max} exceeds the supported maximum of {}",
+                    risc0_zkp::MAX_CYCLES_PO2
+                );
+                Self::from_max_po2(po2_max)
+            }
+            None => Self::default(),
+        };
+
+        if let Some(names) = &config.hash_suites {
+            ctx.suites = names
+                .iter()
+                .map(|name| {
+                    let suite = hash_suite_from_name(name)
+                        .ok_or_else(|| anyhow!("unknown hash suite: {name}"))?;
+                    Ok((name.clone(), suite))
+                })
+                .collect::<Result<_>>()?;
+        }
+
+        if let Some(control_root) = &config.control_root {
+            let control_root = Digest::from_hex(control_root)
+                .map_err(|err| anyhow!("invalid control root {control_root}: {err}"))?;
+            if let Some(params) = ctx.succinct_verifier_parameters.as_mut() {
+                params.control_root = control_root;
+            }
+            if let Some(params) = ctx.groth16_verifier_parameters.as_mut() {
+                params.control_root = control_root;
+            }
+        }
+
+        if let Some(verifying_key) = &config.groth16_verifying_key {
+            if let Some(params) = ctx.groth16_verifier_parameters.as_mut() {
+                params.verifying_key = verifying_key.verifying_key()?;
+            }
+        }
+
+        Ok(ctx)
+    }
+
     /// Return [VerifierContext] with the given map of hash suites.
     pub fn with_suites(mut self, suites: BTreeMap<String, HashSuite<BabyBear>>) -> Self {
         self.suites = suites;
@@ -794,9 +877,9 @@ impl Default for VerifierContext {
 
 #[cfg(test)]
 mod tests {
-    use super::{FakeReceipt, InnerReceipt, Receipt};
+    use super::{FakeReceipt, InnerReceipt, Receipt, VerifierContext, VerifierContextConfig};
     use crate::{
-        sha::{Digest, DIGEST_BYTES},
+        sha::{Digest, Digestible, DIGEST_BYTES},
         MaybePruned,
     };
     use risc0_zkp::verify::VerificationError;
@@ -857,4 +940,52 @@ mod tests {
         let decoded: Receipt = borsh::from_slice(&encoded).unwrap();
         assert_eq!(receipt, decoded);
     }
+
+    #[test]
+    fn verifier_context_from_config() {
+        let default_ctx = VerifierContext::default();
+        let ctx = VerifierContext::from_config(&VerifierContextConfig::default()).unwrap();
+        assert_eq!(
+            ctx.composite_verifier_parameters().unwrap().digest(),
+            default_ctx
+                .composite_verifier_parameters()
+                .unwrap()
+                .digest()
+        );
+        assert_eq!(
+            ctx.suites.keys().collect::<Vec<_>>(),
+            default_ctx.suites.keys().collect::<Vec<_>>()
+        );
+
+        let control_root = Digest::from([7u8; DIGEST_BYTES]);
+        let config: VerifierContextConfig = serde_json::from_value(serde_json::json!({
+            "hash_suites": ["poseidon2"],
+            "max_po2": 22,
+            "control_root": control_root.to_string(),
+        }))
+        .unwrap();
+        let ctx = VerifierContext::from_config(&config).unwrap();
+        assert_eq!(ctx.suites.keys().collect::<Vec<_>>(), ["poseidon2"]);
+        assert_eq!(
+            ctx.segment_verifier_parameters.unwrap().control_ids,
+            VerifierContext::from_max_po2(22)
+                .segment_verifier_parameters
+                .unwrap()
+                .control_ids
+        );
+        assert_eq!(
+            ctx.succinct_verifier_parameters.unwrap().control_root,
+            control_root
+        );
+        assert_eq!(
+            ctx.groth16_verifier_parameters.unwrap().control_root,
+            control_root
+        );
+
+        let config = VerifierContextConfig {
+            hash_suites: Some(vec!["md5".into()]),
+            ..Default::default()
+        };
+        assert!(VerifierContext::from_config(&config).is_err());
+    }
 }
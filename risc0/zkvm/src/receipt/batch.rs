@@ -0,0 +1,162 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parallel verification of many [SuccinctReceipt]s.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::RwLock,
+};
+
+use rayon::prelude::*;
+use risc0_binfmt::Digestible;
+use risc0_core::field::baby_bear::BabyBear;
+use risc0_zkp::{
+    core::{
+        digest::Digest,
+        hash::{hash_suite_from_name, HashSuite},
+    },
+    verify::VerificationError,
+};
+use serde::Serialize;
+
+use super::{
+    merkle::MerkleProof, SuccinctReceipt, SuccinctReceiptVerifierParameters, VerifierContext,
+};
+
+/// Verifies the integrity of many [SuccinctReceipt]s in parallel.
+///
+/// All receipts are verified against the same [SuccinctReceiptVerifierParameters], which are
+/// checked once when the verifier is constructed. Hash suites are constructed once per worker
+/// thread, and control inclusion proofs that have already been verified are not checked again,
+/// so verifying a batch of receipts produced by the same recursion programs is cheaper than
+/// calling [SuccinctReceipt::verify_integrity_with_context] on each of them.
+pub struct SuccinctBatchVerifier {
+    params: SuccinctReceiptVerifierParameters,
+    suites: BTreeSet<String>,
+    verified_controls: RwLock<BTreeMap<(String, Digest), MerkleProof>>,
+}
+
+impl SuccinctBatchVerifier {
+    /// Construct a [SuccinctBatchVerifier] from the succinct verifier parameters and hash suites
+    /// of the given [VerifierContext].
+    ///
+    /// Hash suites are shared between threads by name, so every suite in the context must be one
+    /// of the suites known to [hash_suite_from_name].
+    pub fn new(ctx: &VerifierContext) -> Result<Self, VerificationError> {
+        let params = ctx
+            .succinct_verifier_parameters
+            .clone()
+            .ok_or(VerificationError::VerifierParametersMissing)?;
+        params.check_supported()?;
+
+        let suites = ctx.suites.keys().cloned().collect::<BTreeSet<_>>();
+        if suites
+            .iter()
+            .any(|name| hash_suite_from_name(name).is_none())
+        {
+            return Err(VerificationError::InvalidHashSuite);
+        }
+
+        Ok(Self {
+            params,
+            suites,
+            verified_controls: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    /// Verify the integrity of the given receipts in parallel.
+    ///
+    /// Returns an error listing the index and error of every receipt that failed verification.
+    pub fn verify_integrity<Claim>(
+        &self,
+        receipts: &[SuccinctReceipt<Claim>],
+    ) -> Result<(), BatchVerificationError>
+    where
+        Claim: Digestible + fmt::Debug + Clone + Serialize + Sync,
+    {
+        let failures: Vec<(usize, VerificationError)> = receipts
+            .par_iter()
+            .enumerate()
+            .map_init(
+                || self.hash_suites(),
+                |suites, (index, receipt)| {
+                    self.verify_receipt(suites, receipt)
+                        .err()
+                        .map(|err| (index, err))
+                },
+            )
+            .flatten()
+            .collect();
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(BatchVerificationError { failures })
+    }
+
+    fn hash_suites(&self) -> BTreeMap<String, HashSuite<BabyBear>> {
+        self.suites
+            .iter()
+            .filter_map(|name| Some((name.clone(), hash_suite_from_name(name)?)))
+            .collect()
+    }
+
+    fn verify_receipt<Claim>(
+        &self,
+        suites: &BTreeMap<String, HashSuite<BabyBear>>,
+        receipt: &SuccinctReceipt<Claim>,
+    ) -> Result<(), VerificationError>
+    where
+        Claim: Digestible + fmt::Debug + Clone + Serialize,
+    {
+        let suite = suites
+            .get(&receipt.hashfn)
+            .ok_or(VerificationError::InvalidHashSuite)?;
+
+        receipt.verify_seal(&self.params, suite, |control_id| {
+            let key = (receipt.hashfn.clone(), *control_id);
+            let verified = self.verified_controls.read().unwrap().get(&key)
+                == Some(&receipt.control_inclusion_proof);
+            if !verified {
+                receipt.verify_control_inclusion(&self.params, suite, control_id)?;
+                self.verified_controls
+                    .write()
+                    .unwrap()
+                    .insert(key, receipt.control_inclusion_proof.clone());
+            }
+            Ok(())
+        })
+    }
+}
+
+/// The error returned when one or more receipts in a batch fail verification.
+#[derive(Debug)]
+pub struct BatchVerificationError {
+    /// The index of each receipt that failed verification, along with its error, in order.
+    pub failures: Vec<(usize, VerificationError)>,
+}
+
+impl fmt::Display for BatchVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} receipt(s) failed verification:", self.failures.len())?;
+        for (index, err) in self.failures.iter() {
+            write!(f, "\n  receipt {index}: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BatchVerificationError {}
@@ -19,7 +19,7 @@ use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
 use risc0_binfmt::{tagged_struct, Digestible};
 use risc0_circuit_recursion::control_id::{ALLOWED_CONTROL_ROOT, BN254_IDENTITY_CONTROL_ID};
-use risc0_groth16::{fr_from_hex_string, split_digest, Seal, Verifier, VerifyingKey};
+use risc0_groth16::{fr_from_hex_string, split_digest, Seal, VerifyingKey};
 use risc0_zkp::core::hash::sha::Sha256;
 use risc0_zkp::{core::digest::Digest, verify::VerificationError};
 use serde::{Deserialize, Serialize};
@@ -75,6 +75,9 @@ where
 
     /// Verify the integrity of this receipt, ensuring the claim is attested
     /// to by the seal.
+    ///
+    /// This can be called in the guest to verify a receipt received as input, in which case the
+    /// BN254 arithmetic is accelerated with the bigint2 circuit.
     pub fn verify_integrity_with_context(
         &self,
         ctx: &VerifierContext,
@@ -92,14 +95,11 @@ where
         id_bn554.as_mut_bytes().reverse();
         let id_bn254_fr = fr_from_hex_string(&hex::encode(id_bn554))
             .map_err(|_| VerificationError::ReceiptFormatError)?;
-        Verifier::new(
-            &Seal::from_vec(&self.seal).map_err(|_| VerificationError::ReceiptFormatError)?,
-            &[a0, a1, c0, c1, id_bn254_fr],
-            &params.verifying_key,
-        )
-        .map_err(|_| VerificationError::ReceiptFormatError)?
-        .verify()
-        .map_err(|_| VerificationError::InvalidProof)?;
+        let seal = Seal::from_vec(&self.seal).map_err(|_| VerificationError::ReceiptFormatError)?;
+        let public_inputs = [a0, a1, c0, c1, id_bn254_fr];
+
+        risc0_groth16::verify(&seal, &public_inputs, &params.verifying_key)
+            .map_err(|_| VerificationError::InvalidProof)?;
 
         // Everything passed
         Ok(())
@@ -28,12 +28,12 @@ use risc0_circuit_recursion::{
     control_id::{ALLOWED_CONTROL_ROOT, MIN_LIFT_PO2, POSEIDON2_CONTROL_IDS, SHA256_CONTROL_IDS},
     CircuitImpl, CIRCUIT,
 };
-use risc0_core::field::baby_bear::BabyBearElem;
+use risc0_core::field::baby_bear::{BabyBear, BabyBearElem};
 use risc0_zkp::{
     adapter::{CircuitInfo, ProtocolInfo, PROOF_SYSTEM_INFO},
     core::{
         digest::Digest,
-        hash::{hash_suite_from_name, sha::Sha256},
+        hash::{hash_suite_from_name, sha::Sha256, HashSuite},
     },
     verify::VerificationError,
 };
@@ -110,46 +110,52 @@ where
             .succinct_verifier_parameters
             .as_ref()
             .ok_or(VerificationError::VerifierParametersMissing)?;
-
-        // Check that the proof system and circuit info strings match what is implemented by this
-        // function. Info strings are used a version identifiers, and this verify implementation
-        // supports exactly one proof systema and circuit version at a time.
-        if params.proof_system_info != PROOF_SYSTEM_INFO {
-            return Err(VerificationError::ProofSystemInfoMismatch {
-                expected: PROOF_SYSTEM_INFO,
-                received: params.proof_system_info,
-            });
-        }
-        if params.circuit_info != CircuitImpl::CIRCUIT_INFO {
-            return Err(VerificationError::CircuitInfoMismatch {
-                expected: CircuitImpl::CIRCUIT_INFO,
-                received: params.circuit_info,
-            });
-        }
+        params.check_supported()?;
 
         let suite = ctx
             .suites
             .get(&self.hashfn)
             .ok_or(VerificationError::InvalidHashSuite)?;
 
-        let check_code = |_, control_id: &Digest| -> Result<(), VerificationError> {
-            self.control_inclusion_proof
-                .verify(control_id, &params.control_root, suite.hashfn.as_ref())
-                .map_err(|_| {
-                    tracing::debug!(
-                        "failed to verify control inclusion proof for {control_id} against root {} with {}",
-                        params.control_root,
-                        suite.name,
-                    );
-                    VerificationError::ControlVerificationError {
-                        control_id: *control_id,
-                    }
-                })
-        };
+        self.verify_seal(params, suite, |control_id| {
+            self.verify_control_inclusion(params, suite, control_id)
+        })
+    }
 
+    /// Verify that the control ID used to produce this receipt is included in the control root.
+    pub(crate) fn verify_control_inclusion(
+        &self,
+        params: &SuccinctReceiptVerifierParameters,
+        suite: &HashSuite<BabyBear>,
+        control_id: &Digest,
+    ) -> Result<(), VerificationError> {
+        self.control_inclusion_proof
+            .verify(control_id, &params.control_root, suite.hashfn.as_ref())
+            .map_err(|_| {
+                tracing::debug!(
+                    "failed to verify control inclusion proof for {control_id} against root {} with {}",
+                    params.control_root,
+                    suite.name,
+                );
+                VerificationError::ControlVerificationError {
+                    control_id: *control_id,
+                }
+            })
+    }
+
+    /// Verify the seal with the given hash suite, checking that it attests to the claim, and using
+    /// `check_control` to check the control ID of the recursion program.
+    pub(crate) fn verify_seal(
+        &self,
+        params: &SuccinctReceiptVerifierParameters,
+        suite: &HashSuite<BabyBear>,
+        check_control: impl Fn(&Digest) -> Result<(), VerificationError>,
+    ) -> Result<(), VerificationError> {
         // Verify the receipt itself is correct, and therefore the encoded globals are
         // reliable.
-        risc0_zkp::verify::verify(&CIRCUIT, suite, &self.seal, check_code)?;
+        risc0_zkp::verify::verify(&CIRCUIT, suite, &self.seal, |_, control_id| {
+            check_control(control_id)
+        })?;
 
         // Extract the globals from the seal
         let output_elems: &[BabyBearElem] =
@@ -315,6 +321,27 @@ impl SuccinctReceiptVerifierParameters {
     }
 }
 
+impl SuccinctReceiptVerifierParameters {
+    /// Check that the proof system and circuit info strings match what is implemented by this
+    /// crate. Info strings are used as version identifiers, and this verify implementation supports
+    /// exactly one proof system and circuit version at a time.
+    pub(crate) fn check_supported(&self) -> Result<(), VerificationError> {
+        if self.proof_system_info != PROOF_SYSTEM_INFO {
+            return Err(VerificationError::ProofSystemInfoMismatch {
+                expected: PROOF_SYSTEM_INFO,
+                received: self.proof_system_info,
+            });
+        }
+        if self.circuit_info != CircuitImpl::CIRCUIT_INFO {
+            return Err(VerificationError::CircuitInfoMismatch {
+                expected: CircuitImpl::CIRCUIT_INFO,
+                received: self.circuit_info,
+            });
+        }
+        Ok(())
+    }
+}
+
 impl Digestible for SuccinctReceiptVerifierParameters {
     /// Hash the [SuccinctReceiptVerifierParameters] to get a digest of the struct.
     fn digest<S: Sha256>(&self) -> Digest {
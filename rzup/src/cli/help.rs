@@ -38,7 +38,22 @@ pub static INSTALL_HELP: &str = r"Discussion:
     installs the default RISC Zero installation from the release channels.
 
     If given a toolchain or extension argument then `install` installs the
-    specified toolchain or extension.";
+    specified toolchain or extension.
+
+    If the current directory or one of its parents contains a
+    `risc0-toolchain.toml`, the versions pinned there are installed and
+    activated unless a version is given explicitly.
+
+    Downloads are verified against the SHA-256 manifest published with the
+    release, or the manifest given with `--manifest`, before they are
+    installed. Releases without a manifest are only installed with
+    `--allow-unverified`.
+
+    To install without network access, pass a release tarball or an unpacked
+    toolchain or extension directory with `--from`, along with its name and
+    version. A tarball is verified against the manifest given with
+    `--manifest`, or a `sha256sums.txt` next to it. A directory cannot be
+    verified, so it is only installed with `--allow-unverified`.";
 
 pub static DEFAULT_HELP: &str = r"Discussion:
     Sets the default (active) toolchain to the specified toolchain.
@@ -12,10 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
 use clap::Args;
 
-use crate::{extension::Extension, toolchain::Toolchain};
+use risc0_build::TOOLCHAIN_FILE;
+
+use crate::{
+    extension::Extension,
+    toolchain::Toolchain,
+    utils::{checksum::Manifest, find_pinned_toolchain},
+};
 
 #[derive(Debug, Args)]
 pub struct InstallOpts {
@@ -23,21 +31,84 @@ pub struct InstallOpts {
     pub version: Option<String>,
     #[arg(short, long, help = "Force the update, ignoring existing directories")]
     pub force: bool,
+    #[arg(
+        long,
+        help = "Install from a local release tarball or unpacked directory"
+    )]
+    pub from: Option<PathBuf>,
+    #[arg(long, help = "Verify downloads against this SHA-256 manifest")]
+    pub manifest: Option<PathBuf>,
 }
 
 pub async fn handler(opts: InstallOpts) -> Result<()> {
-    if opts.name.is_none() {
+    let manifest = opts.manifest.as_deref().map(Manifest::load).transpose()?;
+    let manifest = manifest.as_ref();
+    // Versions pinned by the project are used unless a version is given explicitly.
+    let pinned = find_pinned_toolchain()?;
+
+    if let Some(from) = opts.from {
+        let Some(name) = opts.name else {
+            bail!(
+                "`--from` requires the name of the toolchain or extension, \
+                e.g. `rzup install rust v1.81.0 --from rust-toolchain.tar.gz`"
+            );
+        };
+        if !from.exists() {
+            bail!("{} does not exist", from.display());
+        }
+        if from.is_dir() && manifest.is_some() {
+            bail!("`--manifest` can only be used to verify a tarball, not a directory");
+        }
+        let missing_version = || {
+            anyhow!(
+                "`--from` requires the version of {name}, either as an argument or pinned in \
+                {TOOLCHAIN_FILE}"
+            )
+        };
+        if let Ok(toolchain) = name.parse::<Toolchain>() {
+            let version = opts
+                .version
+                .as_deref()
+                .or(toolchain.pinned(&pinned))
+                .ok_or_else(missing_version)?;
+            toolchain.install_from(&from, version, opts.force, manifest)?
+        } else if let Ok(extension) = name.parse::<Extension>() {
+            let version = opts
+                .version
+                .as_deref()
+                .or(extension.pinned(&pinned))
+                .ok_or_else(missing_version)?;
+            extension.install_from(&from, version, opts.force, manifest)?
+        } else {
+            return Err(anyhow!(
+                "invalid value '{}' for '<install>...' \n\nFor more information try '--help'.",
+                name
+            ));
+        }
+    } else if opts.name.is_none() {
         // Install all default
-        Toolchain::Rust.install(None, opts.force).await?;
-        Toolchain::Cpp.install(None, opts.force).await?;
-        Extension::CargoRiscZero.install(None, opts.force).await?;
+        for toolchain in [Toolchain::Rust, Toolchain::Cpp] {
+            toolchain
+                .install_with_manifest(toolchain.pinned(&pinned), opts.force, manifest)
+                .await?;
+        }
+        let extension = Extension::CargoRiscZero;
+        extension
+            .install_with_manifest(extension.pinned(&pinned), opts.force, manifest)
+            .await?;
     } else {
         let name = opts.name.unwrap();
         let version = opts.version.as_deref();
         if let Ok(toolchain) = name.parse::<Toolchain>() {
-            toolchain.install(version, opts.force).await?
+            let version = version.or(toolchain.pinned(&pinned));
+            toolchain
+                .install_with_manifest(version, opts.force, manifest)
+                .await?
         } else if let Ok(extension) = name.parse::<Extension>() {
-            extension.install(version, opts.force).await?
+            let version = version.or(extension.pinned(&pinned));
+            extension
+                .install_with_manifest(version, opts.force, manifest)
+                .await?
         } else {
             return Err(anyhow!(
                 "invalid value '{}' for '<install>...' \n\nFor more information try '--help'.",
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::process::ExitStatus;
+use std::{fmt, process::ExitStatus};
 use thiserror::Error;
 
 /// Custom error type for the rzup CLI tool.
@@ -47,6 +47,54 @@ pub enum RzupError {
     NetworkTimeoutError,
     #[error("Configuration error: {0}")]
     ConfigError(String),
+    #[error(
+        "Checksum mismatch for {name}: expected SHA-256 {expected}, got {actual}.\n\
+        The file may be corrupted or tampered with; it was not installed."
+    )]
+    ChecksumMismatch {
+        name: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("No SHA-256 checksum for {0} in the manifest")]
+    ChecksumMissing(String),
+    #[error("{name} was not installed: {reason}")]
+    Unverified { name: String, reason: Unverifiable },
+}
+
+/// Why an artifact cannot be verified against a SHA-256 manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unverifiable {
+    /// The release does not publish a manifest.
+    Release,
+    /// No manifest was given, and there is none next to the local artifact.
+    LocalArtifact,
+    /// An unpacked directory has no single file to check a digest against.
+    Directory,
+}
+
+impl fmt::Display for Unverifiable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unverifiable::Release => write!(
+                f,
+                "its release does not publish a sha256sums.txt to verify it against.\n\
+                Pass --manifest <file> to verify it against a manifest you trust, or \
+                --allow-unverified to install it unverified."
+            ),
+            Unverifiable::LocalArtifact => write!(
+                f,
+                "there is no sha256sums.txt next to it to verify it against.\n\
+                Pass --manifest <file> to verify it against a manifest you trust, or \
+                --allow-unverified to install it unverified."
+            ),
+            Unverifiable::Directory => write!(
+                f,
+                "a directory cannot be verified against a SHA-256 manifest.\n\
+                Pass --allow-unverified to install it unverified."
+            ),
+        }
+    }
 }
 
 impl RzupError {
@@ -22,14 +22,20 @@ use std::{
 
 use anyhow::{anyhow, Context, Result};
 use flate2::bufread::GzDecoder;
+use risc0_build::PinnedToolchain;
 use tar::Archive;
 use tempfile::tempdir;
 
 use crate::{
-    errors::RzupError,
+    errors::{RzupError, Unverifiable},
     info_msg,
     repo::GithubReleaseInfo,
-    utils::{flock, http_client, rzup_home, target::Target},
+    utils::{
+        allow_unverified, api_base_url,
+        checksum::{check_unverified, verify_artifact, Manifest},
+        copy_dir, flock, http_client, prepare_install_dir, rzup_home,
+        target::Target,
+    },
     verbose_msg,
 };
 
@@ -57,10 +63,18 @@ impl Extension {
         }
     }
 
+    /// Returns the version of this extension pinned by a project, if any.
+    pub fn pinned<'a>(&self, pinned: &'a PinnedToolchain) -> Option<&'a str> {
+        match self {
+            Extension::CargoRiscZero => pinned.cargo_risczero.as_deref(),
+        }
+    }
+
     fn api_url(&self, tag: Option<&str>) -> String {
-        let base_url = match self {
-            Extension::CargoRiscZero => "https://api.github.com/repos/risc0/risc0/releases",
+        let repo = match self {
+            Extension::CargoRiscZero => "risc0",
         };
+        let base_url = format!("{}/repos/risc0/{}/releases", api_base_url(), repo);
         match tag {
             Some(tag) => format!("{}/tags/{}", base_url, tag),
             None => format!("{}/latest", base_url),
@@ -94,6 +108,7 @@ impl Extension {
         tag: Option<&str>,
         extensions_root_dir: &Path,
         force: bool,
+        manifest: Option<&Manifest>,
     ) -> Result<PathBuf> {
         let client = http_client()?;
 
@@ -110,27 +125,8 @@ impl Extension {
             );
         };
 
-        let extension_dir =
-            extensions_root_dir.join(format!("{}-{}", release_info.tag_name, self.to_str()));
-
-        // Remove directory if it exists and force is set
-        if extension_dir.is_dir() && force {
-            info_msg!(format!(
-                "Extension path {} already exists - deleting existing files!",
-                extension_dir.display()
-            ));
-
-            verbose_msg!(format!("deleting {}", extension_dir.display()));
-
-            fs::remove_dir_all(&extension_dir)?;
-        }
-
-        // Skip download if directory already exists and force is not set
-        if extension_dir.is_dir() && !force {
-            info_msg!(format!(
-                "Extension path {} already exists - skipping download.",
-                extension_dir.display()
-            ));
+        let extension_dir = extensions_root_dir.join(self.dir_name(&release_info.tag_name));
+        if prepare_install_dir(&extension_dir, "Extension", force)? {
             return Ok(extension_dir);
         }
 
@@ -164,7 +160,36 @@ impl Extension {
 
         file.write_all(&content)?;
 
-        let tarball = fs::File::open(temp_file_path)?;
+        // Prefer the manifest given by the user over the one published with the release.
+        let release_manifest;
+        let manifest = match manifest {
+            Some(manifest) => Some(manifest),
+            None => {
+                release_manifest = Manifest::for_release(&release_info).await?;
+                release_manifest.as_ref()
+            }
+        };
+        verify_artifact(
+            manifest,
+            &asset.name,
+            &temp_file_path,
+            Unverifiable::Release,
+            allow_unverified(),
+        )?;
+
+        self.unpack(&temp_file_path, &extension_dir)?;
+
+        Ok(extension_dir)
+    }
+
+    /// The name of the directory an extension is installed to in the rzup home directory.
+    fn dir_name(&self, tag: &str) -> String {
+        format!("{}-{}", tag, self.to_str())
+    }
+
+    /// Unpacks a release tarball of this extension into `extension_dir`.
+    fn unpack(&self, tarball_path: &Path, extension_dir: &Path) -> Result<()> {
+        let tarball = fs::File::open(tarball_path)?;
 
         match self {
             Extension::CargoRiscZero => {
@@ -179,7 +204,7 @@ impl Extension {
                     &extension_dir.display()
                 ));
 
-                archive.unpack(&extension_dir)?;
+                archive.unpack(extension_dir)?;
                 let binary_path = extension_dir.join("cargo-risczero");
 
                 verbose_msg!("Setting extension permissons to 0o755");
@@ -190,7 +215,7 @@ impl Extension {
             }
         }
 
-        Ok(extension_dir)
+        Ok(())
     }
 
     pub fn link(&self, dir: &Path) -> Result<()> {
@@ -261,6 +286,17 @@ impl Extension {
     }
 
     pub async fn install(&self, tag: Option<&str>, force: bool) -> Result<()> {
+        self.install_with_manifest(tag, force, None).await
+    }
+
+    /// Downloads and installs the extension, verifying the download against the given SHA-256
+    /// manifest, or else the manifest published with the release.
+    pub async fn install_with_manifest(
+        &self,
+        tag: Option<&str>,
+        force: bool,
+        manifest: Option<&Manifest>,
+    ) -> Result<()> {
         let target = Target::host_target()
             .ok_or_else(|| RzupError::Other("Failed to determine the host target".to_string()))?;
 
@@ -274,11 +310,58 @@ impl Extension {
         match self {
             Extension::CargoRiscZero => {
                 let cargo_risczero_path = self
-                    .download(target, tag, &extensions_root_dir, force)
+                    .download(target, tag, &extensions_root_dir, force, manifest)
                     .await?;
                 self.link(&cargo_risczero_path)?;
             }
         }
         Ok(())
     }
+
+    /// Installs the extension without network access, from a release tarball or a directory
+    /// containing an unpacked extension.
+    ///
+    /// A tarball is verified against the given SHA-256 manifest, or else a manifest next to it.
+    /// A directory cannot be verified, so it is only installed if unverified artifacts are allowed.
+    pub fn install_from(
+        &self,
+        path: &Path,
+        tag: &str,
+        force: bool,
+        manifest: Option<&Manifest>,
+    ) -> Result<()> {
+        let root_dir = rzup_home()?;
+
+        let lockfile_path = root_dir.join("ext-lock");
+        let _lock = flock(&lockfile_path)?;
+
+        let extension_dir = root_dir.join("extensions").join(self.dir_name(tag));
+        if !prepare_install_dir(&extension_dir, "Extension", force)? {
+            if path.is_dir() {
+                check_unverified(
+                    &path.display().to_string(),
+                    Unverifiable::Directory,
+                    allow_unverified(),
+                )?;
+                info_msg!(format!(
+                    "Copying {} extension from {}...",
+                    self.to_str(),
+                    path.display()
+                ));
+                copy_dir(path, &extension_dir)?;
+            } else {
+                let (name, manifest) = Manifest::for_local_artifact(path, manifest)?;
+                verify_artifact(
+                    manifest.as_ref(),
+                    &name,
+                    path,
+                    Unverifiable::LocalArtifact,
+                    allow_unverified(),
+                )?;
+                self.unpack(path, &extension_dir)?;
+            }
+        }
+
+        self.link(&extension_dir)
+    }
 }
@@ -18,7 +18,10 @@ use clap::{CommandFactory, Parser, Subcommand};
 
 use rzup::{cli, extension, toolchain, utils};
 
-use std::fs::{self, OpenOptions};
+use std::{
+    fs::{self, OpenOptions},
+    path::PathBuf,
+};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -47,6 +50,15 @@ struct Rzup {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Base URL of a mirror of the GitHub releases API to fetch releases from [env: RZUP_MIRROR]
+    #[arg(long, global = true)]
+    mirror: Option<String>,
+
+    /// Install artifacts that cannot be verified against a SHA-256 manifest, such as releases
+    /// that don't publish one or unpacked directories passed to `install --from`
+    #[arg(long, global = true)]
+    allow_unverified: bool,
+
     #[command(subcommand)]
     subcmd: Option<RzupSubcmd>,
 }
@@ -65,11 +77,18 @@ enum RzupSubcmd {
     Install {
         /// Name of the toolchain or extension to install
         name: Option<String>,
-        /// Version tag of the toolchain or extension to install
+        /// Version tag of the toolchain or extension to install, defaulting to the version pinned
+        /// in risc0-toolchain.toml or else the latest release
         version: Option<String>,
         /// Force the installation, ignoring existing installations and downloads
         #[arg(short, long)]
         force: bool,
+        /// Install from a local release tarball or unpacked directory instead of downloading
+        #[arg(long, value_name = "PATH", requires = "name")]
+        from: Option<PathBuf>,
+        /// SHA-256 manifest (as written by `sha256sum`) to verify the installed artifacts against
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<PathBuf>,
     },
     /// Update toolchains or extensions
     #[command(after_help = cli::help::UPDATE_HELP, aliases = ["upgrade", "up"])]
@@ -141,6 +160,8 @@ async fn run() -> Result<()> {
 
     // Set verbosity flag based on the CLI argument
     utils::set_verbose(matches.verbose);
+    utils::set_mirror(matches.mirror);
+    utils::set_allow_unverified(matches.allow_unverified);
 
     let Some(subcmd) = matches.subcmd else {
         eprintln!("{}", Rzup::command().render_long_help());
@@ -153,11 +174,15 @@ async fn run() -> Result<()> {
             name,
             version,
             force,
+            from,
+            manifest,
         } => {
             cli::install::handler(cli::install::InstallOpts {
                 name,
                 version,
                 force,
+                from,
+                manifest,
             })
             .await
         }
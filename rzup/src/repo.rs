@@ -18,8 +18,12 @@ use serde::Deserialize;
 
 use crate::utils::target::Target;
 
+/// File names of the SHA-256 manifest of a release, as written by `sha256sum`.
+pub const MANIFEST_NAMES: &[&str] = &["sha256sums.txt", "SHA256SUMS"];
+
 pub struct GithubReleaseInfo {
     pub assets: HashMap<Target, GithubAsset>,
+    pub manifest: Option<GithubAsset>,
     pub tag_name: String,
     pub published_at: String,
 }
@@ -27,18 +31,23 @@ pub struct GithubReleaseInfo {
 #[derive(Deserialize)]
 pub struct GithubAsset {
     pub browser_download_url: String,
-    name: String,
+    pub name: String,
 }
 
 impl GithubReleaseInfo {
-    fn parse_assets(assets: Vec<GithubAsset>) -> HashMap<Target, GithubAsset> {
+    fn parse_assets(
+        assets: Vec<GithubAsset>,
+    ) -> (HashMap<Target, GithubAsset>, Option<GithubAsset>) {
         let mut asset_map = HashMap::new();
+        let mut manifest = None;
         for asset in assets {
-            if let Some(target) = Self::extract_target_from_name(&asset.name) {
+            if MANIFEST_NAMES.contains(&asset.name.as_str()) {
+                manifest = Some(asset);
+            } else if let Some(target) = Self::extract_target_from_name(&asset.name) {
                 asset_map.insert(target, asset);
             }
         }
-        asset_map
+        (asset_map, manifest)
     }
 
     fn extract_target_from_name(name: &str) -> Option<Target> {
@@ -65,10 +74,11 @@ impl<'de> Deserialize<'de> for GithubReleaseInfo {
         }
 
         let release = Release::deserialize(deserializer)?;
-        let assets = GithubReleaseInfo::parse_assets(release.assets);
+        let (assets, manifest) = GithubReleaseInfo::parse_assets(release.assets);
 
         Ok(GithubReleaseInfo {
             assets,
+            manifest,
             tag_name: release.tag_name,
             published_at: release.published_at,
         })
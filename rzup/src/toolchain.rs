@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use crate::{
-    errors::RzupError,
+    errors::{RzupError, Unverifiable},
     info_msg,
     repo::GithubReleaseInfo,
     utils::{
-        command::CommandExt, ensure_binary, flock, http_client, rzup_home, target::Target,
+        allow_unverified, api_base_url,
+        checksum::{check_unverified, verify_artifact, Manifest},
+        command::CommandExt,
+        copy_dir, ensure_binary, flock, http_client, prepare_install_dir, rzup_home,
+        target::Target,
         CPP_TOOLCHAIN_NAME, RUSTUP_TOOLCHAIN_NAME,
     },
     verbose_msg,
@@ -25,6 +29,7 @@ use crate::{
 use anyhow::{bail, Context, Result};
 use downloader::{Download, Downloader};
 use flate2::bufread::GzDecoder;
+use risc0_build::PinnedToolchain;
 use std::{
     fs,
     io::BufReader,
@@ -71,6 +76,14 @@ impl Toolchain {
         }
     }
 
+    /// Returns the version of this toolchain pinned by a project, if any.
+    pub fn pinned<'a>(&self, pinned: &'a PinnedToolchain) -> Option<&'a str> {
+        match self {
+            Toolchain::Rust => pinned.rust.as_deref(),
+            Toolchain::Cpp => pinned.cpp.as_deref(),
+        }
+    }
+
     fn git_url(&self) -> &'static str {
         match self {
             Self::Rust => "https://github.com/risc0/rust.git",
@@ -79,10 +92,11 @@ impl Toolchain {
     }
 
     fn api_url(&self, tag: Option<&str>) -> String {
-        let base_url = match self {
-            Self::Rust => "https://api.github.com/repos/risc0/rust/releases",
-            Self::Cpp => "https://api.github.com/repos/risc0/toolchain/releases",
+        let repo = match self {
+            Self::Rust => "rust",
+            Self::Cpp => "toolchain",
         };
+        let base_url = format!("{}/repos/risc0/{}/releases", api_base_url(), repo);
         match tag {
             Some(tag) => format!("{}/tags/{}", base_url, tag),
             None => format!("{}/latest", base_url),
@@ -116,6 +130,7 @@ impl Toolchain {
         tag: Option<&str>,
         toolchain_root_dir: &Path,
         force: bool,
+        manifest: Option<&Manifest>,
     ) -> Result<PathBuf> {
         let temp_dir = tempdir()?;
         let temp_file_path = match self {
@@ -135,34 +150,8 @@ impl Toolchain {
             );
         };
 
-        let toolchain_dir = toolchain_root_dir.join(format!(
-            "{}-risc0-{}-{}",
-            release_info.tag_name,
-            self.to_str(),
-            target.to_str(),
-        ));
-
-        // Remove directory if it exists and force is set
-        if toolchain_dir.is_dir() && force {
-            info_msg!(format!(
-                "Toolchain path {} already exists - deleting existing files!",
-                toolchain_dir.display()
-            ));
-
-            verbose_msg!(format!(
-                "Removing toolchain directory at {}",
-                toolchain_dir.display()
-            ));
-
-            fs::remove_dir_all(&toolchain_dir)?;
-        }
-
-        // Skip download if directory already exists and force is not set
-        if toolchain_dir.is_dir() && !force {
-            info_msg!(format!(
-                "Toolchain path {} already exists - skipping download.",
-                toolchain_dir.display()
-            ));
+        let toolchain_dir = toolchain_root_dir.join(self.dir_name(&release_info.tag_name, &target));
+        if prepare_install_dir(&toolchain_dir, "Toolchain", force)? {
             return Ok(toolchain_dir);
         }
 
@@ -195,7 +184,36 @@ impl Toolchain {
         })
         .await??;
 
-        let tarball = fs::File::open(&temp_file_path)?;
+        // Prefer the manifest given by the user over the one published with the release.
+        let release_manifest;
+        let manifest = match manifest {
+            Some(manifest) => Some(manifest),
+            None => {
+                release_manifest = Manifest::for_release(&release_info).await?;
+                release_manifest.as_ref()
+            }
+        };
+        verify_artifact(
+            manifest,
+            &asset.name,
+            &temp_file_path,
+            Unverifiable::Release,
+            allow_unverified(),
+        )?;
+
+        self.unpack(&target, &temp_file_path, &toolchain_dir)?;
+
+        Ok(toolchain_dir)
+    }
+
+    /// The name of the directory a toolchain is installed to in the rzup home directory.
+    fn dir_name(&self, tag: &str, target: &Target) -> String {
+        format!("{}-risc0-{}-{}", tag, self.to_str(), target.to_str())
+    }
+
+    /// Unpacks a release tarball of this toolchain into `toolchain_dir`.
+    fn unpack(&self, target: &Target, tarball_path: &Path, toolchain_dir: &Path) -> Result<()> {
+        let tarball = fs::File::open(tarball_path)?;
         info_msg!(format!("Extracting {} toolchain...", self.to_str()));
 
         match self {
@@ -209,7 +227,7 @@ impl Toolchain {
                     &toolchain_dir.display()
                 ));
 
-                archive.unpack(&toolchain_dir)?;
+                archive.unpack(toolchain_dir)?;
 
                 #[cfg(target_family = "unix")]
                 {
@@ -260,11 +278,11 @@ impl Toolchain {
                 ));
 
                 // Move subdir contents to toolchain dir
-                Toolchain::move_toolchain(&subdir, &toolchain_dir)?;
+                Toolchain::move_toolchain(&subdir, toolchain_dir)?;
             }
         }
 
-        Ok(toolchain_dir)
+        Ok(())
     }
 
     pub fn link(&self, dir: &Path) -> Result<()> {
@@ -360,6 +378,17 @@ impl Toolchain {
     }
 
     pub async fn install(&self, tag: Option<&str>, force: bool) -> Result<()> {
+        self.install_with_manifest(tag, force, None).await
+    }
+
+    /// Downloads and installs the toolchain, verifying the download against the given SHA-256
+    /// manifest, or else the manifest published with the release.
+    pub async fn install_with_manifest(
+        &self,
+        tag: Option<&str>,
+        force: bool,
+        manifest: Option<&Manifest>,
+    ) -> Result<()> {
         let target = Target::host_target()
             .ok_or_else(|| RzupError::Other("Failed to determine the host target".to_string()))?;
         let root_dir = rzup_home()?;
@@ -371,13 +400,13 @@ impl Toolchain {
         match self {
             Toolchain::Rust => {
                 let rust_path = self
-                    .download(target, tag, &toolchains_root_dir, force)
+                    .download(target, tag, &toolchains_root_dir, force, manifest)
                     .await?;
                 self.link(&rust_path)?;
             }
             Toolchain::Cpp => {
                 let cpp_path = self
-                    .download(target, tag, &toolchains_root_dir, force)
+                    .download(target, tag, &toolchains_root_dir, force, manifest)
                     .await?;
                 self.link(&cpp_path)?;
             }
@@ -385,6 +414,67 @@ impl Toolchain {
         Ok(())
     }
 
+    /// Installs the toolchain without network access, from a release tarball or a directory
+    /// containing an unpacked toolchain.
+    ///
+    /// A tarball is verified against the given SHA-256 manifest, or else a manifest next to it.
+    /// A directory cannot be verified, so it is only installed if unverified artifacts are allowed.
+    pub fn install_from(
+        &self,
+        path: &Path,
+        tag: &str,
+        force: bool,
+        manifest: Option<&Manifest>,
+    ) -> Result<()> {
+        self.install_from_with(path, tag, force, manifest, allow_unverified())
+    }
+
+    fn install_from_with(
+        &self,
+        path: &Path,
+        tag: &str,
+        force: bool,
+        manifest: Option<&Manifest>,
+        allow_unverified: bool,
+    ) -> Result<()> {
+        let target = Target::host_target()
+            .ok_or_else(|| RzupError::Other("Failed to determine the host target".to_string()))?;
+        let root_dir = rzup_home()?;
+        let lockfile_path = root_dir.join("lock");
+        let _lock = flock(&lockfile_path)?;
+
+        let toolchain_dir = root_dir
+            .join("toolchains")
+            .join(self.dir_name(tag, &target));
+        if !prepare_install_dir(&toolchain_dir, "Toolchain", force)? {
+            if path.is_dir() {
+                check_unverified(
+                    &path.display().to_string(),
+                    Unverifiable::Directory,
+                    allow_unverified,
+                )?;
+                info_msg!(format!(
+                    "Copying {} toolchain from {}...",
+                    self.to_str(),
+                    path.display()
+                ));
+                copy_dir(path, &toolchain_dir)?;
+            } else {
+                let (name, manifest) = Manifest::for_local_artifact(path, manifest)?;
+                verify_artifact(
+                    manifest.as_ref(),
+                    &name,
+                    path,
+                    Unverifiable::LocalArtifact,
+                    allow_unverified,
+                )?;
+                self.unpack(&target, path, &toolchain_dir)?;
+            }
+        }
+
+        self.link(&toolchain_dir)
+    }
+
     pub fn build(&self, version: Option<&str>) -> Result<()> {
         match self {
             Toolchain::Rust => {
@@ -516,22 +606,7 @@ impl Toolchain {
     }
 
     fn move_toolchain(src: &Path, dst: &Path) -> Result<()> {
-        fs::create_dir_all(dst)?;
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let src_path = entry.path();
-            let dst_path = dst.join(entry.file_name());
-            if src_path.is_dir() {
-                // recursively copy dir
-                fs::create_dir_all(&dst_path)?;
-                Toolchain::move_toolchain(&src_path, &dst_path)?;
-            } else {
-                fs::copy(&src_path, &dst_path)?;
-                let metadata = src_path.metadata()?;
-                let permissions = metadata.permissions();
-                fs::set_permissions(&dst_path, permissions)?;
-            }
-        }
+        copy_dir(src, dst)?;
         // clean up
         fs::remove_dir_all(src)?;
 
@@ -573,3 +648,70 @@ impl Toolchain {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_unverified(err: &anyhow::Error) -> bool {
+        matches!(
+            err.downcast_ref::<RzupError>(),
+            Some(RzupError::Unverified { .. })
+        )
+    }
+
+    #[test]
+    fn install_from() {
+        let home = tempdir().unwrap();
+        temp_env::with_var("RISC0_HOME", Some(home.path()), || {
+            install_from_into(home.path())
+        });
+    }
+
+    fn install_from_into(home: &Path) {
+        let cpp_link = home.join(CPP_TOOLCHAIN_NAME);
+
+        let src_dir = tempdir().unwrap();
+        fs::write(src_dir.path().join("cc"), "cc").unwrap();
+
+        // A directory cannot be verified.
+        let err = Toolchain::Cpp
+            .install_from(src_dir.path(), "v1", false, None)
+            .unwrap_err();
+        assert!(is_unverified(&err), "{err}");
+        assert!(!cpp_link.exists());
+
+        Toolchain::Cpp
+            .install_from_with(src_dir.path(), "v1", false, None, true)
+            .unwrap();
+        assert_eq!(fs::read_to_string(cpp_link.join("cc")).unwrap(), "cc");
+
+        // A tarball is verified before it is unpacked.
+        let tarball_dir = tempdir().unwrap();
+        let tarball = tarball_dir.path().join("cpp.tar.xz");
+        fs::write(&tarball, "not a tarball").unwrap();
+        let err = Toolchain::Cpp
+            .install_from(&tarball, "v2", false, None)
+            .unwrap_err();
+        assert!(is_unverified(&err), "{err}");
+
+        fs::write(
+            tarball_dir.path().join("sha256sums.txt"),
+            format!("{}  cpp.tar.xz\n", "0".repeat(64)),
+        )
+        .unwrap();
+        let err = Toolchain::Cpp
+            .install_from(&tarball, "v2", false, None)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<RzupError>(),
+                Some(RzupError::ChecksumMismatch { .. })
+            ),
+            "{err}"
+        );
+
+        // The toolchain that was installed from the directory is still active.
+        assert_eq!(fs::read_to_string(cpp_link.join("cc")).unwrap(), "cc");
+    }
+}
@@ -18,16 +18,21 @@ use fs2::FileExt;
 use lazy_static::lazy_static;
 use regex::Regex;
 use reqwest::{header::HeaderMap, Client};
+use risc0_build::{PinnedToolchain, ToolchainFile};
 use std::{
     env,
     fs::{self, File, OpenOptions},
     path::{Path, PathBuf},
     process::Command,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
 };
 
-use crate::{errors::RzupError, extension::Extension, toolchain::Toolchain, verbose_msg};
+use crate::{errors::RzupError, extension::Extension, info_msg, toolchain::Toolchain, verbose_msg};
 
+pub mod checksum;
 pub mod command;
 pub mod notify;
 pub mod target;
@@ -35,6 +40,8 @@ pub mod target;
 // Global verbosity (set with -v flag)
 lazy_static! {
     static ref VERBOSE: AtomicBool = AtomicBool::new(false);
+    static ref ALLOW_UNVERIFIED: AtomicBool = AtomicBool::new(false);
+    static ref MIRROR: Mutex<Option<String>> = Mutex::new(None);
 }
 
 /// Set the verbosity flag
@@ -47,6 +54,45 @@ pub fn is_verbose() -> bool {
     VERBOSE.load(Ordering::SeqCst)
 }
 
+/// Set whether artifacts without a SHA-256 checksum may be installed (set with
+/// --allow-unverified)
+pub fn set_allow_unverified(allow: bool) {
+    ALLOW_UNVERIFIED.store(allow, Ordering::SeqCst);
+}
+
+/// Check if artifacts without a SHA-256 checksum may be installed
+pub fn allow_unverified() -> bool {
+    ALLOW_UNVERIFIED.load(Ordering::SeqCst)
+}
+
+/// The base URL of the GitHub API, used to find releases when no mirror is set.
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Set the mirror base URL (set with --mirror)
+pub fn set_mirror(mirror: Option<String>) {
+    *MIRROR.lock().unwrap() = mirror;
+}
+
+/// Determines the base URL to fetch release information from, which can be set with the
+/// `--mirror` flag or the `RZUP_MIRROR` environment variable and defaults to the GitHub API.
+///
+/// A mirror must serve release information at the same paths and in the same format as the
+/// GitHub releases API, e.g. `<mirror>/repos/risc0/rust/releases/latest`. Artifacts are
+/// downloaded from the URLs listed in the release information, so they can be hosted on the
+/// mirror as well.
+pub fn api_base_url() -> String {
+    let flag = MIRROR.lock().unwrap().clone();
+    base_url(flag, env::var("RZUP_MIRROR").ok())
+}
+
+/// Picks the base URL from the `--mirror` flag and the `RZUP_MIRROR` environment variable, in
+/// that order of precedence.
+fn base_url(flag: Option<String>, env: Option<String>) -> String {
+    let mirror = flag.or(env).filter(|mirror| !mirror.trim().is_empty());
+    let url = mirror.as_deref().unwrap_or(GITHUB_API_URL);
+    url.trim_end_matches('/').to_string()
+}
+
 /// Determines the home directory for risc0, which can be set via the
 /// `RISC0_HOME` environment variable or defaults to `.risc0` in the home directory.
 pub fn rzup_home() -> Result<PathBuf, RzupError> {
@@ -100,6 +146,64 @@ pub fn flock(path: &Path) -> Result<FileLock, RzupError> {
     Ok(FileLock(file))
 }
 
+/// Finds the toolchain versions pinned by the `risc0-toolchain.toml` of the project in the current
+/// directory, if there is one.
+pub fn find_pinned_toolchain() -> Result<PinnedToolchain> {
+    let Some((path, file)) = ToolchainFile::discover(env::current_dir()?)? else {
+        return Ok(PinnedToolchain::default());
+    };
+    info_msg!(format!(
+        "Using toolchain versions pinned in {}",
+        path.display()
+    ));
+    Ok(file.toolchain)
+}
+
+/// Prepares the directory to install a toolchain or extension into, removing it first if `force`
+/// is set. Returns true if the directory already exists and should be used as is.
+pub fn prepare_install_dir(dir: &Path, kind: &str, force: bool) -> Result<bool> {
+    // Remove directory if it exists and force is set
+    if dir.is_dir() && force {
+        info_msg!(format!(
+            "{kind} path {} already exists - deleting existing files!",
+            dir.display()
+        ));
+
+        verbose_msg!(format!("Removing directory at {}", dir.display()));
+
+        fs::remove_dir_all(dir)?;
+    }
+
+    // Skip installation if directory already exists and force is not set
+    if dir.is_dir() && !force {
+        info_msg!(format!(
+            "{kind} path {} already exists - skipping installation.",
+            dir.display()
+        ));
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Recursively copies the contents of the `src` directory into `dst`, preserving permissions.
+pub fn copy_dir(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+        if src_path.is_dir() {
+            copy_dir(&src_path, &dst_path)?;
+        } else {
+            fs::copy(&src_path, &dst_path)?;
+            let permissions = src_path.metadata()?.permissions();
+            fs::set_permissions(&dst_path, permissions)?;
+        }
+    }
+    Ok(())
+}
+
 pub const RUSTUP_TOOLCHAIN_NAME: &str = "risc0";
 pub const CPP_TOOLCHAIN_NAME: &str = "cpp";
 
@@ -474,3 +578,20 @@ pub fn http_client() -> Result<Client, RzupError> {
 
     Ok(client)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_override() {
+        let env = Some("https://env.example.com/".to_string());
+        let flag = Some("https://flag.example.com/api/".to_string());
+        assert_eq!(base_url(None, None), GITHUB_API_URL);
+        assert_eq!(base_url(None, Some(" ".to_string())), GITHUB_API_URL);
+        assert_eq!(base_url(None, env.clone()), "https://env.example.com");
+
+        // The flag takes precedence over the environment variable.
+        assert_eq!(base_url(flag, env), "https://flag.example.com/api");
+    }
+}
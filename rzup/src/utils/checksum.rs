@@ -0,0 +1,268 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    errors::{RzupError, Unverifiable},
+    info_msg,
+    repo::{GithubReleaseInfo, MANIFEST_NAMES},
+    utils::http_client,
+    verbose_msg,
+};
+
+/// A manifest of the SHA-256 digests of release artifacts, in the format written by `sha256sum`.
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    digests: HashMap<String, String>,
+}
+
+impl Manifest {
+    /// Parses a manifest with one `<sha256>  <file name>` entry per line.
+    pub fn parse(contents: &str) -> Result<Self, RzupError> {
+        let mut digests = HashMap::new();
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            let (digest, name) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                RzupError::parse_error(format!("Invalid line in SHA-256 manifest: {line}"))
+            })?;
+            if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(RzupError::parse_error(format!(
+                    "Invalid SHA-256 digest in manifest: {digest}"
+                )));
+            }
+            // `sha256sum` marks files read in binary mode with a leading `*`, and entries may
+            // include a directory.
+            let name = name.trim_start().trim_start_matches('*');
+            let name = name.rsplit('/').next().unwrap_or(name);
+            digests.insert(name.to_string(), digest.to_lowercase());
+        }
+        Ok(Self { digests })
+    }
+
+    /// Loads a manifest from a file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read SHA-256 manifest {}", path.display()))?;
+        Ok(Self::parse(&contents)?)
+    }
+
+    /// Downloads the manifest published with a release, if the release has one.
+    pub async fn for_release(release_info: &GithubReleaseInfo) -> Result<Option<Self>> {
+        let Some(asset) = &release_info.manifest else {
+            return Ok(None);
+        };
+
+        verbose_msg!(format!(
+            "Downloading SHA-256 manifest from {}",
+            &asset.browser_download_url
+        ));
+        let response = http_client()?
+            .get(&asset.browser_download_url)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(Some(Self::parse(&response.text().await?)?))
+    }
+
+    /// Returns the file name of a local artifact, along with the manifest to verify it against:
+    /// the given manifest, or else a manifest in the same directory as the artifact.
+    pub fn for_local_artifact(
+        path: &Path,
+        manifest: Option<&Manifest>,
+    ) -> Result<(String, Option<Self>)> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("Invalid artifact path {}", path.display()))?
+            .to_string_lossy()
+            .to_string();
+        if let Some(manifest) = manifest {
+            return Ok((name, Some(manifest.clone())));
+        }
+
+        let dir = path.parent().unwrap_or(Path::new("."));
+        for manifest_name in MANIFEST_NAMES {
+            let manifest_path = dir.join(manifest_name);
+            if manifest_path.is_file() {
+                verbose_msg!(format!(
+                    "Using SHA-256 manifest at {}",
+                    manifest_path.display()
+                ));
+                return Ok((name, Some(Self::load(&manifest_path)?)));
+            }
+        }
+        Ok((name, None))
+    }
+
+    /// Checks that the SHA-256 digest of the file at `path` matches the entry for `name`.
+    pub fn verify(&self, name: &str, path: &Path) -> Result<(), RzupError> {
+        let expected = self
+            .digests
+            .get(name)
+            .ok_or_else(|| RzupError::ChecksumMissing(name.to_string()))?;
+        let actual = sha256_file(path)?;
+        if &actual != expected {
+            return Err(RzupError::ChecksumMismatch {
+                name: name.to_string(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+        verbose_msg!(format!("Verified SHA-256 checksum of {name}"));
+        Ok(())
+    }
+}
+
+/// Verifies an artifact against the given manifest. Without a manifest, the artifact is rejected
+/// for the given reason unless unverified artifacts are allowed.
+pub fn verify_artifact(
+    manifest: Option<&Manifest>,
+    name: &str,
+    path: &Path,
+    reason: Unverifiable,
+    allow_unverified: bool,
+) -> Result<(), RzupError> {
+    match manifest {
+        Some(manifest) => manifest.verify(name, path),
+        None => check_unverified(name, reason, allow_unverified),
+    }
+}
+
+/// Rejects an artifact that cannot be verified, such as an unpacked directory, unless unverified
+/// artifacts are allowed.
+pub fn check_unverified(
+    name: &str,
+    reason: Unverifiable,
+    allow_unverified: bool,
+) -> Result<(), RzupError> {
+    if !allow_unverified {
+        return Err(RzupError::Unverified {
+            name: name.to_string(),
+            reason,
+        });
+    }
+    info_msg!(format!(
+        "No SHA-256 manifest available for {name} - installing it unverified."
+    ));
+    Ok(())
+}
+
+/// Computes the hex-encoded SHA-256 digest of a file.
+pub fn sha256_file(path: &Path) -> Result<String, RzupError> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn parse() {
+        let manifest = Manifest::parse(&format!(
+            "{HELLO_SHA256}  hello.tar.gz\n\n{}  *dist/world.tar.xz\n",
+            HELLO_SHA256.to_uppercase()
+        ))
+        .unwrap();
+        assert_eq!(manifest.digests["hello.tar.gz"], HELLO_SHA256);
+        assert_eq!(manifest.digests["world.tar.xz"], HELLO_SHA256);
+
+        for malformed in [
+            HELLO_SHA256,
+            "deadbeef  hello.tar.gz",
+            &format!("{}  hello.tar.gz", &HELLO_SHA256[1..]),
+            &format!("{}g  hello.tar.gz", &HELLO_SHA256[1..]),
+        ] {
+            assert!(
+                matches!(Manifest::parse(malformed), Err(RzupError::ParseError(_))),
+                "{malformed}"
+            );
+        }
+    }
+
+    #[test]
+    fn verify() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hello.tar.gz");
+        fs::write(&path, "hello").unwrap();
+
+        let manifest = Manifest::parse(&format!(
+            "{HELLO_SHA256}  hello.tar.gz\n{}  other.tar.gz\n",
+            "0".repeat(64)
+        ))
+        .unwrap();
+        manifest.verify("hello.tar.gz", &path).unwrap();
+        assert!(matches!(
+            manifest.verify("other.tar.gz", &path),
+            Err(RzupError::ChecksumMismatch { actual, .. }) if actual == HELLO_SHA256
+        ));
+        assert!(matches!(
+            manifest.verify("missing.tar.gz", &path),
+            Err(RzupError::ChecksumMissing(_))
+        ));
+    }
+
+    #[test]
+    fn verify_without_manifest() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hello.tar.gz");
+        fs::write(&path, "hello").unwrap();
+
+        let err =
+            verify_artifact(None, "hello.tar.gz", &path, Unverifiable::Release, false).unwrap_err();
+        assert!(matches!(
+            err,
+            RzupError::Unverified {
+                reason: Unverifiable::Release,
+                ..
+            }
+        ));
+        // The error says why the artifact cannot be verified and which flags to pass.
+        let message = err.to_string();
+        assert!(
+            message.contains("does not publish a sha256sums.txt"),
+            "{message}"
+        );
+        assert!(message.contains("--manifest <file>"), "{message}");
+        assert!(message.contains("--allow-unverified"), "{message}");
+        verify_artifact(None, "hello.tar.gz", &path, Unverifiable::Release, true).unwrap();
+
+        // A manifest is checked even if unverified artifacts are allowed.
+        let manifest = Manifest::parse(&format!("{}  hello.tar.gz", "0".repeat(64))).unwrap();
+        assert!(verify_artifact(
+            Some(&manifest),
+            "hello.tar.gz",
+            &path,
+            Unverifiable::Release,
+            true
+        )
+        .is_err());
+    }
+}
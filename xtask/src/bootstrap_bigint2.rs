@@ -0,0 +1,333 @@
+// Copyright 2024 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generates the modular arithmetic programs used by the `field` module of risc0-bigint2.
+//!
+//! Each program is a blob made of a header, a nondeterministic program that computes the result
+//! and a witness on the host, and a verify program that the guest runs to check a polynomial
+//! identity over the inputs, the result and the witness:
+//!
+//! * the header is four u32s: the length of the nondeterministic program in words, the number of
+//!   verify instructions, the number of constant words, and the bytes of temporary witness space;
+//! * the nondeterministic program is `bibc` bytecode: a list of value types, constants and
+//!   operations, where loads and stores address 16 byte chunks relative to a register;
+//! * the verify program is a list of u32 instructions, followed by the constant words.
+//!
+//! Every identity is checked in 16 byte chunks, so a value of `bits` bits takes `bits / 128`
+//! chunks.
+
+use std::fs;
+
+use clap::Parser;
+
+const FIELD_DIR: &str = "risc0/bigint2/src/field";
+
+/// The widths, in bits, of the generated programs.
+const WIDTHS: [usize; 3] = [256, 384, 4096];
+
+// Registers addressed by the programs.
+const REG_SP: u64 = 2;
+const REG_A1: u64 = 11;
+const REG_A2: u64 = 12;
+const REG_A3: u64 = 13;
+const REG_A4: u64 = 14;
+// Holds the address of the constant words.
+const REG_T3: u64 = 28;
+
+// Opcodes of the nondeterministic program.
+const OP_LOAD: u64 = 0x3;
+const OP_STORE: u64 = 0x4;
+const OP_ADD: u64 = 0x8;
+const OP_SUB: u64 = 0x9;
+const OP_MUL: u64 = 0xA;
+const OP_REM: u64 = 0xB;
+const OP_QUO: u64 = 0xC;
+const OP_INV: u64 = 0xE;
+
+// Memory operations of the verify program.
+const MEM_READ: u32 = 0;
+const MEM_WRITE: u32 = 1;
+const MEM_NOP: u32 = 2;
+
+// Polynomial operations of the verify program.
+const POLY_SHIFT: u32 = 1;
+const POLY_SET_TERM: u32 = 2;
+const POLY_ADD_TOTAL: u32 = 3;
+const POLY_CARRY_1: u32 = 4;
+const POLY_CARRY_2: u32 = 5;
+const POLY_EQ_ZERO: u32 = 6;
+
+/// The instruction that ends the verify program.
+const VERIFY_END: u32 = 0x20000000;
+
+/// The maximum value of a byte, the coefficient bound of an input.
+const BYTE_MAX: u64 = 255;
+
+#[derive(Parser)]
+pub struct BootstrapBigint2;
+
+impl BootstrapBigint2 {
+    pub fn run(&self) {
+        let programs: [(&str, fn(usize) -> Vec<u8>); 4] = [
+            ("modadd", modadd),
+            ("modsub", modsub),
+            ("modmul", modmul),
+            ("modinv", modinv),
+        ];
+        for bits in WIDTHS {
+            for (name, program) in programs {
+                let path = format!("{FIELD_DIR}/{name}_{bits}.blob");
+                fs::write(&path, program(bits)).unwrap();
+                println!("Wrote {path}");
+            }
+        }
+    }
+}
+
+/// A nondeterministic program in `bibc` bytecode.
+#[derive(Default)]
+struct Program {
+    // (coefficients, max positive coefficient, max negative coefficient, min bits)
+    types: Vec<(u64, u64, u64, u64)>,
+    ops: Vec<u64>,
+}
+
+impl Program {
+    /// Returns the index of the type of a value with the given number of byte coefficients and
+    /// bounds.
+    fn ty(&mut self, coeffs: u64, max_pos: u64, max_neg: u64) -> u64 {
+        let ty = (coeffs, max_pos, max_neg, 0);
+        let idx = self.types.iter().position(|t| *t == ty).unwrap_or_else(|| {
+            self.types.push(ty);
+            self.types.len() - 1
+        });
+        idx as u64
+    }
+
+    /// Appends an operation, returning the index of the value it produces.
+    fn op(&mut self, code: u64, ty: u64, a: u64, b: u64) -> u64 {
+        self.ops.push(code | ty << 4 | a << 16 | b << 40);
+        self.ops.len() as u64 - 1
+    }
+
+    fn load(&mut self, ty: u64, reg: u64) -> u64 {
+        self.op(OP_LOAD, ty, reg << 16, 0)
+    }
+
+    fn store(&mut self, ty: u64, reg: u64, value: u64) {
+        self.op(OP_STORE, ty, reg << 16, value);
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = b"bibc".to_vec();
+        // The version, followed by the number of inputs, which these programs don't declare.
+        for word in [1, 0, self.types.len(), 0, self.ops.len()] {
+            out.extend_from_slice(&(word as u32).to_le_bytes());
+        }
+        for &(coeffs, max_pos, max_neg, min_bits) in self.types.iter() {
+            for word in [coeffs, max_pos, max_neg, min_bits] {
+                out.extend_from_slice(&word.to_le_bytes());
+            }
+        }
+        for op in self.ops.iter() {
+            out.extend_from_slice(&op.to_le_bytes());
+        }
+        out
+    }
+}
+
+/// A value in memory, as used in a verify program.
+#[derive(Clone, Copy)]
+struct Value {
+    reg: u64,
+    chunks: u64,
+    /// True if the value is a result or witness written by the nondeterministic program, rather
+    /// than an input.
+    witness: bool,
+}
+
+const fn input(reg: u64, chunks: u64) -> Value {
+    Value {
+        reg,
+        chunks,
+        witness: false,
+    }
+}
+
+const fn witness(reg: u64, chunks: u64) -> Value {
+    Value {
+        reg,
+        chunks,
+        witness: true,
+    }
+}
+
+fn insn(mem: u32, poly: u32, coeff: i32, reg: u64, offset: u64) -> u32 {
+    mem << 28 | poly << 24 | ((coeff + 4) as u32) << 21 | (reg as u32) << 16 | offset as u32
+}
+
+/// Builds a verify program checking that the sum of the given terms, each a coefficient times a
+/// product of values, is zero.
+fn verify(terms: &[(i32, &[Value])]) -> Vec<u32> {
+    let mut out = vec![];
+    let mut written = vec![];
+    let mut carries = 0;
+    for &(coeff, factors) in terms {
+        carries = carries.max(factors.iter().map(|f| f.chunks).sum());
+        for (i, factor) in factors.iter().enumerate() {
+            // Witness values are written to memory on their first use, and read afterwards.
+            let mut mem = MEM_READ;
+            if factor.witness && !written.contains(&factor.reg) {
+                mem = MEM_WRITE;
+                written.push(factor.reg);
+            }
+            let last = i == factors.len() - 1;
+            for k in (0..factor.chunks).rev() {
+                let poly = match k {
+                    0 if last => POLY_ADD_TOTAL,
+                    0 => POLY_SET_TERM,
+                    _ => POLY_SHIFT,
+                };
+                let coeff = if k == 0 { coeff } else { 0 };
+                out.push(insn(mem, poly, coeff, factor.reg, k));
+            }
+        }
+    }
+    for k in (1..carries).rev() {
+        out.push(insn(MEM_NOP, POLY_CARRY_1, -4, 0, k));
+        out.push(insn(MEM_NOP, POLY_CARRY_2, -4, 0, k));
+        out.push(insn(MEM_NOP, POLY_SHIFT, -4, 0, k));
+    }
+    out.push(insn(MEM_NOP, POLY_CARRY_1, -4, 0, 0));
+    out.push(insn(MEM_NOP, POLY_CARRY_2, -4, 0, 0));
+    out.push(insn(MEM_NOP, POLY_EQ_ZERO, -4, 0, 0));
+    out.push(VERIFY_END);
+    out
+}
+
+fn blob(program: &Program, verify: &[u32], consts: &[u32], temp_chunks: u64) -> Vec<u8> {
+    let nondet = program.encode();
+    let header = [
+        nondet.len() as u32 / 4,
+        verify.len() as u32,
+        consts.len() as u32,
+        temp_chunks as u32 * 4,
+    ];
+    let mut out = vec![];
+    for word in header {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out.extend_from_slice(&nondet);
+    for word in verify.iter().chain(consts) {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+/// `a4 = a1 * a2 mod a3`, checked as `a4 + q * a3 - a1 * a2 = 0`.
+fn modmul(bits: usize) -> Vec<u8> {
+    let c = (bits / 128) as u64;
+    let mut p = Program::default();
+    let t_in = p.ty(16 * c, BYTE_MAX, 0);
+    let t_prod = p.ty(32 * c - 1, 16 * c * BYTE_MAX * BYTE_MAX, 0);
+    let t_quo = p.ty(32 * c, BYTE_MAX, 0);
+    let a = p.load(t_in, REG_A1);
+    let b = p.load(t_in, REG_A2);
+    let n = p.load(t_in, REG_A3);
+    let prod = p.op(OP_MUL, t_prod, a, b);
+    let q = p.op(OP_QUO, t_quo, prod, n);
+    let r = p.op(OP_REM, t_in, prod, n);
+    p.store(t_in, REG_A4, r);
+    p.store(t_quo, REG_SP, q);
+    let verify = verify(&[
+        (1, &[witness(REG_A4, c)]),
+        (1, &[witness(REG_SP, 2 * c), input(REG_A3, c)]),
+        (-1, &[input(REG_A1, c), input(REG_A2, c)]),
+    ]);
+    blob(&p, &verify, &[], 2 * c)
+}
+
+/// `a4 = a1 + a2 mod a3`, checked as `a4 + q * a3 - a1 - a2 = 0`.
+fn modadd(bits: usize) -> Vec<u8> {
+    let c = (bits / 128) as u64;
+    let mut p = Program::default();
+    let t_in = p.ty(16 * c, BYTE_MAX, 0);
+    let t_sum = p.ty(16 * c, 2 * BYTE_MAX, 0);
+    let t_quo = p.ty(16, BYTE_MAX, 0);
+    let a = p.load(t_in, REG_A1);
+    let b = p.load(t_in, REG_A2);
+    let n = p.load(t_in, REG_A3);
+    let sum = p.op(OP_ADD, t_sum, a, b);
+    let q = p.op(OP_QUO, t_quo, sum, n);
+    let r = p.op(OP_REM, t_in, sum, n);
+    p.store(t_in, REG_A4, r);
+    p.store(t_quo, REG_SP, q);
+    let verify = verify(&[
+        (1, &[witness(REG_A4, c)]),
+        (1, &[witness(REG_SP, 1), input(REG_A3, c)]),
+        (-1, &[input(REG_A1, c)]),
+        (-1, &[input(REG_A2, c)]),
+    ]);
+    blob(&p, &verify, &[], 1)
+}
+
+/// `a4 = a1 - a2 mod a3`, computed as `a1 - a2 + a3` to keep the value positive, and checked as
+/// `a4 + q * a3 - a1 + a2 - a3 = 0`.
+fn modsub(bits: usize) -> Vec<u8> {
+    let c = (bits / 128) as u64;
+    let mut p = Program::default();
+    let t_in = p.ty(16 * c, BYTE_MAX, 0);
+    let t_diff = p.ty(16 * c, BYTE_MAX, BYTE_MAX);
+    let t_sum = p.ty(16 * c, 2 * BYTE_MAX, BYTE_MAX);
+    let t_quo = p.ty(16, BYTE_MAX, 0);
+    let a = p.load(t_in, REG_A1);
+    let b = p.load(t_in, REG_A2);
+    let n = p.load(t_in, REG_A3);
+    let diff = p.op(OP_SUB, t_diff, a, b);
+    let sum = p.op(OP_ADD, t_sum, diff, n);
+    let q = p.op(OP_QUO, t_quo, sum, n);
+    let r = p.op(OP_REM, t_in, sum, n);
+    p.store(t_in, REG_A4, r);
+    p.store(t_quo, REG_SP, q);
+    let verify = verify(&[
+        (1, &[witness(REG_A4, c)]),
+        (1, &[witness(REG_SP, 1), input(REG_A3, c)]),
+        (-1, &[input(REG_A1, c)]),
+        (1, &[input(REG_A2, c)]),
+        (-1, &[input(REG_A3, c)]),
+    ]);
+    blob(&p, &verify, &[], 1)
+}
+
+/// `a3 = a1^-1 mod a2`, checked as `a1 * a3 - q * a2 - 1 = 0`, where the constant 1 is read
+/// through `t3`.
+fn modinv(bits: usize) -> Vec<u8> {
+    let c = (bits / 128) as u64;
+    let mut p = Program::default();
+    let t_in = p.ty(16 * c, BYTE_MAX, 0);
+    let t_prod = p.ty(32 * c - 1, 16 * c * BYTE_MAX * BYTE_MAX, 0);
+    let a = p.load(t_in, REG_A1);
+    let n = p.load(t_in, REG_A2);
+    let r = p.op(OP_INV, t_in, a, n);
+    let prod = p.op(OP_MUL, t_prod, a, r);
+    let q = p.op(OP_QUO, t_in, prod, n);
+    p.store(t_in, REG_A3, r);
+    p.store(t_in, REG_SP, q);
+    let verify = verify(&[
+        (1, &[input(REG_A1, c), witness(REG_A3, c)]),
+        (-1, &[witness(REG_SP, c), input(REG_A2, c)]),
+        (-1, &[input(REG_T3, 1)]),
+    ]);
+    blob(&p, &verify, &[1, 0, 0, 0], c)
+}
@@ -14,6 +14,7 @@
 
 #[cfg(feature = "zkvm")]
 mod bootstrap;
+mod bootstrap_bigint2;
 #[cfg(feature = "zkvm")]
 mod bootstrap_groth16;
 mod bootstrap_poseidon;
@@ -27,7 +28,8 @@ use clap::{Parser, Subcommand};
 #[cfg(feature = "zkvm")]
 use self::{bootstrap::Bootstrap, bootstrap_groth16::BootstrapGroth16, gen_receipt::GenReceipt};
 use self::{
-    bootstrap_poseidon::BootstrapPoseidon, bootstrap_protos::BootstrapProtos, install::Install,
+    bootstrap_bigint2::BootstrapBigint2, bootstrap_poseidon::BootstrapPoseidon,
+    bootstrap_protos::BootstrapProtos, install::Install,
 };
 
 #[derive(Parser)]
@@ -40,6 +42,7 @@ struct Cli {
 enum Commands {
     #[cfg(feature = "zkvm")]
     Bootstrap(Bootstrap),
+    BootstrapBigint2(BootstrapBigint2),
     #[cfg(feature = "zkvm")]
     BootstrapGroth16(BootstrapGroth16),
     BootstrapPoseidon(BootstrapPoseidon),
@@ -54,6 +57,7 @@ impl Commands {
         match self {
             #[cfg(feature = "zkvm")]
             Commands::Bootstrap(cmd) => cmd.run(),
+            Commands::BootstrapBigint2(cmd) => cmd.run(),
             #[cfg(feature = "zkvm")]
             Commands::BootstrapGroth16(cmd) => cmd.run(),
             Commands::BootstrapPoseidon(cmd) => cmd.run(),